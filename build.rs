@@ -1,6 +1,18 @@
+use std::env;
+
 fn main() {
+    // `zmq-sys` reports whether libzmq was built with the draft API.
+    if env::var_os("CARGO_FEATURE_DRAFT_API").is_some()
+        && env::var("DEP_ZMQ_DRAFT").as_deref() != Ok("1")
+    {
+        panic!(
+            "the `draft-api` feature requires libzmq to be built with its draft API \
+             (`ZMQ_BUILD_DRAFT_API`), but `zmq-sys` does not report it as such"
+        );
+    }
+
     println!(
         "cargo:rustc-env=BUILD_PROFILE={}",
-        std::env::var("PROFILE").unwrap()
+        env::var("PROFILE").unwrap()
     );
 }
@@ -26,7 +26,7 @@ fn server(pull_socket: &zmq::Socket, push_socket: &zmq::Socket, mut workers: u64
         }
     }
 
-    push_socket.send(&count.to_string(), 0).unwrap();
+    push_socket.send(count.to_string(), 0).unwrap();
 }
 
 fn spawn_server(ctx: &mut zmq::Context, workers: u64) -> Sender<()> {
@@ -58,7 +58,7 @@ fn spawn_server(ctx: &mut zmq::Context, workers: u64) -> Sender<()> {
 
 fn worker(push_socket: &zmq::Socket, count: u64) {
     for _ in 0..count {
-        push_socket.send(&100.to_string(), 0).unwrap();
+        push_socket.send(100.to_string(), 0).unwrap();
     }
 
     // Let the server know we're done.
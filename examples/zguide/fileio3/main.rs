@@ -48,7 +48,7 @@ fn client_thread(expected_total: usize) {
         while (credit > 0) && !clean_break {
             // Ask for next chunk
             dealer.send("fetch", SNDMORE).unwrap();
-            dealer.send(&offset.to_string(), SNDMORE).unwrap();
+            dealer.send(offset.to_string(), SNDMORE).unwrap();
             dealer.send(CHUNK_SIZE_STR, 0).unwrap();
             offset += CHUNK_SIZE;
             credit -= 1;
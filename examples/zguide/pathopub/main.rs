@@ -26,7 +26,7 @@ fn main() {
     // Send out all 1,000 topic messages
     for topic_nbr in 0..1000 {
         publisher
-            .send(&format!("{:03}", topic_nbr), zmq::SNDMORE)
+            .send(format!("{:03}", topic_nbr), zmq::SNDMORE)
             .unwrap();
         publisher.send("Save Roger", 0).unwrap();
     }
@@ -36,10 +36,7 @@ fn main() {
     loop {
         sleep(Duration::from_millis(1000));
         publisher
-            .send(
-                &format!("{:03}", topic_range.sample(&mut rng)),
-                zmq::SNDMORE,
-            )
+            .send(format!("{:03}", topic_range.sample(&mut rng)), zmq::SNDMORE)
             .unwrap();
         publisher.send("Off with his head!", 0).unwrap();
     }
@@ -0,0 +1,371 @@
+//! Asynchronous sockets, independent of any particular runtime.
+//!
+//! An `AsyncSocket` wraps a `Socket`, and instead of blocking the thread,
+//! waits for it to become readable or writable by watching its `ZMQ_FD`.
+//! Watching the file descriptor is delegated to an `FdWaker`, which
+//! connects it to a runtime's reactor, or to a hand-written event loop. With
+//! the `tokio` feature, `TokioWaker` does so for the `tokio` runtime. This
+//! module is only available on Unix.
+//!
+//! With the `futures` feature, `AsyncSocket` also implements `Stream`,
+//! yielding multipart messages, and `Sink`, accepting them, so that sockets
+//! can be combined with the adapters of the `futures` crate, e.g. to
+//! forward every message received on a `PULL` socket to a `PUSH` socket.
+
+#[cfg(feature = "futures")]
+use std::collections::VecDeque;
+use std::future::poll_fn;
+use std::io;
+#[cfg(feature = "tokio")]
+use std::os::unix::io::AsRawFd;
+use std::os::unix::io::RawFd;
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+#[cfg(feature = "futures")]
+use futures_sink::Sink;
+use libc::c_int;
+#[cfg(feature = "tokio")]
+use tokio::io::unix::AsyncFd;
+#[cfg(feature = "tokio")]
+use tokio::io::Interest;
+
+use crate::message::msg_ptr;
+use crate::{Error, Message, PollEvents, Result, Socket, DONTWAIT, POLLIN, POLLOUT, SNDMORE};
+
+/// Notifies a task when a socket's `ZMQ_FD` becomes readable.
+///
+/// The file descriptor does not signal whether the socket is readable or
+/// writable, only that `get_events` may have changed, so it is only ever
+/// watched for readability. It is edge-triggered, and reset whenever
+/// `get_events` is called or a message is sent or received; `AsyncSocket`
+/// checks `get_events` before every wait.
+///
+/// An implementation for a runtime typically wraps the file descriptor in
+/// the runtime's type for registering file descriptors with its reactor.
+/// Dropping the waker must deregister the file descriptor, which the
+/// `AsyncSocket` does before closing the socket.
+pub trait FdWaker {
+    /// Return `Poll::Ready` if the file descriptor became readable since the
+    /// last call that did so. Otherwise, arrange for the task to be woken
+    /// by `cx` when it does, and return `Poll::Pending`.
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>>;
+}
+
+/// Watches a socket's `ZMQ_FD` with the reactor of the current `tokio`
+/// runtime.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::asynchronous::AsyncSocket;
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread()
+///     .enable_io()
+///     .build()
+///     .unwrap();
+/// runtime.block_on(async {
+///     let ctx = zmq::Context::new();
+///     let server = ctx.socket(zmq::REP).unwrap();
+///     server.bind("inproc://async-example").unwrap();
+///     let client = ctx.socket(zmq::REQ).unwrap();
+///     client.connect("inproc://async-example").unwrap();
+///
+///     let mut server = AsyncSocket::new(server).unwrap();
+///     let mut client = AsyncSocket::new(client).unwrap();
+///     client.send("ping", 0).await.unwrap();
+///     assert_eq!(server.recv_bytes().await.unwrap(), b"ping");
+///     server.send("pong", 0).await.unwrap();
+///     assert_eq!(client.recv_bytes().await.unwrap(), b"pong");
+/// });
+/// ```
+#[cfg(feature = "tokio")]
+pub struct TokioWaker(AsyncFd<ZmqFd>);
+
+#[cfg(feature = "tokio")]
+struct ZmqFd(RawFd);
+
+#[cfg(feature = "tokio")]
+impl AsRawFd for ZmqFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl TokioWaker {
+    /// Register a `ZMQ_FD` with the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a runtime with IO enabled.
+    pub fn new(fd: RawFd) -> io::Result<TokioWaker> {
+        AsyncFd::with_interest(ZmqFd(fd), Interest::READABLE).map(TokioWaker)
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FdWaker for TokioWaker {
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut guard = ready!(self.0.poll_read_ready(cx))?;
+        guard.clear_ready();
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// A socket whose operations wait asynchronously, see the module
+/// documentation.
+///
+/// The operations take `&mut self`, so that their futures can be sent to
+/// other threads, even though a `Socket` cannot be shared between them.
+pub struct AsyncSocket<W> {
+    // Declared before the socket, so it is dropped before the socket and its
+    // file descriptor are closed.
+    waker: W,
+    socket: Socket,
+    // The parts of the message given to `Sink::start_send`, which have not
+    // been sent yet.
+    #[cfg(feature = "futures")]
+    pending: VecDeque<Message>,
+    // Whether part of the message being sent by the sink has been queued.
+    #[cfg(feature = "futures")]
+    sent_more: bool,
+    // Whether the sink failed in the middle of a message.
+    #[cfg(feature = "futures")]
+    failed: bool,
+}
+
+#[cfg(feature = "tokio")]
+impl AsyncSocket<TokioWaker> {
+    /// Wrap a socket, registering its `ZMQ_FD` with the current `tokio`
+    /// runtime, see `TokioWaker`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a runtime with IO enabled.
+    pub fn new(socket: Socket) -> io::Result<AsyncSocket<TokioWaker>> {
+        AsyncSocket::with_waker(socket, TokioWaker::new)
+    }
+}
+
+impl<W: FdWaker> AsyncSocket<W> {
+    /// Wrap a socket, watching its `ZMQ_FD` with the waker returned by
+    /// `make_waker`.
+    pub fn with_waker<F>(socket: Socket, make_waker: F) -> io::Result<AsyncSocket<W>>
+    where
+        F: FnOnce(RawFd) -> io::Result<W>,
+    {
+        let waker = make_waker(socket.get_fd()?)?;
+        Ok(AsyncSocket {
+            waker,
+            socket,
+            #[cfg(feature = "futures")]
+            pending: VecDeque::new(),
+            #[cfg(feature = "futures")]
+            sent_more: false,
+            #[cfg(feature = "futures")]
+            failed: false,
+        })
+    }
+
+    /// Return a reference to the underlying socket, e.g. to bind, connect or
+    /// set options.
+    pub fn get_ref(&self) -> &Socket {
+        &self.socket
+    }
+
+    /// Drop the waker, deregistering the socket, and return it.
+    pub fn into_inner(self) -> Socket {
+        let AsyncSocket { waker, socket, .. } = self;
+        drop(waker);
+        socket
+    }
+
+    /// Receive a message.
+    pub async fn recv_msg(&mut self) -> Result<Message> {
+        let this = &mut *self;
+        poll_fn(move |cx| this.poll_recv_msg(cx)).await
+    }
+
+    /// Receive a message into a byte vector.
+    pub async fn recv_bytes(&mut self) -> Result<Vec<u8>> {
+        self.recv_msg().await.map(|msg| msg.to_vec())
+    }
+
+    /// Receive a multipart message.
+    pub async fn recv_multipart(&mut self) -> Result<Vec<Vec<u8>>> {
+        let msg = self.recv_msg().await?;
+        let mut more = msg.get_more();
+        let mut parts = vec![msg.to_vec()];
+        // The remaining parts arrive along with the first one.
+        while more {
+            let msg = self.socket.recv_msg(0)?;
+            more = msg.get_more();
+            parts.push(msg.to_vec());
+        }
+        Ok(parts)
+    }
+
+    /// Send a message. `DONTWAIT` is implied, and only used internally.
+    pub async fn send<T>(&mut self, data: T, flags: i32) -> Result<()>
+    where
+        T: Into<Message>,
+    {
+        let mut msg = data.into();
+        let (this, msg) = (&mut *self, &mut msg);
+        poll_fn(move |cx| this.poll_send_msg(cx, msg, flags)).await
+    }
+
+    /// Send a multipart message, setting `SNDMORE` on all but the last
+    /// part, see `Socket::send_multipart`.
+    pub async fn send_multipart<I, T>(&mut self, iter: I, flags: i32) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Message>,
+    {
+        let mut iter = iter.into_iter().peekable();
+        while let Some(part) = iter.next() {
+            let more = if iter.peek().is_some() { SNDMORE } else { 0 };
+            self.send(part, flags | more).await?;
+        }
+        Ok(())
+    }
+
+    fn poll_recv_msg(&self, cx: &mut Context<'_>) -> Poll<Result<Message>> {
+        loop {
+            match self.socket.recv_msg(DONTWAIT) {
+                Err(Error::EAGAIN) => ready!(self.poll_events(cx, POLLIN))?,
+                result => return Poll::Ready(result),
+            }
+        }
+    }
+
+    fn poll_send_msg(
+        &self,
+        cx: &mut Context<'_>,
+        msg: &mut Message,
+        flags: i32,
+    ) -> Poll<Result<()>> {
+        loop {
+            // Unlike `Socket::send`, this leaves the message in place when
+            // it cannot be queued yet.
+            let flags = (flags | DONTWAIT) as c_int;
+            let rc = unsafe { zmq_sys::zmq_msg_send(msg_ptr(msg), self.socket.sock, flags) };
+            if rc != -1 {
+                return Poll::Ready(Ok(()));
+            }
+            match crate::errno_to_error() {
+                Error::EAGAIN => ready!(self.poll_events(cx, POLLOUT))?,
+                e => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    // Wait until `ZMQ_EVENTS` includes any of `events`. Since `ZMQ_FD` is
+    // edge-triggered, and reset by querying `ZMQ_EVENTS`, the events are
+    // checked again whenever the file descriptor signals.
+    fn poll_events(&self, cx: &mut Context<'_>, events: PollEvents) -> Poll<Result<()>> {
+        loop {
+            if self.socket.get_events()?.intersects(events) {
+                return Poll::Ready(Ok(()));
+            }
+            if ready!(self.waker.poll_readable(cx)).is_err() {
+                // The waker can no longer wait, e.g. since the runtime is
+                // shutting down.
+                return Poll::Ready(Err(Error::ETERM));
+            }
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    fn poll_recv_multipart(&self, cx: &mut Context<'_>) -> Poll<Result<Vec<Message>>> {
+        let msg = ready!(self.poll_recv_msg(cx))?;
+        let mut more = msg.get_more();
+        let mut parts = vec![msg];
+        while more {
+            let msg = self.socket.recv_msg(0)?;
+            more = msg.get_more();
+            parts.push(msg);
+        }
+        Poll::Ready(Ok(parts))
+    }
+
+    #[cfg(feature = "futures")]
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.failed {
+            return Poll::Ready(Err(Error::EFSM));
+        }
+        while let Some(mut msg) = self.pending.pop_front() {
+            let flags = if self.pending.is_empty() { 0 } else { SNDMORE };
+            match self.poll_send_msg(cx, &mut msg, flags) {
+                Poll::Ready(Ok(())) => self.sent_more = flags != 0,
+                Poll::Ready(Err(e)) => {
+                    self.pending.clear();
+                    // The parts already queued cannot be taken back, and
+                    // would be joined with the next message.
+                    self.failed = self.sent_more;
+                    return Poll::Ready(Err(e));
+                }
+                Poll::Pending => {
+                    self.pending.push_front(msg);
+                    return Poll::Pending;
+                }
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Receives multipart messages, e.g. on a `SUB` or `PULL` socket.
+///
+/// The stream never ends; errors are yielded as they occur.
+#[cfg(feature = "futures")]
+impl<W: FdWaker> Stream for AsyncSocket<W> {
+    type Item = Result<Vec<Message>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_recv_multipart(cx).map(Some)
+    }
+}
+
+/// Sends multipart messages, e.g. on a `PUB` or `PUSH` socket.
+///
+/// Each message is buffered by `start_send` until it has been queued on the
+/// socket, which happens as soon as the socket can accept it; this only
+/// holds a single message at a time. Flushing does not wait for messages to
+/// be transmitted to peers.
+///
+/// If sending a message fails after some of its parts were queued, which
+/// `libzmq` offers no way to take back, the sink fails permanently: all
+/// further operations fail with `Error::EFSM`, rather than appending the
+/// next message to the incomplete one. A message whose first part could not
+/// be sent is simply dropped along with its error.
+#[cfg(feature = "futures")]
+impl<W: FdWaker + Unpin> Sink<Vec<Message>> for AsyncSocket<W> {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, parts: Vec<Message>) -> Result<()> {
+        let this = self.get_mut();
+        if this.failed {
+            return Err(Error::EFSM);
+        }
+        debug_assert!(this.pending.is_empty());
+        this.pending.extend(parts);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        self.get_mut().poll_send_pending(cx)
+    }
+}
@@ -0,0 +1,275 @@
+//! A ZAP handler, authenticating the connections of a context's sockets.
+//!
+//! 0MQ delegates authentication to a handler bound to
+//! `inproc://zeromq.zap.01`, speaking the ZeroMQ Authentication Protocol
+//! (ZAP, RFC 27). `Authenticator` runs such a handler on its own thread,
+//! similar to CZMQ's `zauth`:
+//!
+//! - Peer addresses are checked against a deny list and, if it is not
+//!   empty, an allow list.
+//! - `NULL` connections are accepted if their address passes. Note that
+//!   `libzmq` only consults the handler for `NULL` connections to sockets
+//!   with a ZAP domain set, see `Socket::set_zap_domain`.
+//! - `PLAIN` connections must present one of the configured user names
+//!   and passwords.
+//! - `CURVE` connections must present one of the configured public keys,
+//!   unless any key is allowed via `allow_any_curve`.
+//! - `GSSAPI` connections are accepted, since the mechanism authenticates
+//!   the principal itself.
+//!
+//! The authenticated user id, i.e. the `PLAIN` user name, the Z85-encoded
+//! `CURVE` public key or the `GSSAPI` principal, is available as the
+//! `"User-Id"` property of received messages, see `Message::gets`.
+//!
+//! # Examples
+//!
+//! ```
+//! let ctx = zmq::Context::new();
+//! let auth = zmq::auth::Authenticator::new(&ctx).unwrap();
+//! auth.add_plain_user("admin", "secret");
+//!
+//! let server = ctx.socket(zmq::PULL).unwrap();
+//! server.set_plain_server(true).unwrap();
+//! server.bind("tcp://127.0.0.1:*").unwrap();
+//! let endpoint = server.get_last_endpoint().unwrap().unwrap();
+//!
+//! let client = ctx.socket(zmq::PUSH).unwrap();
+//! client.set_plain_username(Some("admin")).unwrap();
+//! client.set_plain_password(Some("secret")).unwrap();
+//! client.connect(&endpoint).unwrap();
+//!
+//! client.send("hello", 0).unwrap();
+//! let msg = server.recv_msg(0).unwrap();
+//! assert_eq!(msg.gets("User-Id"), Some("admin"));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::panic;
+use std::str;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use crate::{z85_encode, Context, Error, Result, Socket, PAIR, POLLIN, REP};
+
+/// The endpoint `libzmq` sends ZAP requests to.
+pub const ZAP_ENDPOINT: &str = "inproc://zeromq.zap.01";
+
+const ZAP_VERSION: &[u8] = b"1.0";
+
+#[derive(Default)]
+struct Policy {
+    allowed: HashSet<String>,
+    denied: HashSet<String>,
+    passwords: HashMap<String, String>,
+    curve_keys: HashSet<[u8; 32]>,
+    any_curve: bool,
+}
+
+// The status code, status text and user id of a ZAP reply.
+type Verdict = (&'static str, &'static str, String);
+
+impl Policy {
+    fn authenticate(&self, address: &str, mechanism: &[u8], credentials: &[Vec<u8>]) -> Verdict {
+        if self.denied.contains(address)
+            || (!self.allowed.is_empty() && !self.allowed.contains(address))
+        {
+            return ("400", "Address not allowed", String::new());
+        }
+        match (mechanism, credentials) {
+            (b"NULL", []) => ("200", "OK", String::new()),
+            (b"PLAIN", [username, password]) => {
+                let known = match (str::from_utf8(username), str::from_utf8(password)) {
+                    (Ok(username), Ok(password)) => {
+                        self.passwords.get(username).map(String::as_str) == Some(password)
+                    }
+                    _ => false,
+                };
+                if known {
+                    let username = String::from_utf8_lossy(username).into_owned();
+                    ("200", "OK", username)
+                } else {
+                    ("400", "Invalid user name or password", String::new())
+                }
+            }
+            (b"CURVE", [key]) if key.len() == 32 => {
+                let mut public_key = [0; 32];
+                public_key.copy_from_slice(key);
+                if self.any_curve || self.curve_keys.contains(&public_key) {
+                    ("200", "OK", z85_encode(key).unwrap())
+                } else {
+                    ("400", "Unknown public key", String::new())
+                }
+            }
+            (b"GSSAPI", [principal]) => {
+                let principal = String::from_utf8_lossy(principal).into_owned();
+                ("200", "OK", principal)
+            }
+            _ => ("500", "Unsupported request", String::new()),
+        }
+    }
+}
+
+/// A ZAP handler running on its own thread, see the module documentation.
+///
+/// The policy can be changed at any time, and applies to subsequent
+/// handshakes. Only one handler can be bound per context; creating a
+/// second one fails with `Error::EADDRINUSE`. Dropping the authenticator
+/// stops the handler, after which `libzmq` rejects all connections that
+/// need authentication.
+pub struct Authenticator {
+    policy: Arc<Mutex<Policy>>,
+    control: Socket,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl Authenticator {
+    /// Start handling ZAP requests for the sockets of `ctx`.
+    ///
+    /// Initially, all addresses and no credentials are accepted.
+    pub fn new(ctx: &Context) -> Result<Authenticator> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let handler = ctx.socket(REP)?;
+        handler.bind(ZAP_ENDPOINT)?;
+        let control = ctx.socket(PAIR)?;
+        let control_endpoint = format!(
+            "inproc://zmq-authenticator-{}",
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        control.bind(&control_endpoint)?;
+        let stop = ctx.socket(PAIR)?;
+        stop.connect(&control_endpoint)?;
+
+        let policy = Arc::new(Mutex::new(Policy::default()));
+        let thread_policy = Arc::clone(&policy);
+        let thread = thread::spawn(move || run(&handler, &stop, &thread_policy));
+        Ok(Authenticator {
+            policy,
+            control,
+            thread: Some(thread),
+        })
+    }
+
+    /// Add an IP address to the allow list. Once the list is not empty,
+    /// connections from other addresses are rejected.
+    pub fn allow(&self, address: &str) {
+        self.policy
+            .lock()
+            .unwrap()
+            .allowed
+            .insert(address.to_owned());
+    }
+
+    /// Reject connections from the given IP address.
+    pub fn deny(&self, address: &str) {
+        self.policy
+            .lock()
+            .unwrap()
+            .denied
+            .insert(address.to_owned());
+    }
+
+    /// Accept `PLAIN` connections presenting `username` and `password`,
+    /// replacing any previous password of the user.
+    pub fn add_plain_user(&self, username: &str, password: &str) {
+        self.policy
+            .lock()
+            .unwrap()
+            .passwords
+            .insert(username.to_owned(), password.to_owned());
+    }
+
+    /// Stop accepting `PLAIN` connections of `username`.
+    pub fn remove_plain_user(&self, username: &str) {
+        self.policy.lock().unwrap().passwords.remove(username);
+    }
+
+    /// Accept `CURVE` connections using the given public key.
+    pub fn allow_curve_key(&self, public_key: [u8; 32]) {
+        self.policy.lock().unwrap().curve_keys.insert(public_key);
+    }
+
+    /// Stop accepting `CURVE` connections using the given public key.
+    pub fn remove_curve_key(&self, public_key: &[u8; 32]) {
+        self.policy.lock().unwrap().curve_keys.remove(public_key);
+    }
+
+    /// Accept `CURVE` connections using any public key, which still
+    /// encrypts the connection, but does not authenticate the client.
+    pub fn allow_any_curve(&self, allow: bool) {
+        self.policy.lock().unwrap().any_curve = allow;
+    }
+
+    /// Stop the handler and wait for its thread, returning the error that
+    /// stopped it prematurely, if any.
+    pub fn stop(mut self) -> Result<()> {
+        self.shutdown()
+    }
+
+    fn shutdown(&mut self) -> Result<()> {
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        // Fails if the handler has already returned, which `join` reports.
+        let _ = self.control.send("TERMINATE", 0);
+        match thread.join() {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl Drop for Authenticator {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+fn run(handler: &Socket, control: &Socket, policy: &Mutex<Policy>) -> Result<()> {
+    loop {
+        let mut items = [handler.as_poll_item(POLLIN), control.as_poll_item(POLLIN)];
+        // A signal delivered to this thread must not end authentication.
+        match crate::poll(&mut items, -1) {
+            Err(Error::EINTR) => continue,
+            result => result?,
+        };
+        if items[1].is_readable() {
+            return Ok(());
+        }
+        if items[0].is_readable() {
+            let request = match handler.recv_multipart(0) {
+                Err(Error::EINTR) => continue,
+                result => result?,
+            };
+            handler.send_multipart(reply(&request, policy), 0)?;
+        }
+    }
+}
+
+// Build the reply to a ZAP request, whose frames are the version, request
+// id, domain, address, routing id, mechanism and the credentials.
+fn reply(request: &[Vec<u8>], policy: &Mutex<Policy>) -> Vec<Vec<u8>> {
+    let request_id = request.get(1).cloned().unwrap_or_default();
+    let (status_code, status_text, user_id) = match request {
+        [version, _, _, address, _, mechanism, credentials @ ..] if version == ZAP_VERSION => {
+            match str::from_utf8(address) {
+                Ok(address) => policy
+                    .lock()
+                    .unwrap()
+                    .authenticate(address, mechanism, credentials),
+                Err(_) => ("400", "Address not allowed", String::new()),
+            }
+        }
+        _ => ("500", "Invalid request", String::new()),
+    };
+    vec![
+        ZAP_VERSION.to_vec(),
+        request_id,
+        status_code.into(),
+        status_text.into(),
+        user_id.into_bytes(),
+        Vec::new(),
+    ]
+}
@@ -0,0 +1,88 @@
+//! Generate CURVE key pairs, like `libzmq`'s `curve_keygen` tool.
+//!
+//! ```text
+//! zmq-curve-keygen [--metadata NAME=VALUE]... [CERT_PATH]
+//! ```
+//!
+//! Without `CERT_PATH`, the Z85-encoded keys are printed. Otherwise, a
+//! public certificate is written to `CERT_PATH`, and a secret certificate to
+//! `CERT_PATH_secret`, in the ZPL format used by CZMQ's `zcert`. Existing
+//! files are never overwritten, and on Unix the secret certificate is only
+//! readable by its owner.
+
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage: zmq-curve-keygen [--metadata NAME=VALUE]... [CERT_PATH]";
+
+struct Args {
+    metadata: Vec<(String, String)>,
+    cert_path: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut metadata = Vec::new();
+    let mut cert_path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--metadata" => {
+                let entry = args.next().ok_or("--metadata requires a value")?;
+                let mut split = entry.splitn(2, '=');
+                match (split.next(), split.next()) {
+                    (Some(name), Some(value)) if !name.is_empty() => {
+                        metadata.push((name.to_string(), value.to_string()))
+                    }
+                    _ => return Err(format!("invalid metadata, expected NAME=VALUE: {}", entry)),
+                }
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if cert_path.is_none() && !arg.starts_with('-') => cert_path = Some(arg),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    Ok(Args {
+        metadata,
+        cert_path,
+    })
+}
+
+fn run(args: &Args) -> Result<(), String> {
+    if !zmq::has("curve") {
+        return Err("libzmq was built without CURVE support".to_string());
+    }
+    let mut cert = zmq::Certificate::new().map_err(|e| e.to_string())?;
+    let public_key = cert.public_key_z85();
+
+    let cert_path = match args.cert_path {
+        Some(ref path) => path,
+        None => {
+            println!("== CURVE PUBLIC KEY ==");
+            println!("{}", public_key);
+            println!("== CURVE SECRET KEY ==");
+            println!("{}", cert.secret_key_z85().unwrap());
+            return Ok(());
+        }
+    };
+    for (name, value) in &args.metadata {
+        cert.set_meta(name, value);
+    }
+    cert.save_new(cert_path)
+        .map_err(|e| format!("{}: {}", cert_path, e))?;
+    println!("{}", public_key);
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    if let Err(message) = run(&args) {
+        eprintln!("zmq-curve-keygen: {}", message);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,177 @@
+//! Print the connection events and messages of a socket attached to an
+//! endpoint, for debugging connectivity without writing code.
+//!
+//! ```text
+//! zmq-monitor [--bind] [--type TYPE] [--subscribe PREFIX]... ENDPOINT
+//! ```
+//!
+//! By default, a `SUB` socket subscribed to everything connects to
+//! `ENDPOINT`. `--subscribe` applies to `SUB` and `XSUB` sockets only.
+//! To act as the capture sink of a proxy, use e.g. `--bind --type pull`
+//! with the endpoint the proxy's capture socket connects to.
+
+use std::env;
+use std::process;
+use std::str;
+use std::time::SystemTime;
+
+const USAGE: &str = "usage: zmq-monitor [--bind] [--type TYPE] [--subscribe PREFIX]... ENDPOINT";
+
+// The number of bytes of each frame to show.
+const PREVIEW_LEN: usize = 32;
+
+struct Args {
+    bind: bool,
+    socket_type: zmq::SocketType,
+    subscriptions: Vec<String>,
+    endpoint: String,
+}
+
+fn parse_socket_type(name: &str) -> Option<zmq::SocketType> {
+    let socket_type = match name.to_ascii_lowercase().as_str() {
+        "pair" => zmq::PAIR,
+        "sub" => zmq::SUB,
+        "xsub" => zmq::XSUB,
+        "pull" => zmq::PULL,
+        "dealer" => zmq::DEALER,
+        "router" => zmq::ROUTER,
+        "stream" => zmq::STREAM,
+        _ => return None,
+    };
+    Some(socket_type)
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1);
+    let mut bind = false;
+    let mut socket_type = zmq::SUB;
+    let mut subscriptions = Vec::new();
+    let mut endpoint = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => bind = true,
+            "--type" => {
+                let name = args.next().ok_or("--type requires a value")?;
+                socket_type = parse_socket_type(&name)
+                    .ok_or_else(|| format!("unsupported socket type: {}", name))?;
+            }
+            "--subscribe" => {
+                subscriptions.push(args.next().ok_or("--subscribe requires a value")?);
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if endpoint.is_none() && !arg.starts_with('-') => endpoint = Some(arg),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+    if !subscriptions.is_empty() && socket_type != zmq::SUB && socket_type != zmq::XSUB {
+        return Err(format!(
+            "--subscribe requires --type sub or xsub\n{}",
+            USAGE
+        ));
+    }
+    Ok(Args {
+        bind,
+        socket_type,
+        subscriptions,
+        endpoint: endpoint.ok_or(USAGE)?,
+    })
+}
+
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    format!("{}.{:06}", now.as_secs(), now.subsec_micros())
+}
+
+fn summarize(frame: &[u8]) -> String {
+    let preview = &frame[..frame.len().min(PREVIEW_LEN)];
+    let ellipsis = if frame.len() > PREVIEW_LEN { "..." } else { "" };
+    match str::from_utf8(preview) {
+        Ok(text) if !text.chars().any(char::is_control) => {
+            format!("[{:>6}] {:?}{}", frame.len(), text, ellipsis)
+        }
+        _ => {
+            let hex: String = preview.iter().map(|b| format!("{:02x}", b)).collect();
+            format!("[{:>6}] 0x{}{}", frame.len(), hex, ellipsis)
+        }
+    }
+}
+
+fn print_event(result: zmq::Result<zmq::MonitorEvent>) -> zmq::Result<()> {
+    match result {
+        Ok(event) => println!(
+            "{} event {:?} ({:?}) {}",
+            timestamp(),
+            event.event,
+            event.detail(),
+            event.endpoint
+        ),
+        Err(zmq::Error::EPROTO) => eprintln!("{} malformed monitor event", timestamp()),
+        Err(e) => return Err(e),
+    }
+    Ok(())
+}
+
+fn run(args: &Args) -> zmq::Result<()> {
+    let ctx = zmq::Context::new();
+    let socket = ctx.socket(args.socket_type)?;
+    socket.set_linger(0)?;
+    let monitor_endpoint = format!("inproc://zmq-monitor-{}", process::id());
+    socket.monitor(&monitor_endpoint, zmq::SocketEvent::ALL.to_raw() as i32)?;
+    let monitor = ctx.socket(zmq::PAIR)?;
+    monitor.connect(&monitor_endpoint)?;
+
+    let mut prefixes: Vec<&[u8]> = args.subscriptions.iter().map(|s| s.as_bytes()).collect();
+    if prefixes.is_empty() {
+        prefixes.push(b"");
+    }
+    for prefix in prefixes {
+        if args.socket_type == zmq::SUB {
+            socket.set_subscribe(prefix)?;
+        } else if args.socket_type == zmq::XSUB {
+            // An `XSUB` socket subscribes by sending subscription messages,
+            // which it also replays to publishers connecting later.
+            let mut subscription = vec![1];
+            subscription.extend_from_slice(prefix);
+            socket.send(subscription, 0)?;
+        }
+    }
+    if args.bind {
+        socket.bind(&args.endpoint)?;
+    } else {
+        socket.connect(&args.endpoint)?;
+    }
+
+    loop {
+        let mut items = [
+            monitor.as_poll_item(zmq::POLLIN),
+            socket.as_poll_item(zmq::POLLIN),
+        ];
+        zmq::poll(&mut items, -1)?;
+        if items[0].is_readable() {
+            print_event(monitor.recv_monitor_event(0))?;
+        }
+        if items[1].is_readable() {
+            let parts = socket.recv_multipart(0)?;
+            println!("{} message, {} part(s)", timestamp(), parts.len());
+            for part in &parts {
+                println!("    {}", summarize(part));
+            }
+        }
+    }
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    if let Err(e) = run(&args) {
+        eprintln!("zmq-monitor: {}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use crate::{Result, Socket};
+
+type Callback<'a> = Box<dyn FnMut(&BudgetReport) + 'a>;
+
+/// An upper bound on the memory that a set of sockets may buffer.
+///
+/// `libzmq` does not expose the size of its internal queues, so the budget
+/// works from the configured high-water marks: a socket may queue up to
+/// `ZMQ_SNDHWM` outgoing and `ZMQ_RCVHWM` incoming messages per connection,
+/// e.g. a `PUB` socket with N subscribers up to N times `ZMQ_SNDHWM`.
+/// Multiplying these by the number of peers, as given via `set_peers`, and
+/// by an expected message size -- given at registration, and refined from
+/// the sizes passed to `observe` -- yields the worst case. Sockets with a
+/// high-water mark of zero (unlimited) cannot be bounded and are counted
+/// separately.
+///
+/// This is a static bound derived from the configuration, not a measurement
+/// of what is actually queued: it tells whether buffering could grow beyond
+/// the limit, not whether it has.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PUSH).unwrap();
+/// socket.set_sndhwm(1000).unwrap();
+///
+/// let exceeded = Cell::new(false);
+/// let mut budget = zmq::MemoryBudget::new(64 * 1024);
+/// budget.on_exceeded(|_report| exceeded.set(true));
+/// budget.register(&socket, 1024);
+///
+/// let report = budget.check().unwrap();
+/// assert!(report.is_exceeded());
+/// assert!(exceeded.get());
+/// ```
+pub struct MemoryBudget<'a> {
+    limit: usize,
+    entries: Vec<Entry<'a>>,
+    callbacks: Vec<Callback<'a>>,
+    exceeded: bool,
+}
+
+struct Entry<'a> {
+    socket: &'a Socket,
+    message_size: f64,
+    peers: usize,
+}
+
+/// The result of evaluating a `MemoryBudget`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BudgetReport {
+    /// The configured limit, in bytes.
+    pub limit: usize,
+    /// The estimated worst-case number of buffered bytes, across all sockets
+    /// with bounded queues.
+    pub estimated_bytes: usize,
+    /// The number of registered sockets with an unlimited high-water mark.
+    pub unbounded_sockets: usize,
+}
+
+impl BudgetReport {
+    /// Whether the estimate exceeds the limit, or any socket is unbounded.
+    pub fn is_exceeded(&self) -> bool {
+        self.estimated_bytes > self.limit || self.unbounded_sockets > 0
+    }
+}
+
+impl<'a> MemoryBudget<'a> {
+    /// Create a budget of `limit` bytes.
+    pub fn new(limit: usize) -> MemoryBudget<'a> {
+        MemoryBudget {
+            limit,
+            entries: Vec::new(),
+            callbacks: Vec::new(),
+            exceeded: false,
+        }
+    }
+
+    /// Account for `socket`, expecting messages of about `message_size`
+    /// bytes, and a single peer.
+    pub fn register(&mut self, socket: &'a Socket, message_size: usize) {
+        self.entries.push(Entry {
+            socket,
+            message_size: message_size as f64,
+            peers: 1,
+        });
+    }
+
+    /// Set the number of peers of `socket`, each of which has queues of its
+    /// own, e.g. when subscribers join or leave a `PUB` socket.
+    pub fn set_peers(&mut self, socket: &Socket, peers: usize) {
+        if let Some(entry) = self.entry_mut(socket) {
+            entry.peers = peers;
+        }
+    }
+
+    /// Stop accounting for `socket`.
+    pub fn unregister(&mut self, socket: &Socket) {
+        self.entries.retain(|e| !std::ptr::eq(e.socket, socket));
+    }
+
+    /// Refine the expected message size of `socket` with the size of a
+    /// message actually sent or received on it.
+    ///
+    /// The expectation follows a moving average, so that a few large
+    /// messages raise the estimate without a single outlier dominating it.
+    pub fn observe(&mut self, socket: &Socket, message_len: usize) {
+        if let Some(entry) = self.entry_mut(socket) {
+            entry.message_size = entry.message_size * 0.875 + message_len as f64 * 0.125;
+        }
+    }
+
+    fn entry_mut(&mut self, socket: &Socket) -> Option<&mut Entry<'a>> {
+        self.entries
+            .iter_mut()
+            .find(|e| std::ptr::eq(e.socket, socket))
+    }
+
+    /// Register a callback to be invoked when `check` finds the budget
+    /// exceeded.
+    ///
+    /// Callbacks fire once when the budget becomes exceeded, and again only
+    /// after a subsequent check has found it within bounds.
+    pub fn on_exceeded<F>(&mut self, callback: F)
+    where
+        F: FnMut(&BudgetReport) + 'a,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Compute the current estimate from the sockets' high-water marks and
+    /// numbers of peers.
+    pub fn estimate(&self) -> Result<BudgetReport> {
+        let mut report = BudgetReport {
+            limit: self.limit,
+            estimated_bytes: 0,
+            unbounded_sockets: 0,
+        };
+        for entry in &self.entries {
+            let sndhwm = entry.socket.get_sndhwm()?;
+            let rcvhwm = entry.socket.get_rcvhwm()?;
+            if sndhwm <= 0 || rcvhwm <= 0 {
+                report.unbounded_sockets += 1;
+                continue;
+            }
+            let messages = (sndhwm as usize + rcvhwm as usize).saturating_mul(entry.peers);
+            // The conversion saturates.
+            let bytes = (messages as f64 * entry.message_size) as usize;
+            report.estimated_bytes = report.estimated_bytes.saturating_add(bytes);
+        }
+        Ok(report)
+    }
+
+    /// Compute the current estimate, invoking the `on_exceeded` callbacks if
+    /// the budget has just become exceeded.
+    pub fn check(&mut self) -> Result<BudgetReport> {
+        let report = self.estimate()?;
+        let exceeded = report.is_exceeded();
+        if exceeded && !self.exceeded {
+            for callback in &mut self.callbacks {
+                callback(&report);
+            }
+        }
+        self.exceeded = exceeded;
+        Ok(report)
+    }
+}
+
+impl<'a> fmt::Debug for MemoryBudget<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("MemoryBudget")
+            .field("limit", &self.limit)
+            .field("sockets", &self.entries.len())
+            .field("exceeded", &self.exceeded)
+            .finish()
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{AsEndpoint, Context, ReconnectPolicy, Result, Socket, SocketType};
+
+type Setter = Box<dyn Fn(&Socket) -> Result<()>>;
+
+/// A builder for configured sockets.
+///
+/// Obtained via `Context::build_socket`. Options are recorded on the builder
+/// and applied in the order given when the socket is created, before any
+/// endpoints are bound or connected. This matters for options, such as the
+/// identity or the high-water marks, which only take effect for connections
+/// established after they have been set.
+///
+/// # Examples
+///
+/// ```
+/// let ctx = zmq::Context::new();
+/// let socket = ctx
+///     .build_socket(zmq::PUSH)
+///     .linger(0)
+///     .sndhwm(100_000)
+///     .bind("tcp://127.0.0.1:*")
+///     .unwrap();
+/// assert_eq!(socket.get_linger().unwrap(), 0);
+/// assert_eq!(socket.get_sndhwm().unwrap(), 100_000);
+/// ```
+pub struct SocketBuilder<'a> {
+    context: &'a Context,
+    socket_type: SocketType,
+    setters: Vec<Setter>,
+    binds: Vec<String>,
+    connects: Vec<String>,
+}
+
+macro_rules! builder_options {
+    ( $( $(#[$meta:meta])* $name:ident => $setter:ident as $ty:ty, )* ) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(self, value: $ty) -> Self {
+                self.option(move |socket| socket.$setter(value))
+            }
+        )*
+    };
+}
+
+impl<'a> SocketBuilder<'a> {
+    pub(crate) fn new(context: &'a Context, socket_type: SocketType) -> SocketBuilder<'a> {
+        SocketBuilder {
+            context,
+            socket_type,
+            setters: Vec::new(),
+            binds: Vec::new(),
+            connects: Vec::new(),
+        }
+    }
+
+    /// Apply an arbitrary configuration function to the socket, in order with
+    /// the other options.
+    ///
+    /// This can be used for options that have no dedicated builder method.
+    pub fn option<F>(mut self, setter: F) -> Self
+    where
+        F: Fn(&Socket) -> Result<()> + 'static,
+    {
+        self.setters.push(Box::new(setter));
+        self
+    }
+
+    builder_options! {
+        /// Set the `ZMQ_LINGER` option.
+        linger => set_linger as i32,
+        /// Set the `ZMQ_SNDHWM` option.
+        sndhwm => set_sndhwm as i32,
+        /// Set the `ZMQ_RCVHWM` option.
+        rcvhwm => set_rcvhwm as i32,
+        /// Set the `ZMQ_SNDTIMEO` option.
+        sndtimeo => set_sndtimeo as i32,
+        /// Set the `ZMQ_RCVTIMEO` option.
+        rcvtimeo => set_rcvtimeo as i32,
+        /// Set the `ZMQ_MAXMSGSIZE` option.
+        maxmsgsize => set_maxmsgsize as i64,
+        /// Set the `ZMQ_IMMEDIATE` option.
+        immediate => set_immediate as bool,
+        /// Set the `ZMQ_IPV6` option.
+        ipv6 => set_ipv6 as bool,
+        /// Set the `ZMQ_CONFLATE` option.
+        conflate => set_conflate as bool,
+        /// Set the `ZMQ_RECONNECT_IVL` option.
+        reconnect_ivl => set_reconnect_ivl as i32,
+        /// Set the `ZMQ_RECONNECT_IVL_MAX` option.
+        reconnect_ivl_max => set_reconnect_ivl_max as i32,
+        /// Set the `ZMQ_TCP_KEEPALIVE` option.
+        tcp_keepalive => set_tcp_keepalive as i32,
+    }
+
+    /// Set the `ZMQ_ROUTING_ID` option, the identity under which `ROUTER`
+    /// peers address this socket's connections.
+    pub fn routing_id(self, routing_id: &[u8]) -> Self {
+        let routing_id = routing_id.to_vec();
+        self.option(move |socket| socket.set_routing_id(&routing_id))
+    }
+
+    /// Subscribe to messages starting with `prefix`; may be given more than
+    /// once. Only valid for `SUB` sockets; an `XSUB` socket subscribes by
+    /// sending subscription messages instead.
+    pub fn subscribe(self, prefix: &[u8]) -> Self {
+        let prefix = prefix.to_vec();
+        self.option(move |socket| socket.set_subscribe(&prefix))
+    }
+
+    /// Configure reconnection according to the given policy.
+    pub fn reconnect_policy(self, policy: ReconnectPolicy) -> Self {
+        self.option(move |socket| policy.apply(socket))
+    }
+
+    /// Add an endpoint to bind to when the socket is built.
+    pub fn bind_to(mut self, endpoint: &(impl AsEndpoint + ?Sized)) -> Self {
+        self.binds.push(endpoint.as_endpoint().into_owned());
+        self
+    }
+
+    /// Add an endpoint to connect to when the socket is built.
+    pub fn connect_to(mut self, endpoint: &(impl AsEndpoint + ?Sized)) -> Self {
+        self.connects.push(endpoint.as_endpoint().into_owned());
+        self
+    }
+
+    /// Create the socket, apply all options, then bind and connect to the
+    /// endpoints added via `bind_to` and `connect_to`.
+    pub fn build(self) -> Result<Socket> {
+        let socket = self.context.socket(self.socket_type)?;
+        for setter in &self.setters {
+            setter(&socket)?;
+        }
+        for endpoint in &self.binds {
+            socket.bind(endpoint)?;
+        }
+        for endpoint in &self.connects {
+            socket.connect(endpoint)?;
+        }
+        Ok(socket)
+    }
+
+    /// Build the socket and bind it to `endpoint`.
+    pub fn bind(self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<Socket> {
+        self.bind_to(endpoint).build()
+    }
+
+    /// Build the socket and connect it to `endpoint`.
+    pub fn connect(self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<Socket> {
+        self.connect_to(endpoint).build()
+    }
+}
@@ -0,0 +1,318 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{z85_decode, z85_encode, CurveKeyPair, Result, Socket};
+
+/// A CURVE key pair with metadata, stored in the certificate format of
+/// CZMQ's `zcert`.
+///
+/// A certificate is saved as two files in ZPL format: a public one at the
+/// given path, holding the metadata and public key, and a secret one at
+/// the same path with a `_secret` suffix, holding the secret key as well.
+///
+/// # Examples
+///
+/// ```
+/// # fn main() -> std::io::Result<()> {
+/// # let dir = std::env::temp_dir().join(format!("zmq-cert-doc-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir)?;
+/// let path = dir.join("server.key");
+/// let mut cert = zmq::Certificate::from_keys([1; 32], Some([2; 32]));
+/// cert.set_meta("name", "server");
+/// cert.save(&path)?;
+///
+/// // Loading prefers the secret file, if present.
+/// let loaded = zmq::Certificate::load(&path)?;
+/// assert_eq!(loaded, cert);
+/// assert_eq!(loaded.meta("name"), Some("server"));
+/// # std::fs::remove_dir_all(&dir)
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Certificate {
+    public_key: [u8; 32],
+    secret_key: Option<[u8; 32]>,
+    metadata: Vec<(String, String)>,
+}
+
+impl Certificate {
+    /// Generate a certificate with a new key pair.
+    pub fn new() -> Result<Certificate> {
+        let pair = CurveKeyPair::new()?;
+        Ok(Certificate::from_keys(
+            pair.public_key,
+            Some(pair.secret_key),
+        ))
+    }
+
+    /// Create a certificate from existing keys, without metadata.
+    pub fn from_keys(public_key: [u8; 32], secret_key: Option<[u8; 32]>) -> Certificate {
+        Certificate {
+            public_key,
+            secret_key,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Return the public key.
+    pub fn public_key(&self) -> &[u8; 32] {
+        &self.public_key
+    }
+
+    /// Return the secret key, unless this is a public certificate.
+    pub fn secret_key(&self) -> Option<&[u8; 32]> {
+        self.secret_key.as_ref()
+    }
+
+    /// Return the Z85 encoding of the public key.
+    pub fn public_key_z85(&self) -> String {
+        z85_encode(&self.public_key).unwrap()
+    }
+
+    /// Return the Z85 encoding of the secret key, if any.
+    pub fn secret_key_z85(&self) -> Option<String> {
+        self.secret_key.map(|key| z85_encode(&key).unwrap())
+    }
+
+    /// Return the value of a metadata entry.
+    pub fn meta(&self, name: &str) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over the metadata entries, in the order they were set.
+    pub fn metadata(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.metadata
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Set a metadata entry, replacing any previous value.
+    pub fn set_meta(&mut self, name: &str, value: &str) {
+        match self.metadata.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = value.to_owned(),
+            None => self.metadata.push((name.to_owned(), value.to_owned())),
+        }
+    }
+
+    /// Configure `socket` to use this certificate's keys as a CURVE
+    /// client or server.
+    pub fn apply(&self, socket: &Socket) -> Result<()> {
+        socket.set_curve_publickey(&self.public_key)?;
+        if let Some(ref secret_key) = self.secret_key {
+            socket.set_curve_secretkey(secret_key)?;
+        }
+        Ok(())
+    }
+
+    /// Load a certificate from `path`, or from its secret counterpart if
+    /// that exists.
+    ///
+    /// Malformed files are reported as `io::ErrorKind::InvalidData`.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Certificate> {
+        let secret_path = secret_path(path.as_ref());
+        let text = match fs::read_to_string(&secret_path) {
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => fs::read_to_string(path)?,
+            result => result?,
+        };
+        parse(&text).map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+
+    /// Save the public certificate to `path`, and the secret one, if
+    /// there is a secret key, next to it, replacing existing files.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_files(path.as_ref(), true)
+    }
+
+    /// Like `save`, but fail with `io::ErrorKind::AlreadyExists` instead of
+    /// replacing an existing file.
+    pub fn save_new<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.save_files(path.as_ref(), false)
+    }
+
+    // The secret file is written first, so that a public certificate never
+    // exists without its secret counterpart.
+    fn save_files(&self, path: &Path, replace: bool) -> io::Result<()> {
+        if self.secret_key.is_some() {
+            write_file(&secret_path(path), &self.encode(true)?, true, replace)?;
+        }
+        write_file(path, &self.encode(false)?, false, replace)
+    }
+
+    /// Save the public certificate only to `path`.
+    pub fn save_public<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        write_file(path.as_ref(), &self.encode(false)?, false, true)
+    }
+
+    /// Save the secret certificate to `path`. On Unix, the file is only
+    /// readable by its owner.
+    ///
+    /// Fails with `io::ErrorKind::InvalidInput` if there is no secret key.
+    pub fn save_secret<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        if self.secret_key.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "certificate has no secret key",
+            ));
+        }
+        write_file(path.as_ref(), &self.encode(true)?, true, true)
+    }
+
+    fn encode(&self, secret: bool) -> io::Result<String> {
+        let mut text = String::new();
+        if secret {
+            text.push_str("#   ZeroMQ CURVE **Secret** Certificate\n");
+            text.push_str(
+                "#   DO NOT PROVIDE THIS FILE TO OTHER USERS nor change its permissions.\n",
+            );
+        } else {
+            text.push_str("#   ZeroMQ CURVE Public Certificate\n");
+            text.push_str(
+                "#   Exchange securely, or use a secure mechanism to verify the contents\n",
+            );
+            text.push_str("#   of this file after exchange.\n");
+        }
+        text.push_str("\nmetadata\n");
+        for (name, value) in &self.metadata {
+            if !is_zpl_name(name) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid metadata name: {:?}", name),
+                ));
+            }
+            text.push_str(&format!("    {} = {}\n", name, quote(value)?));
+        }
+        text.push_str("curve\n");
+        text.push_str(&format!(
+            "    public-key = {}\n",
+            quote(&self.public_key_z85())?
+        ));
+        if let (true, Some(secret_key)) = (secret, self.secret_key_z85()) {
+            text.push_str(&format!("    secret-key = {}\n", quote(&secret_key)?));
+        }
+        Ok(text)
+    }
+}
+
+fn secret_path(path: &Path) -> PathBuf {
+    let mut secret = path.as_os_str().to_owned();
+    secret.push("_secret");
+    PathBuf::from(secret)
+}
+
+fn write_file(path: &Path, contents: &str, secret: bool, replace: bool) -> io::Result<()> {
+    let mut options = OpenOptions::new();
+    if replace {
+        options.write(true).create(true).truncate(true);
+    } else {
+        options.write(true).create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(if secret { 0o600 } else { 0o644 });
+    }
+    let mut file = options.open(path)?;
+    // The mode above only applies to newly created files, so an existing
+    // secret file is restricted before the key is written to it.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if secret {
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = secret;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()
+}
+
+fn is_zpl_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "$-_@.&+/".contains(c))
+}
+
+// Quote a value for ZPL, which has no escape sequences.
+fn quote(value: &str) -> io::Result<String> {
+    if value.contains('"') || value.contains('\n') {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("value cannot be stored in a certificate: {:?}", value),
+        ));
+    }
+    Ok(format!("\"{}\"", value))
+}
+
+// Parse the ZPL subset used by certificates: top-level section names, and
+// `name = value` entries indented below them.
+fn parse(text: &str) -> std::result::Result<Certificate, String> {
+    let mut section = String::new();
+    let mut public_key = None;
+    let mut secret_key = None;
+    let mut metadata = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let error = |message: &str| format!("line {}: {}", number + 1, message);
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        if content.len() == line.len() {
+            section = strip_comment(content).trim_end().to_owned();
+            continue;
+        }
+        let mut split = content.splitn(2, '=');
+        let name = split.next().unwrap().trim();
+        let value = unquote(
+            split
+                .next()
+                .ok_or_else(|| error("expected `name = value`"))?,
+        )
+        .ok_or_else(|| error("unterminated quoted value"))?;
+        match (section.as_str(), name) {
+            ("metadata", _) => metadata.push((name.to_owned(), value)),
+            ("curve", "public-key") => {
+                public_key = Some(decode_key(&value).ok_or_else(|| error("invalid public key"))?)
+            }
+            ("curve", "secret-key") => {
+                secret_key = Some(decode_key(&value).ok_or_else(|| error("invalid secret key"))?)
+            }
+            _ => {}
+        }
+    }
+    Ok(Certificate {
+        public_key: public_key.ok_or("missing public key")?,
+        secret_key,
+        metadata,
+    })
+}
+
+fn strip_comment(s: &str) -> &str {
+    s.split('#').next().unwrap()
+}
+
+fn unquote(raw: &str) -> Option<String> {
+    let raw = raw.trim_start();
+    match raw.chars().next() {
+        Some(quote @ '"') | Some(quote @ '\'') => {
+            let end = raw[1..].find(quote)?;
+            Some(raw[1..=end].to_owned())
+        }
+        _ => Some(strip_comment(raw).trim_end().to_owned()),
+    }
+}
+
+fn decode_key(z85: &str) -> Option<[u8; 32]> {
+    let bytes = z85_decode(z85).ok()?;
+    if bytes.len() != 32 {
+        return None;
+    }
+    let mut key = [0; 32];
+    key.copy_from_slice(&bytes);
+    Some(key)
+}
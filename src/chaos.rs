@@ -0,0 +1,238 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use crate::rng::Rng;
+use crate::{poll, Result, Socket, DONTWAIT, POLLIN};
+
+/// The faults injected by a `ChaosProxy`.
+///
+/// Each probability is evaluated independently for every message, in the
+/// order drop, duplicate, reorder, delay, using a generator seeded with
+/// `seed`, so that the same sequence of messages always meets the same
+/// fate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChaosConfig {
+    /// Probability of silently dropping a message.
+    pub drop: f64,
+    /// Probability of forwarding a message twice.
+    pub duplicate: f64,
+    /// Probability of holding a message back until the next message in the
+    /// same direction has been forwarded, or `reorder_window` has passed.
+    pub reorder: f64,
+    /// How long a message held back for reordering may wait for a successor.
+    pub reorder_window: Duration,
+    /// Probability of delaying a message.
+    pub delay: f64,
+    /// Upper bound of the delay, which is chosen uniformly at random.
+    pub max_delay: Duration,
+    /// Seed for the random decisions.
+    pub seed: u64,
+}
+
+impl Default for ChaosConfig {
+    /// A configuration that forwards every message unchanged.
+    fn default() -> Self {
+        ChaosConfig {
+            drop: 0.0,
+            duplicate: 0.0,
+            reorder: 0.0,
+            reorder_window: Duration::from_millis(100),
+            delay: 0.0,
+            max_delay: Duration::from_millis(0),
+            seed: 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Direction {
+    FrontendToBackend,
+    BackendToFrontend,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Scheduled {
+    due: Instant,
+    seq: u64,
+    direction: Direction,
+    parts: Vec<Vec<u8>>,
+}
+
+/// A proxy that forwards messages between two sockets like `proxy`, while
+/// injecting faults -- drops, duplicates, reordering and delays -- for
+/// testing how applications cope with unreliable networks.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+/// use zmq::{ChaosConfig, ChaosProxy};
+///
+/// let ctx = zmq::Context::new();
+/// let frontend = ctx.socket(zmq::ROUTER).unwrap();
+/// let backend = ctx.socket(zmq::DEALER).unwrap();
+/// frontend.bind("tcp://*:5559").unwrap();
+/// backend.bind("tcp://*:5560").unwrap();
+///
+/// let mut proxy = ChaosProxy::new(ChaosConfig {
+///     drop: 0.05,
+///     delay: 0.2,
+///     max_delay: Duration::from_millis(500),
+///     seed: 42,
+///     ..ChaosConfig::default()
+/// });
+/// proxy.run(&frontend, &backend).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct ChaosProxy {
+    config: ChaosConfig,
+    rng: Rng,
+    seq: u64,
+    scheduled: BinaryHeap<Reverse<Scheduled>>,
+    held: [Option<Scheduled>; 2],
+}
+
+impl ChaosProxy {
+    /// Create a proxy injecting the configured faults.
+    pub fn new(config: ChaosConfig) -> ChaosProxy {
+        ChaosProxy {
+            rng: Rng::with_seed(config.seed),
+            config,
+            seq: 0,
+            scheduled: BinaryHeap::new(),
+            held: [None, None],
+        }
+    }
+
+    /// Forward messages until an error occurs, e.g. `Error::ETERM` when the
+    /// context is terminated.
+    pub fn run(&mut self, frontend: &Socket, backend: &Socket) -> Result<()> {
+        loop {
+            self.step(frontend, backend, None)?;
+        }
+    }
+
+    /// Wait for at most `timeout` (forever if `None`) for messages or due
+    /// deliveries, and process them.
+    pub fn step(
+        &mut self,
+        frontend: &Socket,
+        backend: &Socket,
+        timeout: Option<Duration>,
+    ) -> Result<()> {
+        let now = Instant::now();
+        let wait = self
+            .next_due()
+            .map(|due| due.saturating_duration_since(now))
+            .into_iter()
+            .chain(timeout)
+            .min();
+        let timeout_ms = wait.map_or(-1, |d| {
+            // Round up, so that we don't spin while waiting for a due time.
+            (d.as_micros() as i64 + 999) / 1000
+        });
+
+        let mut items = [frontend.as_poll_item(POLLIN), backend.as_poll_item(POLLIN)];
+        poll(&mut items, timeout_ms)?;
+        let readable = [items[0].is_readable(), items[1].is_readable()];
+
+        if readable[0] {
+            while let Some(parts) = recv_nonblocking(frontend)? {
+                self.inject(Direction::FrontendToBackend, parts);
+            }
+        }
+        if readable[1] {
+            while let Some(parts) = recv_nonblocking(backend)? {
+                self.inject(Direction::BackendToFrontend, parts);
+            }
+        }
+        self.deliver(frontend, backend)
+    }
+
+    fn next_due(&self) -> Option<Instant> {
+        let scheduled = self.scheduled.peek().map(|s| s.0.due);
+        let held = self.held.iter().flatten().map(|s| s.due);
+        scheduled.into_iter().chain(held).min()
+    }
+
+    fn schedule(&mut self, direction: Direction, parts: Vec<Vec<u8>>, due: Instant) {
+        self.seq += 1;
+        self.scheduled.push(Reverse(Scheduled {
+            due,
+            seq: self.seq,
+            direction,
+            parts,
+        }));
+    }
+
+    fn inject(&mut self, direction: Direction, parts: Vec<Vec<u8>>) {
+        let now = Instant::now();
+        if self.rng.chance(self.config.drop) {
+            return;
+        }
+        let copies = if self.rng.chance(self.config.duplicate) {
+            2
+        } else {
+            1
+        };
+        let reorder = self.rng.chance(self.config.reorder);
+        let due = if self.rng.chance(self.config.delay) {
+            let max = self.config.max_delay.as_micros() as u64;
+            now + Duration::from_micros(self.rng.range_inclusive(0, max))
+        } else {
+            now
+        };
+
+        let slot = direction as usize;
+        let held = self.held[slot].take();
+        if reorder && held.is_none() {
+            self.seq += 1;
+            self.held[slot] = Some(Scheduled {
+                due: due.max(now + self.config.reorder_window),
+                seq: self.seq,
+                direction,
+                parts: parts.clone(),
+            });
+            if copies == 2 {
+                self.schedule(direction, parts, due);
+            }
+            return;
+        }
+        for _ in 1..copies {
+            self.schedule(direction, parts.clone(), due);
+        }
+        self.schedule(direction, parts, due);
+        if let Some(held) = held {
+            // Release the held message right after its successor.
+            self.schedule(held.direction, held.parts, due);
+        }
+    }
+
+    fn deliver(&mut self, frontend: &Socket, backend: &Socket) -> Result<()> {
+        let now = Instant::now();
+        for slot in 0..2 {
+            if matches!(self.held[slot], Some(ref h) if h.due <= now) {
+                let held = self.held[slot].take().unwrap();
+                self.schedule(held.direction, held.parts, held.due);
+            }
+        }
+        while matches!(self.scheduled.peek(), Some(s) if s.0.due <= now) {
+            let Reverse(msg) = self.scheduled.pop().unwrap();
+            let target = match msg.direction {
+                Direction::FrontendToBackend => backend,
+                Direction::BackendToFrontend => frontend,
+            };
+            target.send_multipart(msg.parts, 0)?;
+        }
+        Ok(())
+    }
+}
+
+fn recv_nonblocking(socket: &Socket) -> Result<Option<Vec<Vec<u8>>>> {
+    match socket.recv_multipart(DONTWAIT) {
+        Ok(parts) => Ok(Some(parts)),
+        Err(crate::Error::EAGAIN) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,64 @@
+//! A simple length-prefixed encoding of multipart messages, used to store
+//! them in files.
+//!
+//! Each message is written as its number of parts, followed by each part as
+//! its length and contents. All integers are 32-bit big-endian.
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+/// Write the parts of a message.
+pub(crate) fn write_parts<W, T>(writer: &mut W, parts: &[T]) -> io::Result<()>
+where
+    W: Write,
+    T: AsRef<[u8]>,
+{
+    write_u32(writer, parts.len())?;
+    for part in parts {
+        let part = part.as_ref();
+        write_u32(writer, part.len())?;
+        writer.write_all(part)?;
+    }
+    Ok(())
+}
+
+/// Read the parts of a message, returning `None` at the end of the input.
+pub(crate) fn read_parts<R: Read>(reader: &mut R) -> io::Result<Option<Vec<Vec<u8>>>> {
+    let count = match read_u32(reader) {
+        Ok(count) => count,
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut parts = Vec::new();
+    for _ in 0..count {
+        let len = read_u32(reader)?;
+        // Read via `take` rather than allocating `len` bytes up-front, so
+        // that a corrupt length cannot trigger a huge allocation.
+        let mut part = Vec::new();
+        reader.take(u64::from(len)).read_to_end(&mut part)?;
+        if part.len() as u64 != u64::from(len) {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        parts.push(part);
+    }
+    Ok(Some(parts))
+}
+
+/// Return the number of bytes `write_parts` produces for `parts`.
+pub(crate) fn encoded_len<T: AsRef<[u8]>>(parts: &[T]) -> u64 {
+    parts
+        .iter()
+        .fold(4, |len, part| len + 4 + part.as_ref().len() as u64)
+}
+
+fn write_u32<W: Write>(writer: &mut W, value: usize) -> io::Result<()> {
+    let value = u32::try_from(value)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "message part too large"))?;
+    writer.write_all(&value.to_be_bytes())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut buf = [0; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
@@ -0,0 +1,85 @@
+use libc::c_void;
+
+use std::fmt;
+
+/// A thread-safe counter, backed by `libzmq`'s atomic counter API.
+///
+/// This is mostly useful for sharing a counter with C code that already uses
+/// the `zmq_atomic_counter_*` functions; pure Rust code should prefer
+/// `std::sync::atomic`.
+///
+/// # Examples
+///
+/// ```
+/// let counter = zmq::AtomicCounter::new();
+/// assert_eq!(counter.increment(), 0);
+/// assert_eq!(counter.increment(), 1);
+/// assert!(counter.decrement());
+/// assert!(!counter.decrement());
+/// assert_eq!(counter.value(), 0);
+/// ```
+pub struct AtomicCounter {
+    counter: *mut c_void,
+}
+
+unsafe impl Send for AtomicCounter {}
+unsafe impl Sync for AtomicCounter {}
+
+impl AtomicCounter {
+    /// Create a new counter, initialized to zero.
+    pub fn new() -> AtomicCounter {
+        let counter = unsafe { zmq_sys::zmq_atomic_counter_new() };
+        if counter.is_null() {
+            panic!("failed to allocate atomic counter");
+        }
+        AtomicCounter { counter }
+    }
+
+    /// Set the counter to `value`.
+    pub fn set(&self, value: i32) {
+        unsafe { zmq_sys::zmq_atomic_counter_set(self.counter, value) }
+    }
+
+    /// Increment the counter by one, returning its previous value.
+    pub fn increment(&self) -> i32 {
+        unsafe { zmq_sys::zmq_atomic_counter_inc(self.counter) }
+    }
+
+    /// Decrement the counter by one. Returns `true` if the counter is still
+    /// greater than zero afterwards.
+    pub fn decrement(&self) -> bool {
+        unsafe { zmq_sys::zmq_atomic_counter_dec(self.counter) != 0 }
+    }
+
+    /// Return the current value of the counter.
+    pub fn value(&self) -> i32 {
+        unsafe { zmq_sys::zmq_atomic_counter_value(self.counter) }
+    }
+
+    /// Return the raw counter pointer, for passing to C code.
+    ///
+    /// The pointer remains valid as long as the `AtomicCounter` is alive.
+    pub fn as_ptr(&self) -> *mut c_void {
+        self.counter
+    }
+}
+
+impl Default for AtomicCounter {
+    fn default() -> Self {
+        AtomicCounter::new()
+    }
+}
+
+impl fmt::Debug for AtomicCounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AtomicCounter")
+            .field("value", &self.value())
+            .finish()
+    }
+}
+
+impl Drop for AtomicCounter {
+    fn drop(&mut self) {
+        unsafe { zmq_sys::zmq_atomic_counter_destroy(&mut self.counter) }
+    }
+}
@@ -0,0 +1,101 @@
+use std::collections::{HashMap, VecDeque};
+
+/// A bounded cache of recently seen request ids, for making request
+/// handlers idempotent.
+///
+/// Clients that retry requests on timeout, like the "Lazy Pirate" pattern,
+/// may deliver the same request more than once. If each request carries a
+/// unique id, a server can use `check_and_record` to detect repeated
+/// requests and skip their side effects. Once `capacity` ids are stored,
+/// the least recently seen one is forgotten.
+///
+/// # Examples
+///
+/// ```
+/// let mut seen = zmq::Deduplicator::new(1000);
+/// assert!(!seen.check_and_record(b"request-1"));
+/// assert!(seen.check_and_record(b"request-1"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Deduplicator {
+    capacity: usize,
+    // The time at which each id was last seen, on a logical clock.
+    last_seen: HashMap<Vec<u8>, u64>,
+    // Ids in order of access; entries superseded by a later access are
+    // skipped on eviction.
+    order: VecDeque<(u64, Vec<u8>)>,
+    clock: u64,
+}
+
+impl Deduplicator {
+    /// Create a cache remembering up to `capacity` ids.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Deduplicator {
+        assert!(capacity > 0, "capacity must be positive");
+        Deduplicator {
+            capacity,
+            last_seen: HashMap::new(),
+            order: VecDeque::new(),
+            clock: 0,
+        }
+    }
+
+    /// Return whether `id` was seen before, and record it as the most
+    /// recently seen id.
+    pub fn check_and_record(&mut self, id: &[u8]) -> bool {
+        self.clock += 1;
+        let seen = self.last_seen.insert(id.to_vec(), self.clock).is_some();
+        self.order.push_back((self.clock, id.to_vec()));
+        while self.last_seen.len() > self.capacity {
+            self.evict_oldest();
+        }
+        if self.order.len() > 2 * self.capacity {
+            self.compact();
+        }
+        seen
+    }
+
+    /// Return whether `id` is currently remembered, without recording it.
+    pub fn contains(&self, id: &[u8]) -> bool {
+        self.last_seen.contains_key(id)
+    }
+
+    /// Return the number of remembered ids.
+    pub fn len(&self) -> usize {
+        self.last_seen.len()
+    }
+
+    /// Return whether no ids are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.last_seen.is_empty()
+    }
+
+    /// Return the maximum number of remembered ids.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Forget all ids.
+    pub fn clear(&mut self) {
+        self.last_seen.clear();
+        self.order.clear();
+    }
+
+    fn evict_oldest(&mut self) {
+        while let Some((time, id)) = self.order.pop_front() {
+            if self.last_seen.get(&id) == Some(&time) {
+                self.last_seen.remove(&id);
+                return;
+            }
+        }
+    }
+
+    fn compact(&mut self) {
+        let last_seen = &self.last_seen;
+        self.order
+            .retain(|&(time, ref id)| last_seen.get(id) == Some(&time));
+    }
+}
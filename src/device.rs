@@ -0,0 +1,142 @@
+//! Ready-made proxies, running on their own thread.
+//!
+//! Each constructor binds a frontend and a backend socket of the matching
+//! types, and starts a steerable proxy between them, see `proxy_steerable`.
+//! The returned `Device` pauses, resumes or stops it via the proxy's
+//! control socket.
+//!
+//! # Examples
+//!
+//! ```
+//! let ctx = zmq::Context::new();
+//! let device = zmq::device::streamer(&ctx, "inproc://tasks", "inproc://workers").unwrap();
+//!
+//! let producer = ctx.socket(zmq::PUSH).unwrap();
+//! producer.connect("inproc://tasks").unwrap();
+//! let worker = ctx.socket(zmq::PULL).unwrap();
+//! worker.connect("inproc://workers").unwrap();
+//!
+//! producer.send("task", 0).unwrap();
+//! assert_eq!(worker.recv_bytes(0).unwrap(), b"task");
+//! device.terminate().unwrap();
+//! ```
+
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, JoinHandle};
+
+use crate::{Context, Result, Socket, SocketType, PAIR};
+
+/// A proxy running on a dedicated thread.
+///
+/// Dropping the device terminates the proxy and waits for its thread.
+pub struct Device {
+    control: Socket,
+    thread: Option<JoinHandle<Result<()>>>,
+}
+
+impl Device {
+    /// Stop forwarding messages; they are queued according to the sockets'
+    /// high-water marks in the meantime.
+    pub fn pause(&self) -> Result<()> {
+        self.control.send("PAUSE", 0)
+    }
+
+    /// Resume forwarding messages after `pause`.
+    pub fn resume(&self) -> Result<()> {
+        self.control.send("RESUME", 0)
+    }
+
+    /// Stop the proxy, wait for its thread, and return the proxy's result.
+    ///
+    /// If the context was terminated first, the proxy has already stopped
+    /// with `Error::ETERM`, which is returned here.
+    pub fn terminate(mut self) -> Result<()> {
+        self.stop()
+    }
+
+    fn stop(&mut self) -> Result<()> {
+        let thread = match self.thread.take() {
+            Some(thread) => thread,
+            None => return Ok(()),
+        };
+        // Fails if the proxy has already returned, which `join` reports.
+        let _ = self.control.send("TERMINATE", 0);
+        match thread.join() {
+            Ok(result) => result,
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    }
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        let _ = self.stop();
+    }
+}
+
+/// Start a shared queue, forwarding requests from clients connected to a
+/// `ROUTER` socket bound to `frontend` to workers connected to a `DEALER`
+/// socket bound to `backend`, and replies back.
+pub fn queue(ctx: &Context, frontend: &str, backend: &str) -> Result<Device> {
+    start(
+        ctx,
+        (SocketType::ROUTER, frontend),
+        (SocketType::DEALER, backend),
+    )
+}
+
+/// Start a forwarder, passing messages from publishers connected to an
+/// `XSUB` socket bound to `frontend` to subscribers connected to an `XPUB`
+/// socket bound to `backend`, and subscriptions back.
+pub fn forwarder(ctx: &Context, frontend: &str, backend: &str) -> Result<Device> {
+    start(
+        ctx,
+        (SocketType::XSUB, frontend),
+        (SocketType::XPUB, backend),
+    )
+}
+
+/// Start a streamer, passing messages from producers connected to a `PULL`
+/// socket bound to `frontend` to consumers connected to a `PUSH` socket
+/// bound to `backend`.
+pub fn streamer(ctx: &Context, frontend: &str, backend: &str) -> Result<Device> {
+    start(
+        ctx,
+        (SocketType::PULL, frontend),
+        (SocketType::PUSH, backend),
+    )
+}
+
+fn start(
+    ctx: &Context,
+    frontend: (SocketType, &str),
+    backend: (SocketType, &str),
+) -> Result<Device> {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    let bind = |(socket_type, endpoint): (SocketType, &str)| -> Result<Socket> {
+        let socket = ctx.socket(socket_type)?;
+        socket.bind(endpoint)?;
+        Ok(socket)
+    };
+    let mut frontend = bind(frontend)?;
+    let mut backend = bind(backend)?;
+
+    let control_endpoint = format!(
+        "inproc://zmq-device-{}",
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let control = ctx.socket(PAIR)?;
+    control.bind(&control_endpoint)?;
+    let mut proxy_control = ctx.socket(PAIR)?;
+    proxy_control.connect(&control_endpoint)?;
+
+    let thread = thread::spawn(move || {
+        crate::proxy_steerable(&mut frontend, &mut backend, &mut proxy_control)
+    });
+    Ok(Device {
+        control,
+        thread: Some(thread),
+    })
+}
@@ -0,0 +1,193 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed endpoint, as passed to `Socket::bind` and `Socket::connect`.
+///
+/// Parsing an endpoint string catches mistakes such as a misspelled
+/// transport or a missing port with a descriptive `EndpointError`, whereas
+/// `libzmq` only fails with e.g. `Error::EPROTONOSUPPORT` or `Error::EINVAL`
+/// once the endpoint is used. An `Endpoint` can also be built from its
+/// parts, and is formatted back into the string form by `Display`.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::{Endpoint, EndpointError};
+///
+/// let endpoint: Endpoint = "tcp://127.0.0.1:5555".parse().unwrap();
+/// assert_eq!(
+///     endpoint,
+///     Endpoint::Tcp { host: "127.0.0.1".into(), port: Some(5555) }
+/// );
+///
+/// let error = "tpc://127.0.0.1:5555".parse::<Endpoint>().unwrap_err();
+/// assert_eq!(error, EndpointError::UnknownTransport("tpc".into()));
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PULL).unwrap();
+/// socket.bind(&Endpoint::Inproc("endpoint-example".into())).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `tcp://host:port`, where the host is an address, a hostname or an
+    /// interface name, or `*` to bind to all interfaces. A port of `None`
+    /// stands for `*`, which binds to a port chosen by the system.
+    Tcp { host: String, port: Option<u16> },
+    /// `ipc://path`, a Unix domain socket.
+    Ipc(String),
+    /// `inproc://name`, for sockets of the same context.
+    Inproc(String),
+    /// `pgm://interface;address:port`, reliable multicast via PGM.
+    Pgm(String),
+    /// `epgm://interface;address:port`, reliable multicast via PGM,
+    /// encapsulated in UDP.
+    Epgm(String),
+    /// `udp://host:port`, for `RADIO` and `DISH` sockets.
+    Udp { host: String, port: u16 },
+    /// `tipc://address`, the TIPC cluster transport.
+    Tipc(String),
+    /// `vmci://address`, for communication between virtual machines.
+    Vmci(String),
+}
+
+/// Errors that can occur while parsing an `Endpoint`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EndpointError {
+    /// The endpoint does not start with a transport, such as `tcp://`.
+    MissingTransport,
+    /// The transport is not one of those known to `Endpoint`.
+    UnknownTransport(String),
+    /// Nothing follows the transport.
+    MissingAddress,
+    /// A `tcp://` or `udp://` address has no port.
+    MissingPort,
+    /// The port is not a number from 0 to 65535.
+    InvalidPort(String),
+}
+
+impl fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EndpointError::MissingTransport => {
+                write!(f, "endpoint does not start with a transport, e.g. tcp://")
+            }
+            EndpointError::UnknownTransport(ref transport) => {
+                write!(f, "unknown transport {}://", transport)
+            }
+            EndpointError::MissingAddress => write!(f, "endpoint has no address"),
+            EndpointError::MissingPort => write!(f, "endpoint has no port"),
+            EndpointError::InvalidPort(ref port) => write!(f, "invalid port {:?}", port),
+        }
+    }
+}
+
+impl std::error::Error for EndpointError {}
+
+impl FromStr for Endpoint {
+    type Err = EndpointError;
+
+    fn from_str(s: &str) -> Result<Endpoint, EndpointError> {
+        let (transport, address) = s.split_once("://").ok_or(EndpointError::MissingTransport)?;
+        if address.is_empty() {
+            return Err(EndpointError::MissingAddress);
+        }
+        let address = address.to_owned();
+        match transport {
+            "tcp" => {
+                let (host, port) = split_port(&address)?;
+                let port = if port == "*" {
+                    None
+                } else {
+                    Some(parse_port(port)?)
+                };
+                Ok(Endpoint::Tcp { host, port })
+            }
+            "udp" => {
+                let (host, port) = split_port(&address)?;
+                Ok(Endpoint::Udp {
+                    host,
+                    port: parse_port(port)?,
+                })
+            }
+            "ipc" => Ok(Endpoint::Ipc(address)),
+            "inproc" => Ok(Endpoint::Inproc(address)),
+            "pgm" => Ok(Endpoint::Pgm(address)),
+            "epgm" => Ok(Endpoint::Epgm(address)),
+            "tipc" => Ok(Endpoint::Tipc(address)),
+            "vmci" => Ok(Endpoint::Vmci(address)),
+            _ => Err(EndpointError::UnknownTransport(transport.to_owned())),
+        }
+    }
+}
+
+// Split at the last colon, since IPv6 addresses, in brackets, contain colons
+// as well.
+fn split_port(address: &str) -> Result<(String, &str), EndpointError> {
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => Ok((host.to_owned(), port)),
+        Some(_) => Err(EndpointError::MissingAddress),
+        None => Err(EndpointError::MissingPort),
+    }
+}
+
+fn parse_port(port: &str) -> Result<u16, EndpointError> {
+    if port.is_empty() {
+        return Err(EndpointError::MissingPort);
+    }
+    port.parse()
+        .map_err(|_| EndpointError::InvalidPort(port.to_owned()))
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Endpoint::Tcp {
+                ref host,
+                port: Some(port),
+            } => write!(f, "tcp://{}:{}", host, port),
+            Endpoint::Tcp {
+                ref host,
+                port: None,
+            } => write!(f, "tcp://{}:*", host),
+            Endpoint::Ipc(ref path) => write!(f, "ipc://{}", path),
+            Endpoint::Inproc(ref name) => write!(f, "inproc://{}", name),
+            Endpoint::Pgm(ref address) => write!(f, "pgm://{}", address),
+            Endpoint::Epgm(ref address) => write!(f, "epgm://{}", address),
+            Endpoint::Udp { ref host, port } => write!(f, "udp://{}:{}", host, port),
+            Endpoint::Tipc(ref address) => write!(f, "tipc://{}", address),
+            Endpoint::Vmci(ref address) => write!(f, "vmci://{}", address),
+        }
+    }
+}
+
+/// An endpoint accepted by `Socket::bind`, `Socket::connect` and their
+/// counterparts: either an endpoint string or an `Endpoint`.
+pub trait AsEndpoint {
+    /// Return the endpoint in its string form.
+    fn as_endpoint(&self) -> Cow<'_, str>;
+}
+
+impl AsEndpoint for str {
+    fn as_endpoint(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl AsEndpoint for String {
+    fn as_endpoint(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl AsEndpoint for Endpoint {
+    fn as_endpoint(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+}
+
+impl<T: AsEndpoint + ?Sized> AsEndpoint for &T {
+    fn as_endpoint(&self) -> Cow<'_, str> {
+        (**self).as_endpoint()
+    }
+}
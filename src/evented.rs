@@ -0,0 +1,64 @@
+use std::io;
+
+use mio::event::Source;
+use mio::unix::SourceFd;
+use mio::{Interest, Registry, Token};
+
+use crate::Socket;
+
+/// Registers the socket's `ZMQ_FD` with a `mio` event loop.
+///
+/// The file descriptor does not signal whether the socket is readable or
+/// writable. It only becomes readable, edge-triggered, when the result of
+/// `get_events` may have changed, and is reset whenever `get_events` is
+/// called or a message is sent or received. It is therefore always
+/// registered for readability, whatever the requested interest, and after
+/// every wakeup the socket must be serviced until `get_events` no longer
+/// reports the events of interest; otherwise, no further wakeup may occur.
+///
+/// # Examples
+///
+/// ```
+/// use mio::{Events, Interest, Poll, Token};
+///
+/// let ctx = zmq::Context::new();
+/// let mut receiver = ctx.socket(zmq::PULL).unwrap();
+/// receiver.bind("inproc://mio-example").unwrap();
+/// let sender = ctx.socket(zmq::PUSH).unwrap();
+/// sender.connect("inproc://mio-example").unwrap();
+///
+/// let mut poll = Poll::new().unwrap();
+/// poll.registry()
+///     .register(&mut receiver, Token(0), Interest::READABLE)
+///     .unwrap();
+/// sender.send("hello", 0).unwrap();
+/// sender.send("world", 0).unwrap();
+///
+/// let mut events = Events::with_capacity(8);
+/// let mut received = Vec::new();
+/// while received.len() < 2 {
+///     poll.poll(&mut events, None).unwrap();
+///     // Drain the socket, since there is no wakeup for messages that were
+///     // already queued.
+///     while receiver.get_events().unwrap().contains(zmq::POLLIN) {
+///         received.push(receiver.recv_bytes(zmq::DONTWAIT).unwrap());
+///     }
+/// }
+/// assert_eq!(received, vec![b"hello", b"world"]);
+/// ```
+impl Source for Socket {
+    fn register(&mut self, registry: &Registry, token: Token, _: Interest) -> io::Result<()> {
+        let fd = self.get_fd()?;
+        SourceFd(&fd).register(registry, token, Interest::READABLE)
+    }
+
+    fn reregister(&mut self, registry: &Registry, token: Token, _: Interest) -> io::Result<()> {
+        let fd = self.get_fd()?;
+        SourceFd(&fd).reregister(registry, token, Interest::READABLE)
+    }
+
+    fn deregister(&mut self, registry: &Registry) -> io::Result<()> {
+        let fd = self.get_fd()?;
+        SourceFd(&fd).deregister(registry)
+    }
+}
@@ -0,0 +1,288 @@
+//! Record framing for `STREAM` sockets.
+//!
+//! A `STREAM` socket hands out raw TCP data in whatever chunks it arrives,
+//! prefixed by the identity of the peer it came from. `StreamFramer` keeps a
+//! buffer per peer and reassembles these chunks into records, which are
+//! either preceded by a big-endian length prefix or terminated by a
+//! delimiter.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Error, Result, Socket, SNDMORE};
+
+/// The width of a big-endian length prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixWidth {
+    U8,
+    U16,
+    U32,
+    U64,
+}
+
+impl PrefixWidth {
+    fn len(self) -> usize {
+        match self {
+            PrefixWidth::U8 => 1,
+            PrefixWidth::U16 => 2,
+            PrefixWidth::U32 => 4,
+            PrefixWidth::U64 => 8,
+        }
+    }
+
+    fn max_value(self) -> u64 {
+        match self {
+            PrefixWidth::U8 => u64::from(u8::MAX),
+            PrefixWidth::U16 => u64::from(u16::MAX),
+            PrefixWidth::U32 => u64::from(u32::MAX),
+            PrefixWidth::U64 => u64::MAX,
+        }
+    }
+}
+
+/// How records are delimited within a byte stream.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Framing {
+    /// Each record is preceded by its length, in network byte order.
+    LengthPrefixed(PrefixWidth),
+    /// Each record is terminated by the given, non-empty byte sequence, which
+    /// is not part of the record.
+    Delimited(Vec<u8>),
+}
+
+/// An event produced by `StreamFramer::recv`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// A peer connected.
+    Connected(Vec<u8>),
+    /// A complete record was received from a peer.
+    Record { peer: Vec<u8>, data: Vec<u8> },
+    /// A peer disconnected. `incomplete` holds any trailing bytes that did
+    /// not form a complete record.
+    Disconnected { peer: Vec<u8>, incomplete: Vec<u8> },
+}
+
+/// Reassembles records from the byte streams of a `STREAM` socket.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::framing::{Framing, StreamFramer};
+///
+/// let mut framer = StreamFramer::new(Framing::Delimited(b"\r\n".to_vec()));
+/// assert!(framer.push(b"peer", b"HELO exam").unwrap().is_empty());
+/// assert_eq!(
+///     framer.push(b"peer", b"ple.com\r\nQUIT\r\n").unwrap(),
+///     vec![b"HELO example.com".to_vec(), b"QUIT".to_vec()],
+/// );
+/// ```
+#[derive(Debug)]
+pub struct StreamFramer {
+    framing: Framing,
+    max_record_len: usize,
+    buffers: HashMap<Vec<u8>, Buffer>,
+    pending: VecDeque<StreamEvent>,
+    // A peer whose buffer may hold an oversized record, found after the
+    // records returned along with it.
+    unchecked: Option<Vec<u8>>,
+}
+
+// The data buffered for one peer.
+#[derive(Debug, Default)]
+struct Buffer {
+    data: Vec<u8>,
+    // How many leading bytes of `data` are known not to start a delimiter,
+    // so that searching resumes where the previous search stopped.
+    scanned: usize,
+}
+
+impl StreamFramer {
+    /// Create a framer with no limit on the record length.
+    pub fn new(framing: Framing) -> StreamFramer {
+        if let Framing::Delimited(ref delimiter) = framing {
+            assert!(!delimiter.is_empty(), "delimiter must not be empty");
+        }
+        StreamFramer {
+            framing,
+            max_record_len: usize::MAX,
+            buffers: HashMap::new(),
+            pending: VecDeque::new(),
+            unchecked: None,
+        }
+    }
+
+    /// Limit the length of records, and thereby the amount of data buffered
+    /// per peer.
+    pub fn with_max_record_len(mut self, max_record_len: usize) -> StreamFramer {
+        self.max_record_len = max_record_len;
+        self
+    }
+
+    /// Append `data` received from `peer` to its buffer, and return the
+    /// records it completes.
+    ///
+    /// Returns `Error::EMSGSIZE` if a record exceeds the maximum length; the
+    /// peer's buffer is then discarded, since the stream can no longer be
+    /// parsed reliably, and the peer should be disconnected. If `data` also
+    /// completes records preceding the oversized one, these are returned
+    /// first, and the error is returned by the next call for the peer, which
+    /// may pass empty `data`.
+    pub fn push(&mut self, peer: &[u8], data: &[u8]) -> Result<Vec<Vec<u8>>> {
+        let buffer = self.buffers.entry(peer.to_vec()).or_default();
+        buffer.data.extend_from_slice(data);
+        let mut records = Vec::new();
+        match split_records(&self.framing, self.max_record_len, buffer, &mut records) {
+            Err(_) if !records.is_empty() => Ok(records),
+            Err(err) => {
+                buffer.data.clear();
+                buffer.scanned = 0;
+                Err(err)
+            }
+            Ok(()) => Ok(records),
+        }
+    }
+
+    /// Drop the buffered data of `peer`, returning it.
+    pub fn remove_peer(&mut self, peer: &[u8]) -> Vec<u8> {
+        self.buffers
+            .remove(peer)
+            .map(|buffer| buffer.data)
+            .unwrap_or_default()
+    }
+
+    /// Receive from a `STREAM` socket until the next event is available.
+    ///
+    /// This relies on `ZMQ_STREAM_NOTIFY` being enabled (the default), so
+    /// that connects and disconnects are signalled by empty messages.
+    pub fn recv(&mut self, socket: &Socket, flags: i32) -> Result<StreamEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            if let Some(peer) = self.unchecked.take() {
+                self.push(&peer, &[])?;
+            }
+            let peer = socket.recv_bytes(flags)?;
+            let data = socket.recv_bytes(flags)?;
+            if data.is_empty() {
+                let event = match self.buffers.remove(&peer) {
+                    Some(buffer) => StreamEvent::Disconnected {
+                        peer,
+                        incomplete: buffer.data,
+                    },
+                    None => {
+                        self.buffers.insert(peer.clone(), Buffer::default());
+                        StreamEvent::Connected(peer)
+                    }
+                };
+                return Ok(event);
+            }
+            let records = self.push(&peer, &data)?;
+            if !records.is_empty() {
+                self.unchecked = Some(peer.clone());
+            }
+            for data in records {
+                self.pending.push_back(StreamEvent::Record {
+                    peer: peer.clone(),
+                    data,
+                });
+            }
+        }
+    }
+
+    /// Encode `record` according to the framing.
+    pub fn encode(&self, record: &[u8]) -> Result<Vec<u8>> {
+        if record.len() > self.max_record_len {
+            return Err(Error::EMSGSIZE);
+        }
+        let mut out;
+        match self.framing {
+            Framing::LengthPrefixed(width) => {
+                let len = record.len() as u64;
+                if len > width.max_value() {
+                    return Err(Error::EMSGSIZE);
+                }
+                out = Vec::with_capacity(width.len() + record.len());
+                out.extend_from_slice(&len.to_be_bytes()[8 - width.len()..]);
+                out.extend_from_slice(record);
+            }
+            Framing::Delimited(ref delimiter) => {
+                if find(record, delimiter).is_some() {
+                    return Err(Error::EINVAL);
+                }
+                out = Vec::with_capacity(record.len() + delimiter.len());
+                out.extend_from_slice(record);
+                out.extend_from_slice(delimiter);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Encode `record` and send it to `peer` over a `STREAM` socket.
+    pub fn send(&self, socket: &Socket, peer: &[u8], record: &[u8]) -> Result<()> {
+        let data = self.encode(record)?;
+        socket.send(peer, SNDMORE)?;
+        socket.send(data, 0)
+    }
+}
+
+fn split_records(
+    framing: &Framing,
+    max_record_len: usize,
+    buffer: &mut Buffer,
+    records: &mut Vec<Vec<u8>>,
+) -> Result<()> {
+    let mut start = 0;
+    let result = loop {
+        let rest = &buffer.data[start..];
+        match *framing {
+            Framing::LengthPrefixed(width) => {
+                if rest.len() < width.len() {
+                    break Ok(());
+                }
+                let mut len_bytes = [0u8; 8];
+                len_bytes[8 - width.len()..].copy_from_slice(&rest[..width.len()]);
+                let len = u64::from_be_bytes(len_bytes);
+                if len > max_record_len as u64 {
+                    break Err(Error::EMSGSIZE);
+                }
+                let len = len as usize;
+                if rest.len() - width.len() < len {
+                    break Ok(());
+                }
+                records.push(rest[width.len()..width.len() + len].to_vec());
+                start += width.len() + len;
+            }
+            Framing::Delimited(ref delimiter) => {
+                let scanned = buffer.scanned.min(rest.len());
+                match find(&rest[scanned..], delimiter) {
+                    Some(pos) => {
+                        let pos = scanned + pos;
+                        if pos > max_record_len {
+                            break Err(Error::EMSGSIZE);
+                        }
+                        records.push(rest[..pos].to_vec());
+                        start += pos + delimiter.len();
+                        buffer.scanned = 0;
+                    }
+                    None => {
+                        // The last bytes may be the start of a delimiter
+                        // completed by the next chunk.
+                        buffer.scanned = rest.len().saturating_sub(delimiter.len() - 1);
+                        if rest.len() > max_record_len.saturating_add(delimiter.len() - 1) {
+                            break Err(Error::EMSGSIZE);
+                        }
+                        break Ok(());
+                    }
+                }
+            }
+        }
+    };
+    buffer.data.drain(..start);
+    result
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
@@ -5,6 +5,7 @@
 use bitflags::bitflags;
 use libc::{c_int, c_long, c_short};
 
+use std::collections::BTreeMap;
 use std::ffi;
 use std::fmt;
 use std::marker::PhantomData;
@@ -15,7 +16,8 @@ use std::os::unix::io::{AsRawFd, RawFd as UnixRawFd};
 use std::os::windows::io::{AsRawSocket, RawSocket};
 use std::result;
 use std::string::FromUtf8Error;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::{mem, ptr, str};
 
 use zmq_sys::{errno, RawFd};
@@ -30,11 +32,75 @@ macro_rules! zmq_try {
     }}
 }
 
+#[cfg(unix)]
+pub mod asynchronous;
+pub mod auth;
+mod budget;
+mod builder;
+mod certificate;
+mod chaos;
+mod codec;
+mod counter;
+mod dedup;
+pub mod device;
+mod endpoint;
+#[cfg(all(unix, feature = "mio"))]
+mod evented;
+pub mod framing;
 mod message;
+mod metrics;
+mod monitor;
+mod poller;
+mod priority;
+mod ratelimit;
+mod reconnect;
+mod record;
+mod rng;
+mod shutdown;
+mod snapshot;
 mod sockopt;
-
+mod spool;
+pub mod stream;
+mod subscriptions;
+#[cfg(feature = "draft-api")]
+mod timers;
+#[cfg(feature = "topology")]
+pub mod topology;
+mod ttl;
+mod typed;
+
+pub use crate::budget::{BudgetReport, MemoryBudget};
+pub use crate::builder::SocketBuilder;
+pub use crate::certificate::Certificate;
+pub use crate::chaos::{ChaosConfig, ChaosProxy};
+pub use crate::counter::AtomicCounter;
+pub use crate::dedup::Deduplicator;
+pub use crate::endpoint::{AsEndpoint, Endpoint, EndpointError};
 use crate::message::msg_ptr;
 pub use crate::message::Message;
+pub use crate::metrics::{MetricsRegistry, SocketStats};
+pub use crate::monitor::{EventDetail, MonitorEvent};
+pub use crate::poller::Poller;
+pub use crate::priority::PriorityReceiver;
+pub use crate::ratelimit::{OnExcess, RateLimit, RateLimitedSocket};
+pub use crate::reconnect::{Backoff, ReconnectPolicy, ReconnectWatcher};
+pub use crate::record::{Record, Recorder, Replayer};
+pub use crate::shutdown::{Shutdown, ShutdownReport};
+pub use crate::snapshot::{OptionChange, OptionSnapshot, OptionValue};
+pub use crate::sockopt::{GetSockOpt, SetSockOpt};
+pub use crate::spool::Spooler;
+pub use crate::subscriptions::{SubscriptionChange, SubscriptionTracker};
+#[cfg(feature = "draft-api")]
+pub use crate::timers::Timers;
+pub use crate::ttl::{send_with_ttl, ExpiryFilter, TtlStamp, TTL_STAMP_LEN};
+#[cfg(feature = "draft-api")]
+pub use crate::typed::{
+    ClientSocket, DishSocket, GatherSocket, RadioSocket, ScatterSocket, ServerSocket,
+};
+pub use crate::typed::{
+    DealerSocket, PairSocket, PubSocket, PullSocket, PushSocket, RepSocket, ReqSocket, RouteGuard,
+    RouterSocket, StreamSocket, SubSocket, XPubSocket, XSubSocket,
+};
 pub use crate::SocketType::*;
 
 /// `zmq`-specific Result type.
@@ -56,6 +122,18 @@ pub enum SocketType {
     XPUB,
     XSUB,
     STREAM,
+    #[cfg(feature = "draft-api")]
+    SERVER,
+    #[cfg(feature = "draft-api")]
+    CLIENT,
+    #[cfg(feature = "draft-api")]
+    RADIO,
+    #[cfg(feature = "draft-api")]
+    DISH,
+    #[cfg(feature = "draft-api")]
+    GATHER,
+    #[cfg(feature = "draft-api")]
+    SCATTER,
 }
 
 impl SocketType {
@@ -73,11 +151,25 @@ impl SocketType {
             XPUB => zmq_sys::ZMQ_XPUB,
             XSUB => zmq_sys::ZMQ_XSUB,
             STREAM => zmq_sys::ZMQ_STREAM,
+            #[cfg(feature = "draft-api")]
+            SERVER => zmq_sys::ZMQ_SERVER,
+            #[cfg(feature = "draft-api")]
+            CLIENT => zmq_sys::ZMQ_CLIENT,
+            #[cfg(feature = "draft-api")]
+            RADIO => zmq_sys::ZMQ_RADIO,
+            #[cfg(feature = "draft-api")]
+            DISH => zmq_sys::ZMQ_DISH,
+            #[cfg(feature = "draft-api")]
+            GATHER => zmq_sys::ZMQ_GATHER,
+            #[cfg(feature = "draft-api")]
+            SCATTER => zmq_sys::ZMQ_SCATTER,
         };
         raw as c_int
     }
-    fn from_raw(raw: c_int) -> SocketType {
-        match raw as u32 {
+    // Returns `None` for types that are unknown, or only supported with the
+    // `draft-api` feature.
+    fn try_from_raw(raw: c_int) -> Option<SocketType> {
+        let socket_type = match raw as u32 {
             zmq_sys::ZMQ_PAIR => PAIR,
             zmq_sys::ZMQ_PUB => PUB,
             zmq_sys::ZMQ_SUB => SUB,
@@ -90,8 +182,21 @@ impl SocketType {
             zmq_sys::ZMQ_XPUB => XPUB,
             zmq_sys::ZMQ_XSUB => XSUB,
             zmq_sys::ZMQ_STREAM => STREAM,
-            _ => panic!("socket type is out of range!"),
-        }
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_SERVER => SERVER,
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_CLIENT => CLIENT,
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_RADIO => RADIO,
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_DISH => DISH,
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_GATHER => GATHER,
+            #[cfg(feature = "draft-api")]
+            zmq_sys::ZMQ_SCATTER => SCATTER,
+            _ => return None,
+        };
+        Some(socket_type)
     }
 }
 
@@ -123,10 +228,20 @@ impl SocketEvent {
         self as u16
     }
 
-    // TODO: this should not need to be public
+    /// Convert a raw event type, e.g. as received from a monitor socket.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the event type is unknown, e.g. one introduced by a newer
+    /// `libzmq`; see `try_from_raw`.
     pub fn from_raw(raw: u16) -> SocketEvent {
+        SocketEvent::try_from_raw(raw).unwrap_or_else(|| panic!("unknown event type {}", raw))
+    }
+
+    /// Convert a raw event type, returning `None` if it is unknown.
+    pub fn try_from_raw(raw: u16) -> Option<SocketEvent> {
         use SocketEvent::*;
-        match u32::from(raw) {
+        let event = match u32::from(raw) {
             zmq_sys::ZMQ_EVENT_CONNECTED => CONNECTED,
             zmq_sys::ZMQ_EVENT_CONNECT_DELAYED => CONNECT_DELAYED,
             zmq_sys::ZMQ_EVENT_CONNECT_RETRIED => CONNECT_RETRIED,
@@ -143,8 +258,9 @@ impl SocketEvent {
             zmq_sys::ZMQ_EVENT_HANDSHAKE_FAILED_PROTOCOL => HANDSHAKE_FAILED_PROTOCOL,
             zmq_sys::ZMQ_EVENT_HANDSHAKE_FAILED_AUTH => HANDSHAKE_FAILED_AUTH,
             zmq_sys::ZMQ_EVENT_ALL => ALL,
-            x => panic!("unknown event type {}", x),
-        }
+            _ => return None,
+        };
+        Some(event)
     }
 }
 
@@ -198,6 +314,10 @@ pub enum Error {
     ENOCOMPATPROTO,
     ETERM,
     EMTHREAD,
+
+    /// An error code this crate does not know, e.g. one introduced by a
+    /// newer `libzmq`; `message` still returns its description.
+    EUNKNOWN(i32),
 }
 
 impl Error {
@@ -232,6 +352,8 @@ impl Error {
             Error::ENOCOMPATPROTO => errno::ENOCOMPATPROTO,
             Error::ETERM => errno::ETERM,
             Error::EMTHREAD => errno::EMTHREAD,
+
+            Error::EUNKNOWN(raw) => raw,
         }
     }
 
@@ -293,19 +415,28 @@ impl Error {
             errno::ETERM => Error::ETERM,
             errno::EMTHREAD => Error::EMTHREAD,
 
-            x => unsafe {
-                let s = zmq_sys::zmq_strerror(x);
-                panic!(
-                    "unknown error [{}]: {}",
-                    x,
-                    str::from_utf8(ffi::CStr::from_ptr(s).to_bytes()).unwrap()
-                )
-            },
+            x => Error::EUNKNOWN(x),
+        }
+    }
+
+    /// Classify the error, see `ErrorKind`.
+    pub fn kind(self) -> ErrorKind {
+        match self {
+            Error::EAGAIN => ErrorKind::WouldBlock,
+            Error::EINTR => ErrorKind::Interrupted,
+            Error::ETERM => ErrorKind::Terminated,
+            Error::EFSM | Error::EPROTO | Error::ENOCOMPATPROTO => ErrorKind::Protocol,
+            Error::ENOMEM | Error::EMFILE | Error::ENOBUFS | Error::EMTHREAD => ErrorKind::Resource,
+            Error::ENOTSUP | Error::EPROTONOSUPPORT => ErrorKind::Unsupported,
+            _ => ErrorKind::Other,
         }
     }
 
     /// Returns the error message provided by 0MQ.
     pub fn message(self) -> &'static str {
+        if let Error::EUNKNOWN(raw) = self {
+            return unknown_error_message(raw);
+        }
         unsafe {
             let s = zmq_sys::zmq_strerror(self.to_raw());
             let v: &'static [u8] = mem::transmute(ffi::CStr::from_ptr(s).to_bytes());
@@ -314,6 +445,36 @@ impl Error {
     }
 }
 
+/// A coarse classification of `Error`s, by how a caller would typically
+/// react to them, see `Error::kind`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// The operation would have blocked, or its timeout expired
+    /// (`EAGAIN`). It can be retried later, e.g. once `poll` reports the
+    /// socket as ready.
+    WouldBlock,
+    /// A signal interrupted the operation (`EINTR`). It can be retried
+    /// right away, see `Socket::set_on_interrupt`.
+    Interrupted,
+    /// The context was terminated (`ETERM`). The socket should be closed,
+    /// as no operation on it will succeed again.
+    Terminated,
+    /// The operation is not valid in the current state of the socket's
+    /// messaging pattern, e.g. a `REQ` socket sending twice (`EFSM`), or a
+    /// peer violated the protocol.
+    Protocol,
+    /// A resource limit was reached, e.g. memory, file descriptors or
+    /// application threads. Retrying may succeed once resources are freed.
+    Resource,
+    /// The operation, option or transport is not supported, e.g. an option
+    /// that does not apply to the socket's type (`ENOTSUP`), or a transport
+    /// the 0MQ library was built without (`EPROTONOSUPPORT`).
+    Unsupported,
+    /// Any other error, typically caused by invalid arguments or
+    /// configuration, which retrying does not fix.
+    Other,
+}
+
 impl std::error::Error for Error {
     fn description(&self) -> &str {
         self.message()
@@ -347,16 +508,28 @@ impl From<Error> for std::io::Error {
             Error::EAGAIN => ErrorKind::WouldBlock,
             Error::EINVAL => ErrorKind::InvalidInput,
             Error::EINTR => ErrorKind::Interrupted,
+            Error::ENOMEM => ErrorKind::OutOfMemory,
+            Error::ENOTSUP | Error::EPROTONOSUPPORT => ErrorKind::Unsupported,
             _ => ErrorKind::Other,
         };
-        // TODO: With rust 1.14 and up there is an optimization
-        // opportunity using `std::io::Error: From<ErrorKind>` when
-        // `kind != Other`. We should do that once 1.14 has been
-        // stable for a bit.
+        // The `zmq::Error` is kept as the inner error, so that it can be
+        // recovered with `get_ref` and `downcast_ref`.
         std::io::Error::new(kind, error)
     }
 }
 
+// The description of an unknown error code may live in a buffer that is
+// reused by the next call, so it is copied, once per code.
+fn unknown_error_message(raw: i32) -> &'static str {
+    static MESSAGES: Mutex<BTreeMap<i32, &'static str>> = Mutex::new(BTreeMap::new());
+
+    let mut messages = MESSAGES.lock().unwrap_or_else(|e| e.into_inner());
+    messages.entry(raw).or_insert_with(|| {
+        let s = unsafe { ffi::CStr::from_ptr(zmq_sys::zmq_strerror(raw)) };
+        Box::leak(s.to_string_lossy().into_owned().into_boxed_str())
+    })
+}
+
 fn errno_to_error() -> Error {
     Error::from_raw(unsafe { zmq_sys::zmq_errno() })
 }
@@ -376,6 +549,7 @@ pub fn version() -> (i32, i32, i32) {
 
 struct RawContext {
     ctx: *mut c_void,
+    default_linger: Mutex<Option<i32>>,
 }
 
 impl RawContext {
@@ -428,6 +602,7 @@ impl Context {
         Context {
             raw: Arc::new(RawContext {
                 ctx: unsafe { zmq_sys::zmq_ctx_new() },
+                default_linger: Mutex::new(None),
             }),
         }
     }
@@ -447,6 +622,33 @@ impl Context {
         Ok(())
     }
 
+    /// Get the maximum number of sockets allowed on the context.
+    pub fn get_max_sockets(&self) -> Result<i32> {
+        let rc =
+            zmq_try!(unsafe { zmq_sys::zmq_ctx_get(self.raw.ctx, zmq_sys::ZMQ_MAX_SOCKETS as _) });
+        Ok(rc as i32)
+    }
+
+    /// Set the maximum number of sockets allowed on the context, which is
+    /// at most `get_socket_limit()`.
+    ///
+    /// Once the maximum is reached, creating further sockets fails with
+    /// `Error::EMFILE`. The value only takes effect if it is set before the
+    /// first socket is created.
+    pub fn set_max_sockets(&self, value: i32) -> Result<()> {
+        zmq_try!(unsafe {
+            zmq_sys::zmq_ctx_set(self.raw.ctx, zmq_sys::ZMQ_MAX_SOCKETS as _, value)
+        });
+        Ok(())
+    }
+
+    /// Get the largest number of sockets that `set_max_sockets` accepts.
+    pub fn get_socket_limit(&self) -> Result<i32> {
+        let rc =
+            zmq_try!(unsafe { zmq_sys::zmq_ctx_get(self.raw.ctx, zmq_sys::ZMQ_SOCKET_LIMIT as _) });
+        Ok(rc as i32)
+    }
+
     /// Create a new socket.
     ///
     /// Note that the returned socket keeps a an `Arc` reference to
@@ -459,11 +661,55 @@ impl Context {
             return Err(errno_to_error());
         }
 
-        Ok(Socket {
+        let socket = Socket {
             sock,
             context: Some(self.clone()),
             owned: true,
-        })
+            multipart_limits: Mutex::new(None),
+            on_interrupt: Mutex::new(OnInterrupt::Fail),
+        };
+        if let Some(linger) = self.get_default_linger() {
+            socket.set_linger(linger)?;
+        }
+        Ok(socket)
+    }
+
+    /// Get the linger period applied to new sockets, if any.
+    pub fn get_default_linger(&self) -> Option<i32> {
+        *self.raw.default_linger.lock().unwrap()
+    }
+
+    /// Set the `ZMQ_LINGER` value applied to every socket subsequently
+    /// created from this context (and its clones).
+    ///
+    /// `libzmq` defaults to an infinite linger period, which makes
+    /// terminating the context hang as long as any socket still has
+    /// undelivered messages. Setting a default of `Some(0)`, or a small
+    /// bound, avoids that without having to configure each socket
+    /// individually. Sockets that already exist are not affected, and the
+    /// value can still be overridden per socket via `Socket::set_linger`.
+    /// `None` restores the `libzmq` default.
+    pub fn set_default_linger(&self, linger: Option<i32>) {
+        *self.raw.default_linger.lock().unwrap() = linger;
+    }
+
+    /// Start building a new, configured socket.
+    ///
+    /// See `SocketBuilder` for details.
+    pub fn build_socket(&self, socket_type: SocketType) -> SocketBuilder<'_> {
+        SocketBuilder::new(self, socket_type)
+    }
+
+    /// Shut down the context, making blocking operations on its sockets in
+    /// any thread fail with `Error::ETERM`, without waiting for the sockets
+    /// to be closed.
+    ///
+    /// Subsequent operations other than closing also fail with `ETERM`,
+    /// and so does creating new sockets. The context itself is only
+    /// terminated once it and all its sockets have been dropped.
+    pub fn shutdown(&self) -> Result<()> {
+        zmq_try!(unsafe { zmq_sys::zmq_ctx_shutdown(self.raw.ctx) });
+        Ok(())
     }
 
     /// Try to destroy the context. This is different than the destructor; the
@@ -487,14 +733,98 @@ pub struct Socket {
     #[allow(dead_code)]
     context: Option<Context>,
     owned: bool,
+    // Behind mutexes rather than in cells, since the thread-safe socket
+    // types of the `typed` module share a `Socket` between threads.
+    multipart_limits: Mutex<Option<MultipartLimits>>,
+    on_interrupt: Mutex<OnInterrupt>,
 }
 
 unsafe impl Send for Socket {}
 
+/// Limits enforced by `Socket::recv_multipart`.
+///
+/// A peer can send a multipart message with an arbitrary number of parts,
+/// all of which `recv_multipart` would otherwise collect in memory. Note that
+/// the size of each individual part can be bounded by `libzmq` itself, via
+/// the `ZMQ_MAXMSGSIZE` option.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MultipartLimits {
+    /// The maximum number of parts per message.
+    pub max_parts: Option<usize>,
+    /// The maximum total size of all parts of a message, in bytes.
+    pub max_bytes: Option<usize>,
+}
+
+impl MultipartLimits {
+    fn exceeded(&self, parts: usize, bytes: usize) -> bool {
+        matches!(self.max_parts, Some(max) if parts > max)
+            || matches!(self.max_bytes, Some(max) if bytes > max)
+    }
+}
+
+/// What a socket's blocking calls do when they are interrupted by a signal,
+/// see `Socket::set_on_interrupt`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnInterrupt {
+    /// Fail with `Error::EINTR`, so that the caller can react to the
+    /// signal, e.g. by shutting down. This is the default.
+    #[default]
+    Fail,
+    /// Restart the call, e.g. to ignore a `SIGCHLD` from a subprocess.
+    Retry,
+}
+
 impl Drop for Socket {
     fn drop(&mut self) {
-        if self.owned && unsafe { zmq_sys::zmq_close(self.sock) } == -1 {
-            panic!("{}", errno_to_error());
+        // Panicking here would abort the process if the socket is dropped
+        // while unwinding; use `close` to handle the error instead.
+        if let Err(e) = self.close_raw() {
+            log::warn!("failed to close socket: {}", e);
+        }
+    }
+}
+
+/// The outcome of `Socket::close_with_timeout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CloseOutcome {
+    /// All pending messages were delivered, and the context was terminated.
+    Flushed,
+    /// The timeout expired, and the messages that were still pending were
+    /// discarded. This includes a zero timeout, since it is not known
+    /// whether any messages were pending.
+    TimedOut,
+    /// The context is still in use, and keeps delivering the pending
+    /// messages, if any, until the timeout expires.
+    Pending,
+}
+
+/// A binding created by `Socket::bind_scoped`, which is undone when the
+/// guard is dropped.
+pub struct ScopedBind<'a> {
+    socket: &'a Socket,
+    endpoint: String,
+}
+
+impl<'a> ScopedBind<'a> {
+    /// Return the resolved endpoint the socket is bound to, e.g. with the
+    /// port chosen by the system.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Unbind the socket now, returning any error.
+    pub fn unbind(mut self) -> Result<()> {
+        let endpoint = mem::take(&mut self.endpoint);
+        self.socket.unbind(&endpoint)
+    }
+}
+
+impl<'a> Drop for ScopedBind<'a> {
+    fn drop(&mut self) {
+        if !self.endpoint.is_empty() {
+            // The socket may have been unbound behind our back, or the
+            // context terminated, neither of which is worth panicking over.
+            let _ = self.socket.unbind(&self.endpoint);
         }
     }
 }
@@ -530,6 +860,7 @@ macro_rules! sockopt_setter {
     ) => {
         $(#[$meta])*
         pub fn $setter(&self, value: $ty) -> Result<()> {
+            self.check_option(zmq_sys::$constant_name as c_int)?;
             <$ty as sockopt::Setter>::set(self.sock, zmq_sys::$constant_name as c_int, value)
         }
     };
@@ -596,8 +927,14 @@ where
 {
     fn send(self, socket: &Socket, flags: i32) -> Result<()> {
         let mut msg = self.into();
-        zmq_try!(unsafe { zmq_sys::zmq_msg_send(msg_ptr(&mut msg), socket.sock, flags as c_int) });
-        Ok(())
+        // The message is only consumed once it is queued, so it can be
+        // passed again after an interruption.
+        socket.retry_interrupted(|| {
+            zmq_try!(unsafe {
+                zmq_sys::zmq_msg_send(msg_ptr(&mut msg), socket.sock, flags as c_int)
+            });
+            Ok(())
+        })
     }
 }
 
@@ -628,6 +965,152 @@ impl Socket {
             sock,
             context: None,
             owned: true,
+            multipart_limits: Mutex::new(None),
+            on_interrupt: Mutex::new(OnInterrupt::Fail),
+        }
+    }
+
+    /// Close the socket, returning any error.
+    ///
+    /// Dropping the socket closes it as well, but can only log an error.
+    /// Pending messages are delivered in the background for the
+    /// `ZMQ_LINGER` period, see `close_with_linger`.
+    pub fn close(mut self) -> Result<()> {
+        self.close_raw()
+    }
+
+    /// Set the `ZMQ_LINGER` period to `linger` milliseconds, then close the
+    /// socket.
+    ///
+    /// This is the recommended way to shut a socket down: with the default,
+    /// infinite linger period, terminating the context blocks until all
+    /// pending messages have been delivered, which may be never.
+    ///
+    /// Note that `libzmq` does not report whether pending messages were
+    /// actually flushed: closing returns immediately, and delivery continues
+    /// in the background for at most `linger` milliseconds, a bound which is
+    /// enforced when the context is terminated. The returned error only
+    /// indicates that setting the option or closing the socket failed.
+    pub fn close_with_linger(mut self, linger: i32) -> Result<()> {
+        self.set_linger(linger)?;
+        self.close_raw()
+    }
+
+    /// Close the socket, giving pending messages at most `timeout` to be
+    /// delivered, and report whether they were.
+    ///
+    /// If the socket holds the last handle to its context, e.g. during
+    /// process shutdown after all other handles and sockets were dropped,
+    /// the context is terminated as well. This blocks until the pending
+    /// messages are delivered or the timeout expires, which tells which of
+    /// the two happened. Otherwise, the context keeps delivering messages in
+    /// the background, as with `close_with_linger`, and the outcome is
+    /// unknown.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let ctx = zmq::Context::new();
+    /// let sender = ctx.socket(zmq::PUSH).unwrap();
+    /// sender.bind("inproc://close-example").unwrap();
+    /// let receiver = ctx.socket(zmq::PULL).unwrap();
+    /// receiver.connect("inproc://close-example").unwrap();
+    /// sender.send("last words", 0).unwrap();
+    ///
+    /// // Still referenced by `ctx` and `receiver`.
+    /// let outcome = sender.close_with_timeout(Duration::from_secs(1)).unwrap();
+    /// assert_eq!(outcome, zmq::CloseOutcome::Pending);
+    /// assert_eq!(receiver.recv_bytes(0).unwrap(), b"last words");
+    /// ```
+    pub fn close_with_timeout(mut self, timeout: Duration) -> Result<CloseOutcome> {
+        self.set_linger(timeout.as_millis().min(i32::MAX as u128) as i32)?;
+        let context = self.context.take();
+        self.close_raw()?;
+        let raw = match context.map(|context| Arc::try_unwrap(context.raw)) {
+            Some(Ok(raw)) => raw,
+            _ => return Ok(CloseOutcome::Pending),
+        };
+        let start = Instant::now();
+        // Terminating the context returns once the socket's messages are
+        // delivered, or its linger period expires.
+        drop(raw);
+        if start.elapsed() < timeout {
+            Ok(CloseOutcome::Flushed)
+        } else {
+            Ok(CloseOutcome::TimedOut)
+        }
+    }
+
+    fn close_raw(&mut self) -> Result<()> {
+        if self.owned {
+            self.owned = false;
+            zmq_try!(unsafe { zmq_sys::zmq_close(self.sock) });
+        }
+        Ok(())
+    }
+
+    /// Get the value of the socket option with the given `ZMQ_*` constant,
+    /// e.g. for an option added in a newer `libzmq` that has no dedicated
+    /// accessor yet.
+    ///
+    /// ```
+    /// let ctx = zmq::Context::new();
+    /// let socket = ctx.socket(zmq::PUSH).unwrap();
+    /// socket.setsockopt(zmq_sys::ZMQ_SNDHWM as i32, 10).unwrap();
+    /// assert_eq!(socket.getsockopt::<i32>(zmq_sys::ZMQ_SNDHWM as i32), Ok(10));
+    /// ```
+    ///
+    /// The type must match the option: `libzmq` fails with `Error::EINVAL`
+    /// e.g. when reading an `i64` option as `i32`. String valued options are
+    /// read as `Vec<u8>`, including the terminating nul byte.
+    pub fn getsockopt<T: GetSockOpt>(&self, option: i32) -> Result<T> {
+        sockopt::get(self.sock, option as c_int)
+    }
+
+    /// Set the socket option with the given `ZMQ_*` constant, see
+    /// `getsockopt`.
+    ///
+    /// Like the dedicated setters, this fails with `Error::ENOTSUP`, of kind
+    /// `ErrorKind::Unsupported`, for options known not to apply to the
+    /// socket's type.
+    pub fn setsockopt<T: SetSockOpt>(&self, option: i32, value: T) -> Result<()> {
+        self.check_option(option as c_int)?;
+        sockopt::set(self.sock, option as c_int, value)
+    }
+
+    /// Read the raw value of a socket option into `value`, returning the
+    /// length of the value.
+    ///
+    /// This passes `value` to `zmq_getsockopt` as is, so it must have
+    /// exactly the size of a numeric option, and room for the whole value of
+    /// a byte or string valued one.
+    pub fn getsockopt_raw(&self, option: i32, value: &mut [u8]) -> Result<usize> {
+        let mut size = value.len();
+        zmq_try!(unsafe {
+            zmq_sys::zmq_getsockopt(
+                self.sock,
+                option as c_int,
+                value.as_mut_ptr() as *mut c_void,
+                &mut size,
+            )
+        });
+        Ok(size)
+    }
+
+    /// Set the raw value of a socket option, which is passed to
+    /// `zmq_setsockopt` as is, i.e. numeric values must be given in native
+    /// byte order.
+    pub fn setsockopt_raw(&self, option: i32, value: &[u8]) -> Result<()> {
+        sockopt::set(self.sock, option as c_int, value)
+    }
+
+    // Fail with `ENOTSUP` when setting an option that does not apply to the
+    // socket's type, which `libzmq` either rejects with the less telling
+    // `EINVAL`, or silently ignores.
+    fn check_option(&self, option: c_int) -> Result<()> {
+        match sockopt::applicable_types(option) {
+            Some(types) if !types.contains(&self.get_socket_type()?) => Err(Error::ENOTSUP),
+            _ => Ok(()),
         }
     }
 
@@ -641,34 +1124,97 @@ impl Socket {
     }
 
     /// Accept connections on a socket.
-    pub fn bind(&self, endpoint: &str) -> Result<()> {
-        let c_str = ffi::CString::new(endpoint.as_bytes()).unwrap();
+    ///
+    /// Besides `tcp://`, `inproc://` and, on most platforms, `ipc://`,
+    /// endpoints can use the optional transports `tipc://`, `vmci://`,
+    /// `pgm://` and `epgm://`, if the 0MQ library supports them, see
+    /// `has()`. The endpoint is given as a string, or as an `Endpoint`.
+    pub fn bind(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()> {
+        let c_str = ffi::CString::new(endpoint.as_endpoint().as_bytes()).unwrap();
         zmq_try!(unsafe { zmq_sys::zmq_bind(self.sock, c_str.as_ptr()) });
         Ok(())
     }
 
+    /// Bind the socket, returning a guard that unbinds it when dropped.
+    ///
+    /// The endpoint is resolved via `ZMQ_LAST_ENDPOINT` right after
+    /// binding, so that wildcard addresses and ports, e.g. in
+    /// `tcp://127.0.0.1:*`, are released as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let ctx = zmq::Context::new();
+    /// let socket = ctx.socket(zmq::REP).unwrap();
+    /// {
+    ///     let binding = socket.bind_scoped("tcp://127.0.0.1:*").unwrap();
+    ///     println!("listening on {}", binding.endpoint());
+    /// }
+    /// // The port has been released again.
+    /// ```
+    pub fn bind_scoped(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<ScopedBind<'_>> {
+        let endpoint = endpoint.as_endpoint();
+        self.bind(&*endpoint)?;
+        let resolved = match self.get_last_endpoint() {
+            Ok(Ok(resolved)) => resolved,
+            Ok(Err(_)) | Err(_) => {
+                // Without the resolved endpoint, fall back to the one given,
+                // which is only ambiguous for wildcards.
+                endpoint.into_owned()
+            }
+        };
+        Ok(ScopedBind {
+            socket: self,
+            endpoint: resolved,
+        })
+    }
+
     /// Stop accepting connections on a socket
-    pub fn unbind(&self, endpoint: &str) -> Result<()> {
-        let c_str = ffi::CString::new(endpoint.as_bytes()).unwrap();
+    pub fn unbind(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()> {
+        let c_str = ffi::CString::new(endpoint.as_endpoint().as_bytes()).unwrap();
         zmq_try!(unsafe { zmq_sys::zmq_unbind(self.sock, c_str.as_ptr()) });
         Ok(())
     }
 
     /// Connect a socket.
-    pub fn connect(&self, endpoint: &str) -> Result<()> {
-        let c_str = ffi::CString::new(endpoint.as_bytes()).unwrap();
+    pub fn connect(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()> {
+        let c_str = ffi::CString::new(endpoint.as_endpoint().as_bytes()).unwrap();
         zmq_try!(unsafe { zmq_sys::zmq_connect(self.sock, c_str.as_ptr()) });
         Ok(())
     }
 
     /// Disconnect a previously connected socket
-    pub fn disconnect(&self, endpoint: &str) -> Result<()> {
-        let c_str = ffi::CString::new(endpoint.as_bytes()).unwrap();
+    pub fn disconnect(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()> {
+        let c_str = ffi::CString::new(endpoint.as_endpoint().as_bytes()).unwrap();
         zmq_try!(unsafe { zmq_sys::zmq_disconnect(self.sock, c_str.as_ptr()) });
         Ok(())
     }
 
+    /// Join a group, to receive the messages sent to it on a `DISH` socket.
+    ///
+    /// Group names are limited to `ZMQ_GROUP_MAX_LENGTH` (255) bytes.
+    #[cfg(feature = "draft-api")]
+    pub fn join(&self, group: &str) -> Result<()> {
+        let c_str = ffi::CString::new(group.as_bytes()).unwrap();
+        zmq_try!(unsafe { zmq_sys::zmq_join(self.sock, c_str.as_ptr()) });
+        Ok(())
+    }
+
+    /// Leave a group previously joined with `join`.
+    #[cfg(feature = "draft-api")]
+    pub fn leave(&self, group: &str) -> Result<()> {
+        let c_str = ffi::CString::new(group.as_bytes()).unwrap();
+        zmq_try!(unsafe { zmq_sys::zmq_leave(self.sock, c_str.as_ptr()) });
+        Ok(())
+    }
+
     /// Configure the socket for monitoring
+    ///
+    /// `events` is a mask of `SocketEvent` values, e.g.
+    /// `SocketEvent::ALL.to_raw() as i32`. The events are published on a
+    /// `PAIR` socket bound to `monitor_endpoint`, which must be an `inproc`
+    /// endpoint; connect another `PAIR` socket to it and read the events
+    /// using `recv_monitor_event`.
     pub fn monitor(&self, monitor_endpoint: &str, events: i32) -> Result<()> {
         let c_str = ffi::CString::new(monitor_endpoint.as_bytes()).unwrap();
         zmq_try!(unsafe {
@@ -680,7 +1226,9 @@ impl Socket {
     /// Send a message.
     ///
     /// Due to the provided `From` implementations, this works for
-    /// `&[u8]`, `Vec<u8>` and `&str` `Message` itself.
+    /// `&[u8]`, `Vec<u8>`, `&str` and `Message` itself. A `Message` is handed
+    /// to `zmq_msg_send` as is, so forwarding received frames does not copy
+    /// them; neither does sending a `Vec<u8>`, whose buffer is taken over.
     pub fn send<T>(&self, data: T, flags: i32) -> Result<()>
     where
         T: Sendable,
@@ -688,6 +1236,44 @@ impl Socket {
         data.send(self, flags)
     }
 
+    /// Send a message, blocking until it is accepted, and report while the
+    /// socket is blocked at its high-water mark.
+    ///
+    /// If the message cannot be queued right away, `on_block` is called
+    /// with the time spent waiting so far, first with zero and then about
+    /// every 100 milliseconds until the socket becomes writable. This makes
+    /// backpressure visible to metrics or logging, instead of silently
+    /// stalling the thread. `ZMQ_SNDTIMEO` is not taken into account.
+    pub fn send_blocking_with_progress<T, F>(
+        &self,
+        data: T,
+        flags: i32,
+        mut on_block: F,
+    ) -> Result<()>
+    where
+        T: Into<Message>,
+        F: FnMut(Duration),
+    {
+        const PROGRESS_INTERVAL_MS: i64 = 100;
+
+        let mut msg = data.into();
+        let flags = flags | DONTWAIT;
+        let mut blocked_since = None;
+        loop {
+            let rc = unsafe { zmq_sys::zmq_msg_send(msg_ptr(&mut msg), self.sock, flags as c_int) };
+            if rc != -1 {
+                return Ok(());
+            }
+            match errno_to_error() {
+                Error::EAGAIN => {}
+                e => return Err(e),
+            }
+            let since = *blocked_since.get_or_insert_with(Instant::now);
+            on_block(since.elapsed());
+            self.poll(POLLOUT, PROGRESS_INTERVAL_MS)?;
+        }
+    }
+
     /// Send a `Message` message.
     #[deprecated(since = "0.9.0", note = "Use `send` instead")]
     pub fn send_msg(&self, msg: Message, flags: i32) -> Result<()> {
@@ -699,6 +1285,26 @@ impl Socket {
         self.send(data, flags)
     }
 
+    /// Send a multipart message, setting `SNDMORE` on all but the last
+    /// part. `flags` is applied to every part.
+    ///
+    /// An empty iterator sends nothing. If sending a part fails, the parts
+    /// sent before it remain queued as an incomplete message, which is only
+    /// delivered once a final part without `SNDMORE` follows.
+    ///
+    /// ```
+    /// let ctx = zmq::Context::new();
+    /// let sender = ctx.socket(zmq::PAIR).unwrap();
+    /// let receiver = ctx.socket(zmq::PAIR).unwrap();
+    /// receiver.bind("inproc://send-multipart-example").unwrap();
+    /// sender.connect("inproc://send-multipart-example").unwrap();
+    ///
+    /// sender.send_multipart(["peer", "", "payload"], 0).unwrap();
+    /// assert_eq!(
+    ///     receiver.recv_multipart(0).unwrap(),
+    ///     vec![b"peer".to_vec(), vec![], b"payload".to_vec()]
+    /// );
+    /// ```
     pub fn send_multipart<I, T>(&self, iter: I, flags: i32) -> Result<()>
     where
         I: IntoIterator<Item = T>,
@@ -722,19 +1328,44 @@ impl Socket {
     /// Receive a message into a `Message`. The length passed to zmq_msg_recv
     /// is the length of the buffer.
     pub fn recv(&self, msg: &mut Message, flags: i32) -> Result<()> {
-        zmq_try!(unsafe { zmq_sys::zmq_msg_recv(msg_ptr(msg), self.sock, flags as c_int) });
-        Ok(())
+        self.retry_interrupted(|| {
+            zmq_try!(unsafe { zmq_sys::zmq_msg_recv(msg_ptr(msg), self.sock, flags as c_int) });
+            Ok(())
+        })
     }
 
     /// Receive bytes into a slice. The length passed to `zmq_recv` is the length of the slice. The
     /// return value is the number of bytes in the message, which may be larger than the length of
     /// the slice, indicating truncation.
+    ///
+    /// Unlike the other receive methods, this does not allocate, so a single buffer can be reused
+    /// across receives:
+    ///
+    /// ```
+    /// let ctx = zmq::Context::new();
+    /// let sender = ctx.socket(zmq::PAIR).unwrap();
+    /// let receiver = ctx.socket(zmq::PAIR).unwrap();
+    /// receiver.bind("inproc://recv-into-example").unwrap();
+    /// sender.connect("inproc://recv-into-example").unwrap();
+    /// sender.send("short", 0).unwrap();
+    /// sender.send("rather long", 0).unwrap();
+    ///
+    /// let mut buf = [0; 8];
+    /// let len = receiver.recv_into(&mut buf, 0).unwrap();
+    /// assert_eq!(&buf[..len], b"short");
+    ///
+    /// let len = receiver.recv_into(&mut buf, 0).unwrap();
+    /// assert!(len > buf.len()); // truncated
+    /// assert_eq!(&buf[..], b"rather l");
+    /// ```
     pub fn recv_into(&self, bytes: &mut [u8], flags: i32) -> Result<usize> {
         let bytes_ptr = bytes.as_mut_ptr() as *mut c_void;
-        let rc = zmq_try!(unsafe {
-            zmq_sys::zmq_recv(self.sock, bytes_ptr, bytes.len(), flags as c_int)
-        });
-        Ok(rc as usize)
+        self.retry_interrupted(|| {
+            let rc = zmq_try!(unsafe {
+                zmq_sys::zmq_recv(self.sock, bytes_ptr, bytes.len(), flags as c_int)
+            });
+            Ok(rc as usize)
+        })
     }
 
     /// Receive a message into a fresh `Message`.
@@ -762,13 +1393,36 @@ impl Socket {
     /// Note that this will allocate a new vector for each message part; for
     /// many applications it will be possible to process the different parts
     /// sequentially and reuse allocations that way.
+    ///
+    /// If the message exceeds the limits set via `set_multipart_limits`,
+    /// its remaining parts are discarded and `Error::EMSGSIZE` is returned.
     pub fn recv_multipart(&self, flags: i32) -> Result<Vec<Vec<u8>>> {
-        let mut parts: Vec<Vec<u8>> = vec![];
+        self.recv_multipart_msg(flags)
+            .map(|parts| parts.iter().map(|msg| msg.to_vec()).collect())
+    }
+
+    /// Receive all parts of a multipart message as `Message`s, without
+    /// copying their contents.
+    ///
+    /// Once this returns, no part of the message is left unread, so the next
+    /// receive starts at a message boundary. The limits set via
+    /// `set_multipart_limits` apply as for `recv_multipart`.
+    pub fn recv_multipart_msg(&self, flags: i32) -> Result<Vec<Message>> {
+        let limits = *self.multipart_limits.lock().unwrap();
+        let mut parts: Vec<Message> = vec![];
+        let mut total_bytes = 0usize;
         loop {
-            let part = self.recv_bytes(flags)?;
-            parts.push(part);
+            let msg = self.recv_msg(flags)?;
+            total_bytes = total_bytes.saturating_add(msg.len());
+            let more_parts = msg.get_more();
+            if matches!(limits, Some(l) if l.exceeded(parts.len() + 1, total_bytes)) {
+                if more_parts {
+                    self.discard_remaining_parts()?;
+                }
+                return Err(Error::EMSGSIZE);
+            }
+            parts.push(msg);
 
-            let more_parts = self.get_rcvmore()?;
             if !more_parts {
                 break;
             }
@@ -776,28 +1430,135 @@ impl Socket {
         Ok(parts)
     }
 
+    /// Receive and decode an event from a socket connected to a monitor
+    /// endpoint, see `monitor`.
+    ///
+    /// `Error::EPROTO` is returned if the received message is not a
+    /// monitor event.
+    pub fn recv_monitor_event(&self, flags: i32) -> Result<MonitorEvent> {
+        MonitorEvent::from_parts(&self.recv_multipart_msg(flags)?)
+    }
+
+    // Once the first part of a message has arrived, `libzmq` guarantees that
+    // all other parts are available as well, so this does not block.
+    fn discard_remaining_parts(&self) -> Result<()> {
+        let mut msg = Message::new();
+        loop {
+            self.recv(&mut msg, 0)?;
+            if !msg.get_more() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Return the limits enforced by `recv_multipart`, if any.
+    pub fn get_multipart_limits(&self) -> Option<MultipartLimits> {
+        *self.multipart_limits.lock().unwrap()
+    }
+
+    /// Limit the number of parts and total size of the messages accepted by
+    /// `recv_multipart`, or remove the limits with `None`.
+    pub fn set_multipart_limits(&self, limits: Option<MultipartLimits>) {
+        *self.multipart_limits.lock().unwrap() = limits;
+    }
+
+    /// Return what blocking calls do when interrupted by a signal.
+    pub fn get_on_interrupt(&self) -> OnInterrupt {
+        *self.on_interrupt.lock().unwrap()
+    }
+
+    /// Set what blocking calls do when interrupted by a signal, i.e. when
+    /// `libzmq` fails with `Error::EINTR`. This applies to `send`, the
+    /// `recv` methods and `poll`.
+    ///
+    /// A signal arriving while a call waits makes it fail by default. With
+    /// `OnInterrupt::Retry`, the call is restarted instead; note that a
+    /// restarted call waits for its full timeout again, be it the timeout of
+    /// `poll` or `ZMQ_RCVTIMEO` and `ZMQ_SNDTIMEO`.
+    pub fn set_on_interrupt(&self, on_interrupt: OnInterrupt) {
+        *self.on_interrupt.lock().unwrap() = on_interrupt;
+    }
+
+    fn retry_interrupted<T, F>(&self, mut f: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        loop {
+            match f() {
+                Err(Error::EINTR) if self.get_on_interrupt() == OnInterrupt::Retry => {}
+                result => return result,
+            }
+        }
+    }
+
     sockopts! {
-        /// Accessor for the `ZMQ_IPV6` option.
+        /// Accessor for the `ZMQ_IPV6` option. It is disabled by default,
+        /// in which case IPv6 addresses such as `tcp://[::1]:5555` cannot be
+        /// bound to or connected to.
         (is_ipv6, set_ipv6) => ZMQ_IPV6 as bool,
-        /// Accessor for the `ZMQ_IMMEDIATE` option.
+        /// Accessor for the `ZMQ_IMMEDIATE` option. When enabled, messages
+        /// are only queued to connections that have been established, so
+        /// that load-balancing sockets such as `PUSH` and `DEALER` do not
+        /// hand messages to peers that are still connecting, or may never
+        /// come up.
         (is_immediate, set_immediate) => ZMQ_IMMEDIATE as bool,
         /// Accessor for the `ZMQ_PLAIN_SERVER` option.
         (is_plain_server, set_plain_server) => ZMQ_PLAIN_SERVER as bool,
         /// Accessor for the `ZMQ_CONFLATE` option.
         (is_conflate, set_conflate) => ZMQ_CONFLATE as bool,
+        /// Accessor for the `ZMQ_PROBE_ROUTER` option. When enabled on a
+        /// `ROUTER`, `DEALER` or `REQ` socket, an empty message is sent to
+        /// each `ROUTER` peer as soon as the connection is established, so
+        /// that the peer learns the identity of the connecting socket.
+        ///
+        /// `libzmq` does not allow reading the option back, so
+        /// `is_probe_router` fails with `Error::EINVAL`.
         (is_probe_router, set_probe_router) => ZMQ_PROBE_ROUTER as bool,
+        /// Accessor for the `ZMQ_ROUTER_MANDATORY` option. When enabled, a
+        /// `ROUTER` socket fails to send to an unknown peer with
+        /// `Error::EHOSTUNREACH`, rather than silently dropping the message.
         (is_router_mandatory, set_router_mandatory) => ZMQ_ROUTER_MANDATORY as bool,
         (is_router_handover, set_router_handover) => ZMQ_ROUTER_HANDOVER as bool,
         (is_curve_server, set_curve_server) => ZMQ_CURVE_SERVER as bool,
         (is_gssapi_server, set_gssapi_server) => ZMQ_GSSAPI_SERVER as bool,
         (is_gssapi_plaintext, set_gssapi_plaintext) => ZMQ_GSSAPI_PLAINTEXT as bool,
+        /// Set the `ZMQ_REQ_RELAXED` option. When enabled, a `REQ` socket
+        /// may send a new request before the reply to the previous one was
+        /// received, instead of failing with `Error::EFSM`; the pending
+        /// request is abandoned. Usually combined with `ZMQ_REQ_CORRELATE`.
         (_, set_req_relaxed) => ZMQ_REQ_RELAXED as bool,
+        /// Accessor for the `ZMQ_INVERT_MATCHING` option. When enabled,
+        /// subscriptions act as a reject list: only messages matching none
+        /// of them are delivered. Since publishers filter messages as well,
+        /// it must be set on both the `PUB`/`XPUB` and the `SUB`/`XSUB`
+        /// side.
+        (is_invert_matching, set_invert_matching) => ZMQ_INVERT_MATCHING as bool,
+        /// Set the `ZMQ_REQ_CORRELATE` option. When enabled, a `REQ` socket
+        /// tags each request with an id, and discards replies to requests
+        /// other than the latest one.
         (_, set_req_correlate) => ZMQ_REQ_CORRELATE as bool,
+        /// Accessor for the `ZMQ_MULTICAST_LOOP` option, which controls
+        /// whether multicast `udp://` messages are looped back to the
+        /// sending host.
+        #[cfg(feature = "draft-api")]
+        (is_multicast_loop, set_multicast_loop) => ZMQ_MULTICAST_LOOP as bool,
+    }
+
+    /// Record the current value of all readable options.
+    ///
+    /// See `OptionSnapshot` for details.
+    pub fn option_snapshot(&self) -> Result<OptionSnapshot> {
+        snapshot::take(self)
     }
 
     /// Return the type of this socket.
+    ///
+    /// Fails with `Error::ENOTSUP` for a type this crate does not know, or
+    /// only supports with the `draft-api` feature, e.g. for a socket
+    /// created with `from_raw`.
     pub fn get_socket_type(&self) -> Result<SocketType> {
-        sockopt::get(self.sock, zmq_sys::ZMQ_TYPE as c_int).map(SocketType::from_raw)
+        let raw = sockopt::get(self.sock, zmq_sys::ZMQ_TYPE as c_int)?;
+        SocketType::try_from_raw(raw).ok_or(Error::ENOTSUP)
     }
 
     /// Return true if there are more frames of a multipart message to receive.
@@ -814,6 +1575,10 @@ impl Socket {
         (get_recovery_ivl, set_recovery_ivl) => ZMQ_RECOVERY_IVL as i32,
         (get_sndbuf, set_sndbuf) => ZMQ_SNDBUF as i32,
         (get_rcvbuf, set_rcvbuf) => ZMQ_RCVBUF as i32,
+        /// Accessor for the `ZMQ_TOS` option, the type-of-service byte of
+        /// the IP packets sent on TCP connections made afterwards. A DSCP
+        /// code point goes in the upper six bits, so e.g. expedited
+        /// forwarding (46) is set as `46 << 2`.
         (get_tos, set_tos) => ZMQ_TOS as i32,
         (get_linger, set_linger) => ZMQ_LINGER as i32,
         (get_reconnect_ivl, set_reconnect_ivl) => ZMQ_RECONNECT_IVL as i32,
@@ -861,27 +1626,98 @@ impl Socket {
         /// In the `0.9` series, this will be rectified.
         (get_events) => ZMQ_EVENTS as PollEvents,
 
+        /// Accessor for the `ZMQ_MULTICAST_HOPS` option, the time-to-live
+        /// of outgoing `pgm://`, `epgm://` and multicast `udp://` packets.
+        /// The default of 1 keeps them on the local network.
         (get_multicast_hops, set_multicast_hops) => ZMQ_MULTICAST_HOPS as i32,
+        /// Accessor for the `ZMQ_MULTICAST_MAXTPDU` option, the maximum
+        /// size of a multicast transport data unit, in bytes.
+        (get_multicast_maxtpdu, set_multicast_maxtpdu) => ZMQ_MULTICAST_MAXTPDU as i32,
+        /// Accessor for the `ZMQ_RCVTIMEO` option, the number of
+        /// milliseconds a blocking receive waits before failing with
+        /// `Error::EAGAIN`. The default of -1 waits indefinitely.
         (get_rcvtimeo, set_rcvtimeo) => ZMQ_RCVTIMEO as i32,
+        /// Accessor for the `ZMQ_SNDTIMEO` option, the number of
+        /// milliseconds a blocking send waits before failing with
+        /// `Error::EAGAIN`. The default of -1 waits indefinitely.
         (get_sndtimeo, set_sndtimeo) => ZMQ_SNDTIMEO as i32,
+        /// Accessor for the `ZMQ_TCP_KEEPALIVE` option, which overrides the
+        /// `SO_KEEPALIVE` setting of TCP connections: 1 enables keepalive
+        /// probes, 0 disables them, and -1 (the default) leaves the OS
+        /// default in place.
+        ///
+        /// Like the other `tcp_keepalive` options, where -1 also means the
+        /// OS default, this only affects connections made afterwards.
         (get_tcp_keepalive, set_tcp_keepalive) => ZMQ_TCP_KEEPALIVE as i32,
+        /// Accessor for the `ZMQ_TCP_KEEPALIVE_CNT` option, the number of
+        /// unanswered keepalive probes after which a connection is dropped.
         (get_tcp_keepalive_cnt, set_tcp_keepalive_cnt) => ZMQ_TCP_KEEPALIVE_CNT as i32,
+        /// Accessor for the `ZMQ_TCP_KEEPALIVE_IDLE` option, the number of
+        /// idle seconds before the first keepalive probe is sent.
         (get_tcp_keepalive_idle, set_tcp_keepalive_idle) => ZMQ_TCP_KEEPALIVE_IDLE as i32,
+        /// Accessor for the `ZMQ_TCP_KEEPALIVE_INTVL` option, the number of
+        /// seconds between keepalive probes.
         (get_tcp_keepalive_intvl, set_tcp_keepalive_intvl) => ZMQ_TCP_KEEPALIVE_INTVL as i32,
+        /// Accessor for the `ZMQ_HANDSHAKE_IVL` option, the number of
+        /// milliseconds a new connection may take to complete the ZMTP
+        /// handshake before it is closed. The default is 30000; 0 disables
+        /// the limit.
         (get_handshake_ivl, set_handshake_ivl) => ZMQ_HANDSHAKE_IVL as i32,
-        // TODO: deprecate to align with ZMQ's preferred naming
+        /// Accessor for the `ZMQ_USE_FD` option, a pre-opened listening
+        /// socket, e.g. passed in by systemd socket activation, which the
+        /// next `bind` uses instead of creating one. The socket is matched
+        /// by the address given to `bind`, and closed along with this
+        /// socket. The default of -1 disables this.
+        (get_use_fd, set_use_fd) => ZMQ_USE_FD as i32,
+        // TODO: deprecate in favor of `set_routing_id`
         (_, set_identity) => ZMQ_ROUTING_ID as &[u8],
+        /// Set the `ZMQ_ROUTING_ID` option, the identity under which
+        /// `ROUTER` peers address this socket's connections, instead of a
+        /// generated one.
+        (_, set_routing_id) => ZMQ_ROUTING_ID as &[u8],
+        /// Set the `ZMQ_CONNECT_ROUTING_ID` option, the identity under
+        /// which a `ROUTER` or `STREAM` socket addresses the peer of its
+        /// next `connect`.
+        (_, set_connect_routing_id) => ZMQ_CONNECT_ROUTING_ID as &[u8],
         (_, set_subscribe) => ZMQ_SUBSCRIBE as &[u8],
         (_, set_unsubscribe) => ZMQ_UNSUBSCRIBE as &[u8],
+        /// Accessor for the `ZMQ_HEARTBEAT_IVL` option, the number of
+        /// milliseconds between ZMTP heartbeats sent on each connection. The
+        /// default of 0 disables heartbeats.
         (get_heartbeat_ivl, set_heartbeat_ivl) => ZMQ_HEARTBEAT_IVL as i32,
+        /// Accessor for the `ZMQ_HEARTBEAT_TTL` option, the number of
+        /// milliseconds the remote peer should wait for a heartbeat before
+        /// timing out the connection. It is rounded down to deciseconds.
         (get_heartbeat_ttl, set_heartbeat_ttl) => ZMQ_HEARTBEAT_TTL as i32,
+        /// Accessor for the `ZMQ_HEARTBEAT_TIMEOUT` option, the number of
+        /// milliseconds to wait for a reply to a heartbeat before the
+        /// connection is closed. The default of -1 uses the heartbeat
+        /// interval.
         (get_heartbeat_timeout, set_heartbeat_timeout) => ZMQ_HEARTBEAT_TIMEOUT as i32,
         (get_connect_timeout, set_connect_timeout) => ZMQ_CONNECT_TIMEOUT as i32,
+        /// Accessor for the `ZMQ_VMCI_CONNECT_TIMEOUT` option, the timeout
+        /// in milliseconds for establishing `vmci://` connections.
+        ///
+        /// Like the other `vmci` options, this fails with `Error::EINVAL`
+        /// unless `has("vmci")`.
+        (get_vmci_connect_timeout, set_vmci_connect_timeout) => ZMQ_VMCI_CONNECT_TIMEOUT as i32,
+        /// Accessor for the `ZMQ_VMCI_BUFFER_SIZE` option, the size of the
+        /// buffer of `vmci://` connections.
+        (get_vmci_buffer_size, set_vmci_buffer_size) => ZMQ_VMCI_BUFFER_SIZE as u64,
+        /// Accessor for the `ZMQ_VMCI_BUFFER_MIN_SIZE` option.
+        (get_vmci_buffer_min_size, set_vmci_buffer_min_size) => ZMQ_VMCI_BUFFER_MIN_SIZE as u64,
+        /// Accessor for the `ZMQ_VMCI_BUFFER_MAX_SIZE` option.
+        (get_vmci_buffer_max_size, set_vmci_buffer_max_size) => ZMQ_VMCI_BUFFER_MAX_SIZE as u64,
+    }
+
+    // TODO: deprecate in favor of `get_routing_id`
+    pub fn get_identity(&self) -> Result<Vec<u8>> {
+        self.get_routing_id()
     }
 
-    // TODO: deprecate to align with ZMQ's preferred naming
-    pub fn get_identity(&self) -> Result<Vec<u8>> {
-        // 255 = identity max length
+    /// Get the `ZMQ_ROUTING_ID` option value, see `set_routing_id`.
+    pub fn get_routing_id(&self) -> Result<Vec<u8>> {
+        // 255 = routing id max length
         sockopt::get_bytes(self.sock, zmq_sys::ZMQ_ROUTING_ID as c_int, 255)
     }
 
@@ -891,14 +1727,23 @@ impl Socket {
         sockopt::get_string(self.sock, zmq_sys::ZMQ_SOCKS_PROXY as c_int, 255, true)
     }
 
+    /// Return the `ZMQ_BINDTODEVICE` option, see `set_bindtodevice`.
+    pub fn get_bindtodevice(&self) -> Result<result::Result<String, Vec<u8>>> {
+        // 16 = IFNAMSIZ, the longest interface name plus the nul byte
+        sockopt::get_string(self.sock, zmq_sys::ZMQ_BINDTODEVICE as c_int, 16, true)
+    }
+
+    /// Return the security mechanism in use. Fails with `Error::ENOTSUP`,
+    /// of kind `ErrorKind::Unsupported`, for a mechanism this crate does not
+    /// know.
     pub fn get_mechanism(&self) -> Result<Mechanism> {
-        sockopt::get(self.sock, zmq_sys::ZMQ_MECHANISM as c_int).map(|mech| match mech {
-            zmq_sys::ZMQ_NULL => Mechanism::ZMQ_NULL,
-            zmq_sys::ZMQ_PLAIN => Mechanism::ZMQ_PLAIN,
-            zmq_sys::ZMQ_CURVE => Mechanism::ZMQ_CURVE,
-            zmq_sys::ZMQ_GSSAPI => Mechanism::ZMQ_GSSAPI,
-            _ => panic!("Mechanism is out of range!"),
-        })
+        match sockopt::get(self.sock, zmq_sys::ZMQ_MECHANISM as c_int)? {
+            zmq_sys::ZMQ_NULL => Ok(Mechanism::ZMQ_NULL),
+            zmq_sys::ZMQ_PLAIN => Ok(Mechanism::ZMQ_PLAIN),
+            zmq_sys::ZMQ_CURVE => Ok(Mechanism::ZMQ_CURVE),
+            zmq_sys::ZMQ_GSSAPI => Ok(Mechanism::ZMQ_GSSAPI),
+            _ => Err(Error::ENOTSUP),
+        }
     }
 
     pub fn get_plain_username(&self) -> Result<result::Result<String, Vec<u8>>> {
@@ -911,6 +1756,7 @@ impl Socket {
         sockopt::get_string(self.sock, zmq_sys::ZMQ_PLAIN_PASSWORD as c_int, 256, true)
     }
 
+    /// Return the `ZMQ_ZAP_DOMAIN` option, see `set_zap_domain`.
     pub fn get_zap_domain(&self) -> Result<result::Result<String, Vec<u8>>> {
         // 255 = arbitrary size
         sockopt::get_string(self.sock, zmq_sys::ZMQ_ZAP_DOMAIN as c_int, 255, true)
@@ -979,14 +1825,45 @@ impl Socket {
 
     sockopts! {
         (_, set_socks_proxy) => ZMQ_SOCKS_PROXY as Option<&str>,
+        /// Set the `ZMQ_BINDTODEVICE` option, the network interface (e.g.
+        /// `"eth0"`) that connections made afterwards are bound to via
+        /// `SO_BINDTODEVICE`. This is only supported on Linux, and usually
+        /// requires the `CAP_NET_RAW` capability.
+        (_, set_bindtodevice) => ZMQ_BINDTODEVICE as &str,
         (_, set_plain_username) => ZMQ_PLAIN_USERNAME as Option<&str>,
         (_, set_plain_password) => ZMQ_PLAIN_PASSWORD as Option<&str>,
+        /// Set the `ZMQ_ZAP_DOMAIN` option, the authentication domain passed
+        /// to the ZAP handler for connections to this socket. `libzmq` only
+        /// consults the handler for `NULL` connections if a domain is set.
         (_, set_zap_domain) => ZMQ_ZAP_DOMAIN as &str,
         (_, set_xpub_welcome_msg) => ZMQ_XPUB_WELCOME_MSG as Option<&str>,
+        /// Set the `ZMQ_XPUB_VERBOSE` option. When enabled, an `XPUB` socket
+        /// passes on all subscription messages, rather than only the first
+        /// subscription to each topic.
         (_, set_xpub_verbose) => ZMQ_XPUB_VERBOSE as bool,
-
+        /// Set the `ZMQ_XPUB_VERBOSER` option, which extends
+        /// `ZMQ_XPUB_VERBOSE` to unsubscription messages as well.
+        (_, set_xpub_verboser) => ZMQ_XPUB_VERBOSER as bool,
+        /// Set the `ZMQ_XPUB_MANUAL` option. When enabled, subscription
+        /// messages are passed to the application but not applied; instead,
+        /// `set_subscribe` and `set_unsubscribe` on the `XPUB` socket change
+        /// the subscriptions of the peer that sent the last subscription
+        /// message. This allows a proxy to filter or rewrite subscriptions.
+        (_, set_xpub_manual) => ZMQ_XPUB_MANUAL as bool,
+        /// Set the `ZMQ_XPUB_MANUAL_LAST_VALUE` option, which enables
+        /// `ZMQ_XPUB_MANUAL`, and additionally sends a message only to the
+        /// subscriber that subscribed last, to support last value caching.
+        #[cfg(feature = "draft-api")]
+        (_, set_xpub_manual_last_value) => ZMQ_XPUB_MANUAL_LAST_VALUE as bool,
+
+        /// Set the `ZMQ_CURVE_PUBLICKEY` option, given either as the
+        /// 32-byte binary key or its 40-character Z85 encoding.
         (_, set_curve_publickey) => ZMQ_CURVE_PUBLICKEY as &[u8],
+        /// Set the `ZMQ_CURVE_SECRETKEY` option, given either as the
+        /// 32-byte binary key or its 40-character Z85 encoding.
         (_, set_curve_secretkey) => ZMQ_CURVE_SECRETKEY as &[u8],
+        /// Set the `ZMQ_CURVE_SERVERKEY` option, given either as the
+        /// 32-byte binary key or its 40-character Z85 encoding.
         (_, set_curve_serverkey) => ZMQ_CURVE_SERVERKEY as &[u8],
         (_, set_gssapi_principal) => ZMQ_GSSAPI_PRINCIPAL as &str,
         (_, set_gssapi_service_principal) => ZMQ_GSSAPI_SERVICE_PRINCIPAL as &str,
@@ -1008,7 +1885,13 @@ impl Socket {
     /// The return value on success will be either zero (no event) or one (some
     /// event was signaled).
     pub fn poll(&self, events: PollEvents, timeout_ms: i64) -> Result<i32> {
-        poll(&mut [self.as_poll_item(events)], timeout_ms)
+        self.retry_interrupted(|| poll(&mut [self.as_poll_item(events)], timeout_ms))
+    }
+
+    /// Like `poll`, but with the timeout given as a `Duration`, see
+    /// `poll_timeout`.
+    pub fn poll_timeout(&self, events: PollEvents, timeout: Option<Duration>) -> Result<i32> {
+        self.retry_interrupted(|| poll_timeout(&mut [self.as_poll_item(events)], timeout))
     }
 }
 
@@ -1116,7 +1999,7 @@ impl<'a> PollItem<'a> {
 /// indicates to block indefinitely until an event has occurred.
 ///
 /// The result, if not `Err`, indicates the number of poll items that have
-/// events signaled.
+/// events signaled, which `ready_items` yields.
 pub fn poll(items: &mut [PollItem], timeout: i64) -> Result<i32> {
     let rc = zmq_try!(unsafe {
         zmq_sys::zmq_poll(
@@ -1128,6 +2011,63 @@ pub fn poll(items: &mut [PollItem], timeout: i64) -> Result<i32> {
     Ok(rc as i32)
 }
 
+/// Return the positions of the poll items on which events occurred during
+/// the last `poll`, along with those events.
+///
+/// ```
+/// let ctx = zmq::Context::new();
+/// let idle = ctx.socket(zmq::PULL).unwrap();
+/// let busy = ctx.socket(zmq::PULL).unwrap();
+/// busy.bind("inproc://ready-items").unwrap();
+/// let sender = ctx.socket(zmq::PUSH).unwrap();
+/// sender.connect("inproc://ready-items").unwrap();
+/// sender.send("hello", 0).unwrap();
+///
+/// let mut items = [idle.as_poll_item(zmq::POLLIN), busy.as_poll_item(zmq::POLLIN)];
+/// assert_eq!(zmq::poll(&mut items, -1).unwrap(), 1);
+/// let ready: Vec<_> = zmq::ready_items(&items).collect();
+/// assert_eq!(ready, vec![(1, zmq::POLLIN)]);
+/// ```
+pub fn ready_items<'i>(items: &'i [PollItem]) -> impl Iterator<Item = (usize, PollEvents)> + 'i {
+    items
+        .iter()
+        .map(PollItem::get_revents)
+        .enumerate()
+        .filter(|(_, revents)| !revents.is_empty())
+}
+
+/// Poll for events on multiple sockets, like `poll`, but with the timeout
+/// given as a `Duration`. A timeout of `None` blocks indefinitely until an
+/// event has occurred.
+///
+/// The timeout is rounded up to whole milliseconds, so that a short timeout
+/// does not degrade into a busy loop.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PULL).unwrap();
+/// let mut items = [socket.as_poll_item(zmq::POLLIN)];
+/// let ready = zmq::poll_timeout(&mut items, Some(Duration::from_millis(10))).unwrap();
+/// assert_eq!(ready, 0);
+/// ```
+pub fn poll_timeout(items: &mut [PollItem], timeout: Option<Duration>) -> Result<i32> {
+    poll(items, timeout_millis(timeout))
+}
+
+fn timeout_millis(timeout: Option<Duration>) -> i64 {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return -1,
+    };
+    let mut millis = timeout.as_millis();
+    if timeout.subsec_nanos() % 1_000_000 != 0 {
+        millis += 1;
+    }
+    millis.min(c_long::MAX as u128) as i64
+}
+
 /// Start a 0MQ proxy in the current thread.
 ///
 /// A proxy connects a frontend socket with a backend socket, where the exact
@@ -1156,8 +2096,8 @@ pub fn proxy_with_capture(
 /// Start a 0MQ proxy in the current thread, with a control socket.
 ///
 /// If PAUSE is received on the control socket, the proxy suspends its activities. If RESUME is received,
-/// it goes on. If TERMINATE is received, it terminates smoothly. At start, the proxy runs normally
-/// as if `proxy` was used.
+/// it goes on. If TERMINATE is received, it terminates smoothly, and `Ok(())` is returned. At
+/// start, the proxy runs normally as if `proxy` was used.
 pub fn proxy_steerable(
     frontend: &mut Socket,
     backend: &mut Socket,
@@ -1186,22 +2126,20 @@ pub fn proxy_steerable_with_capture(
 
 /// Return true if the used 0MQ library has the given capability.
 ///
-/// The return value is always the `Some` variant; it used to return
-/// `None` for older, now unsupported versions of 0MQ that didn't have
-/// the wrapped `zmq_has` function. Thus, for code that requires `zmq`
-/// version 0.9.0 or newer, you can safely call `unwrap` on the return
-/// value.
-///
-/// For a list of capabilities, please consult the `zmq_has` manual
-/// page.
-///
-/// # Compatibility
+/// Capabilities include the transports `"ipc"`, `"pgm"`, `"tipc"`,
+/// `"vmci"` and `"norm"`, the security mechanisms `"curve"` and `"gssapi"`, and
+/// `"draft"` for the draft API. For a full list, please consult the
+/// `zmq_has` manual page.
 ///
-/// In the `zmq` 0.10.0, this function will simply return `bool`.
-///
-pub fn has(capability: &str) -> Option<bool> {
+/// ```
+/// if !zmq::has("curve") {
+///     println!("CURVE is unavailable, falling back to PLAIN");
+/// }
+/// assert!(!zmq::has("no-such-capability"));
+/// ```
+pub fn has(capability: &str) -> bool {
     let c_str = ffi::CString::new(capability).unwrap();
-    unsafe { Some(zmq_sys::zmq_has(c_str.as_ptr()) == 1) }
+    unsafe { zmq_sys::zmq_has(c_str.as_ptr()) == 1 }
 }
 
 /// A CURVE key pair generated by 0MQ.
@@ -1248,6 +2186,34 @@ impl CurveKeyPair {
 
         Ok(pair)
     }
+
+    /// Restore a key pair from its secret key, deriving the public key.
+    ///
+    /// This wraps `zmq_curve_public`, and fails with `Error::ENOTSUP` if
+    /// 0MQ was built without CURVE support.
+    pub fn from_secret_key(secret_key: [u8; 32]) -> Result<CurveKeyPair> {
+        let mut ffi_public_key = [0u8; 41];
+        let ffi_secret_key = ffi::CString::new(z85_encode(&secret_key).unwrap()).unwrap();
+
+        zmq_try!(unsafe {
+            zmq_sys::zmq_curve_public(
+                ffi_public_key.as_mut_ptr() as *mut libc::c_char,
+                ffi_secret_key.as_ptr(),
+            )
+        });
+
+        let mut public_key = [0; 32];
+        unsafe {
+            zmq_sys::zmq_z85_decode(
+                public_key.as_mut_ptr(),
+                ffi_public_key.as_ptr() as *mut libc::c_char,
+            );
+        }
+        Ok(CurveKeyPair {
+            public_key,
+            secret_key,
+        })
+    }
 }
 
 /// Errors that can occur while encoding Z85.
@@ -18,6 +18,16 @@ use super::errno_to_error;
 /// convenience APIs provided (e.g. `Socket::recv_bytes()` or
 /// `Socket::send()`). However, using message objects can make multiple
 /// operations in a loop more efficient, since allocated memory can be reused.
+///
+/// A `Message` dereferences to `[u8]`, so its contents can be passed to
+/// anything taking a byte slice without copying:
+///
+/// ```
+/// let msg = zmq::Message::from("hello");
+/// let bytes: &[u8] = &msg;
+/// assert_eq!(bytes, b"hello");
+/// assert_eq!(&msg[1..3], b"el");
+/// ```
 pub struct Message {
     msg: zmq_sys::zmq_msg_t,
 }
@@ -37,6 +47,11 @@ impl fmt::Debug for Message {
     }
 }
 
+// The value of `ZMQ_GROUP_MAX_LENGTH` since libzmq 4.3.0; `zmq_sys` still
+// declares the older limit of 15.
+#[cfg(feature = "draft-api")]
+const GROUP_MAX_LENGTH: usize = 255;
+
 unsafe extern "C" fn drop_msg_data_box(data: *mut c_void, hint: *mut c_void) {
     let _ = Box::from_raw(slice::from_raw_parts_mut(data as *mut u8, hint as usize));
 }
@@ -123,7 +138,71 @@ impl Message {
         rc != 0
     }
 
-    /// Query a message metadata property.
+    /// Replace the contents of this message with those of `other`, leaving
+    /// `other` empty. This wraps `zmq_msg_move`, so no data is copied.
+    ///
+    /// There is deliberately no counterpart for `zmq_msg_copy`: it makes
+    /// both messages share one buffer, which would let safe code mutate
+    /// the contents of one message through the other.
+    pub fn move_from(&mut self, other: &mut Message) {
+        let rc = unsafe { zmq_sys::zmq_msg_move(&mut self.msg, &mut other.msg) };
+        assert_eq!(rc, 0);
+    }
+
+    /// Return the routing id of a message received on a `SERVER` socket,
+    /// identifying the `CLIENT` peer it came from.
+    #[cfg(feature = "draft-api")]
+    pub fn routing_id(&self) -> Option<u32> {
+        let ptr = &self.msg as *const _ as *mut _;
+        match unsafe { zmq_sys::zmq_msg_routing_id(ptr) } {
+            0 => None,
+            routing_id => Some(routing_id),
+        }
+    }
+
+    /// Set the routing id, which determines the peer a message sent on a
+    /// `SERVER` socket is delivered to.
+    ///
+    /// Fails with `Error::EINVAL` for a routing id of 0.
+    #[cfg(feature = "draft-api")]
+    pub fn set_routing_id(&mut self, routing_id: u32) -> crate::Result<()> {
+        zmq_try!(unsafe { zmq_sys::zmq_msg_set_routing_id(&mut self.msg, routing_id) });
+        Ok(())
+    }
+
+    /// Return the group of a message sent or received on a `RADIO` or
+    /// `DISH` socket.
+    #[cfg(feature = "draft-api")]
+    pub fn group(&self) -> Option<&str> {
+        let ptr = &self.msg as *const _ as *mut _;
+        let group = unsafe { zmq_sys::zmq_msg_group(ptr) };
+        if group.is_null() {
+            return None;
+        }
+        match str::from_utf8(unsafe { ffi::CStr::from_ptr(group) }.to_bytes()) {
+            Ok("") | Err(_) => None,
+            Ok(group) => Some(group),
+        }
+    }
+
+    /// Set the group, which determines the `DISH` sockets a message sent
+    /// on a `RADIO` socket is delivered to.
+    ///
+    /// Fails with `Error::EINVAL` if the group is longer than
+    /// `ZMQ_GROUP_MAX_LENGTH` (255) bytes.
+    #[cfg(feature = "draft-api")]
+    pub fn set_group(&mut self, group: &str) -> crate::Result<()> {
+        // `zmq_msg_set_group` silently truncates longer names.
+        if group.len() > GROUP_MAX_LENGTH {
+            return Err(crate::Error::EINVAL);
+        }
+        let c_str = ffi::CString::new(group.as_bytes()).unwrap();
+        zmq_try!(unsafe { zmq_sys::zmq_msg_set_group(&mut self.msg, c_str.as_ptr()) });
+        Ok(())
+    }
+
+    /// Query a message metadata property, such as `"Peer-Address"`,
+    /// `"User-Id"` or a property supplied by the ZAP handler.
     ///
     /// # Non-UTF8 values
     ///
@@ -135,7 +214,7 @@ impl Message {
     ///
     /// This is considered a bug in the bindings, and will be fixed with the
     /// next API-breaking release.
-    pub fn gets<'a>(&'a mut self, property: &str) -> Option<&'a str> {
+    pub fn gets<'a>(&'a self, property: &str) -> Option<&'a str> {
         let c_str = ffi::CString::new(property.as_bytes()).unwrap();
 
         let value = unsafe { zmq_sys::zmq_msg_gets(&self.msg, c_str.as_ptr()) };
@@ -183,9 +262,18 @@ impl DerefMut for Message {
     }
 }
 
-impl<'a> From<&'a [u8]> for Message {
-    /// Construct a message from a byte slice by copying the data.
-    fn from(data: &'a [u8]) -> Self {
+// The largest message `libzmq` stores inline rather than in a separate
+// allocation (`max_vsm_size`, on 64-bit targets).
+const MAX_INLINE_SIZE: usize = 33;
+
+impl<'a, T> From<&'a T> for Message
+where
+    T: AsRef<[u8]> + ?Sized,
+{
+    /// Construct a message from a byte slice, string slice, or a reference
+    /// to anything else holding bytes, by copying the data.
+    fn from(data: &'a T) -> Self {
+        let data = data.as_ref();
         unsafe {
             let mut msg = Message::with_size_uninit(data.len());
             ptr::copy_nonoverlapping(data.as_ptr(), msg.as_mut_ptr(), data.len());
@@ -195,8 +283,12 @@ impl<'a> From<&'a [u8]> for Message {
 }
 
 impl From<Vec<u8>> for Message {
-    /// Construct a message from a byte vector without copying the data.
+    /// Construct a message from a byte vector without copying the data,
+    /// unless it is small enough to be stored inline in the message.
     fn from(msg: Vec<u8>) -> Self {
+        if msg.len() <= MAX_INLINE_SIZE {
+            return Message::from(&msg[..]);
+        }
         Message::from(msg.into_boxed_slice())
     }
 }
@@ -223,26 +315,11 @@ impl From<Box<[u8]>> for Message {
     }
 }
 
-impl<'a> From<&'a str> for Message {
-    /// Construct a message from a string slice by copying the UTF-8 data.
-    fn from(msg: &str) -> Self {
-        Message::from(msg.as_bytes())
-    }
-}
-
-impl<'a> From<&'a String> for Message {
-    /// Construct a message from a string slice by copying the UTF-8 data.
-    fn from(msg: &String) -> Self {
-        Message::from(msg.as_bytes())
-    }
-}
-
-impl<'a, T> From<&'a T> for Message
-where
-    T: Into<Message> + Clone,
-{
-    fn from(v: &'a T) -> Self {
-        v.clone().into()
+impl From<String> for Message {
+    /// Construct a message from a string without copying the UTF-8 data,
+    /// unless it is small enough to be stored inline in the message.
+    fn from(msg: String) -> Self {
+        Message::from(msg.into_bytes())
     }
 }
 
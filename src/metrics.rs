@@ -0,0 +1,191 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Traffic counters for one socket.
+///
+/// `libzmq` does not keep such statistics, so the application updates the
+/// counters as it sends and receives, e.g. from a wrapper around its send
+/// and receive calls. All methods can be called concurrently.
+#[derive(Debug, Default)]
+pub struct SocketStats {
+    messages_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_sent: AtomicU64,
+    bytes_received: AtomicU64,
+    dropped: AtomicU64,
+    reconnects: AtomicU64,
+    queue_full: AtomicU64,
+}
+
+macro_rules! stats_counters {
+    ($( $(#[$meta:meta])* $field:ident ),* $(,)?) => {
+        impl SocketStats {
+            $(
+                $(#[$meta])*
+                pub fn $field(&self) -> u64 {
+                    self.$field.load(Ordering::Relaxed)
+                }
+            )*
+        }
+    };
+}
+
+stats_counters! {
+    /// Return the number of messages sent.
+    messages_sent,
+    /// Return the number of messages received.
+    messages_received,
+    /// Return the number of payload bytes sent.
+    bytes_sent,
+    /// Return the number of payload bytes received.
+    bytes_received,
+    /// Return the number of messages dropped.
+    dropped,
+    /// Return the number of reconnections.
+    reconnects,
+    /// Return how often a send found the queue full.
+    queue_full,
+}
+
+impl SocketStats {
+    /// Count a sent message of `bytes` bytes.
+    pub fn record_sent(&self, bytes: usize) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Count a received message of `bytes` bytes.
+    pub fn record_received(&self, bytes: usize) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    /// Count a dropped message.
+    pub fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a reconnection, e.g. from a `ReconnectWatcher` hook.
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a send that would have blocked at the high-water mark.
+    pub fn record_queue_full(&self) {
+        self.queue_full.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+// Name, help text and accessor of each exported metric.
+type Metric = (&'static str, &'static str, fn(&SocketStats) -> u64);
+
+const METRICS: &[Metric] = &[
+    (
+        "zmq_messages_sent_total",
+        "Messages sent.",
+        SocketStats::messages_sent,
+    ),
+    (
+        "zmq_messages_received_total",
+        "Messages received.",
+        SocketStats::messages_received,
+    ),
+    (
+        "zmq_bytes_sent_total",
+        "Payload bytes sent.",
+        SocketStats::bytes_sent,
+    ),
+    (
+        "zmq_bytes_received_total",
+        "Payload bytes received.",
+        SocketStats::bytes_received,
+    ),
+    (
+        "zmq_messages_dropped_total",
+        "Messages dropped.",
+        SocketStats::dropped,
+    ),
+    (
+        "zmq_reconnects_total",
+        "Reconnections.",
+        SocketStats::reconnects,
+    ),
+    (
+        "zmq_queue_full_total",
+        "Sends that found the queue at the high-water mark.",
+        SocketStats::queue_full,
+    ),
+];
+
+/// A set of named `SocketStats`, which can be rendered in the Prometheus
+/// text exposition format.
+///
+/// # Examples
+///
+/// ```
+/// let registry = zmq::MetricsRegistry::new();
+/// let stats = registry.register("frontend");
+/// stats.record_sent(5);
+///
+/// let text = registry.render_prometheus();
+/// assert!(text.contains("zmq_messages_sent_total{socket=\"frontend\"} 1\n"));
+/// assert!(text.contains("zmq_bytes_sent_total{socket=\"frontend\"} 5\n"));
+/// ```
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    sockets: Mutex<Vec<(String, Arc<SocketStats>)>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry.
+    pub fn new() -> MetricsRegistry {
+        MetricsRegistry::default()
+    }
+
+    /// Return the counters of the socket called `name`, creating them if
+    /// necessary.
+    pub fn register(&self, name: &str) -> Arc<SocketStats> {
+        let mut sockets = self.sockets.lock().unwrap();
+        if let Some((_, stats)) = sockets.iter().find(|(n, _)| n == name) {
+            return Arc::clone(stats);
+        }
+        let stats = Arc::new(SocketStats::default());
+        sockets.push((name.to_owned(), Arc::clone(&stats)));
+        stats
+    }
+
+    /// Stop exporting the counters of the socket called `name`.
+    pub fn unregister(&self, name: &str) {
+        self.sockets.lock().unwrap().retain(|(n, _)| n != name);
+    }
+
+    /// Render the counters of all registered sockets, labelled with the
+    /// socket name, in the Prometheus text exposition format.
+    pub fn render_prometheus(&self) -> String {
+        let sockets = self.sockets.lock().unwrap();
+        let mut out = String::new();
+        for &(metric, help, value) in METRICS {
+            let _ = writeln!(out, "# HELP {} {}", metric, help);
+            let _ = writeln!(out, "# TYPE {} counter", metric);
+            for (name, stats) in sockets.iter() {
+                let _ = writeln!(
+                    out,
+                    "{}{{socket=\"{}\"}} {}",
+                    metric,
+                    escape_label(name),
+                    value(stats)
+                );
+            }
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
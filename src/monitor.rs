@@ -0,0 +1,78 @@
+use std::ops::Deref;
+use std::str;
+use std::time::Duration;
+
+use crate::{Error, Result, SocketEvent};
+
+/// The meaning of a `MonitorEvent`'s value, which depends on the event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventDetail {
+    /// The file descriptor of the underlying connection or listener, for
+    /// `CONNECTED`, `LISTENING`, `ACCEPTED`, `CLOSED` and `DISCONNECTED`.
+    Fd(u32),
+    /// The `errno` value describing the failure, for `BIND_FAILED`,
+    /// `ACCEPT_FAILED`, `CLOSE_FAILED` and `HANDSHAKE_FAILED_NO_DETAIL`.
+    ///
+    /// This is kept raw, since `Error` does not cover all values `libzmq`
+    /// may report here, such as `ECONNRESET`.
+    Errno(i32),
+    /// The interval until the next attempt, for `CONNECT_RETRIED`.
+    ReconnectInterval(Duration),
+    /// The `ZMQ_PROTOCOL_ERROR_*` code, for `HANDSHAKE_FAILED_PROTOCOL`.
+    ProtocolError(u32),
+    /// The ZAP status code, e.g. 400, for `HANDSHAKE_FAILED_AUTH`.
+    AuthStatus(u32),
+    /// The event carries no value.
+    None,
+}
+
+/// An event read from a monitor socket, see `Socket::monitor`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonitorEvent {
+    pub event: SocketEvent,
+    /// The event value, whose meaning depends on the event: a file
+    /// descriptor, an `errno` value or a reconnection interval.
+    pub value: u32,
+    /// The endpoint the event relates to.
+    pub endpoint: String,
+}
+
+impl MonitorEvent {
+    /// Decode the two frames of a monitor message.
+    ///
+    /// The first frame holds the event id and value in native byte order,
+    /// the second one the endpoint. `Error::EPROTO` is returned if the
+    /// frames do not have this layout, or the event is unknown.
+    pub fn from_parts<T: Deref<Target = [u8]>>(parts: &[T]) -> Result<MonitorEvent> {
+        let (header, endpoint) = match parts {
+            [header, endpoint] if header.len() == 6 => (&header[..], &endpoint[..]),
+            _ => return Err(Error::EPROTO),
+        };
+        let id = u16::from_ne_bytes([header[0], header[1]]);
+        let value = u32::from_ne_bytes([header[2], header[3], header[4], header[5]]);
+        Ok(MonitorEvent {
+            event: SocketEvent::try_from_raw(id).ok_or(Error::EPROTO)?,
+            value,
+            endpoint: str::from_utf8(endpoint)
+                .map_err(|_| Error::EPROTO)?
+                .to_owned(),
+        })
+    }
+
+    /// Interpret the event value according to the event.
+    pub fn detail(&self) -> EventDetail {
+        use crate::SocketEvent::*;
+        match self.event {
+            CONNECTED | LISTENING | ACCEPTED | CLOSED | DISCONNECTED => EventDetail::Fd(self.value),
+            BIND_FAILED | ACCEPT_FAILED | CLOSE_FAILED | HANDSHAKE_FAILED_NO_DETAIL => {
+                EventDetail::Errno(self.value as i32)
+            }
+            CONNECT_RETRIED => {
+                EventDetail::ReconnectInterval(Duration::from_millis(self.value.into()))
+            }
+            HANDSHAKE_FAILED_PROTOCOL => EventDetail::ProtocolError(self.value),
+            HANDSHAKE_FAILED_AUTH => EventDetail::AuthStatus(self.value),
+            CONNECT_DELAYED | MONITOR_STOPPED | HANDSHAKE_SUCCEEDED | ALL => EventDetail::None,
+        }
+    }
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "draft-api")]
+use libc::{c_int, c_long};
+#[cfg(feature = "draft-api")]
+use std::os::raw::c_void;
+use std::time::Duration;
+#[cfg(feature = "draft-api")]
+use std::{mem, ptr};
+
+use zmq_sys::RawFd;
+
+#[cfg(not(feature = "draft-api"))]
+use crate::poll_timeout;
+use crate::{ready_items, Error, PollEvents, PollItem, Result, Socket};
+
+/// Polls a set of registered sockets and file descriptors, each identified
+/// by a key.
+///
+/// Unlike with `poll`, the caller does not need to maintain a slice of
+/// `PollItem`s, or map the positions of ready items back to sockets:
+/// `ready` yields the keys of the registrations on which events occurred.
+///
+/// With the `draft-api` feature, polling is implemented with `zmq_poller`
+/// rather than `zmq_poll`, which also supports the thread-safe socket
+/// types, such as `SERVER` and `CLIENT`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let ctx = zmq::Context::new();
+/// let jobs = ctx.socket(zmq::PULL).unwrap();
+/// let control = ctx.socket(zmq::PULL).unwrap();
+/// jobs.bind("inproc://poller-jobs").unwrap();
+/// control.bind("inproc://poller-control").unwrap();
+///
+/// let mut poller = zmq::Poller::new();
+/// poller.register(&jobs, "jobs", zmq::POLLIN).unwrap();
+/// poller.register(&control, "control", zmq::POLLIN).unwrap();
+///
+/// let sender = ctx.socket(zmq::PUSH).unwrap();
+/// sender.connect("inproc://poller-control").unwrap();
+/// sender.send("stop", 0).unwrap();
+///
+/// assert_eq!(poller.poll(Some(Duration::from_secs(1))).unwrap(), 1);
+/// let ready: Vec<_> = poller.ready().collect();
+/// assert_eq!(ready, vec![(&"control", zmq::POLLIN)]);
+/// ```
+pub struct Poller<'a, K> {
+    items: Vec<PollItem<'a>>,
+    keys: Vec<K>,
+    backend: Backend,
+}
+
+impl<'a, K> Default for Poller<'a, K> {
+    fn default() -> Self {
+        Poller {
+            items: Vec::new(),
+            keys: Vec::new(),
+            backend: Backend::new(),
+        }
+    }
+}
+
+impl<'a, K: PartialEq> Poller<'a, K> {
+    /// Create a poller without any registrations.
+    pub fn new() -> Poller<'a, K> {
+        Poller::default()
+    }
+
+    /// Register a socket to be polled for `events`, under `key`.
+    ///
+    /// Fails with `Error::EINVAL` if the key or the socket is already
+    /// registered.
+    pub fn register(&mut self, socket: &'a Socket, key: K, events: PollEvents) -> Result<()> {
+        if self.items.iter().any(|item| item.has_socket(socket)) {
+            return Err(Error::EINVAL);
+        }
+        self.push(socket.as_poll_item(events), key)
+    }
+
+    /// Register a non-0MQ socket, given by its file descriptor, to be
+    /// polled for `events`, under `key`.
+    ///
+    /// Fails with `Error::EINVAL` if the key or the file descriptor is
+    /// already registered.
+    pub fn register_fd(&mut self, fd: RawFd, key: K, events: PollEvents) -> Result<()> {
+        if self.items.iter().any(|item| item.has_fd(fd)) {
+            return Err(Error::EINVAL);
+        }
+        self.push(PollItem::from_fd(fd, events), key)
+    }
+
+    fn push(&mut self, item: PollItem<'a>, key: K) -> Result<()> {
+        if self.keys.contains(&key) {
+            return Err(Error::EINVAL);
+        }
+        self.backend.add(&item)?;
+        self.items.push(item);
+        self.keys.push(key);
+        Ok(())
+    }
+
+    /// Change the events polled for the registration under `key`.
+    ///
+    /// Fails with `Error::EINVAL` if the key is not registered.
+    pub fn modify(&mut self, key: &K, events: PollEvents) -> Result<()> {
+        let index = self.position(key)?;
+        self.backend.modify(&self.items[index], events)?;
+        self.items[index].set_events(events);
+        Ok(())
+    }
+
+    /// Remove the registration under `key`.
+    ///
+    /// Fails with `Error::EINVAL` if the key is not registered.
+    pub fn unregister(&mut self, key: &K) -> Result<()> {
+        let index = self.position(key)?;
+        self.backend.remove(&self.items[index])?;
+        self.items.remove(index);
+        self.keys.remove(index);
+        Ok(())
+    }
+
+    fn position(&self, key: &K) -> Result<usize> {
+        self.keys.iter().position(|k| k == key).ok_or(Error::EINVAL)
+    }
+
+    /// Return the number of registrations.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Return true if there are no registrations.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Wait until events occur on any of the registrations, or the timeout
+    /// expires, see `poll_timeout`. Returns the number of registrations on
+    /// which events occurred, which are then yielded by `ready`.
+    pub fn poll(&mut self, timeout: Option<Duration>) -> Result<usize> {
+        self.backend.wait(&mut self.items, timeout)
+    }
+
+    /// Return the keys of the registrations on which events occurred during
+    /// the last `poll`, along with those events.
+    pub fn ready(&self) -> impl Iterator<Item = (&K, PollEvents)> {
+        ready_items(&self.items).map(move |(index, revents)| (&self.keys[index], revents))
+    }
+}
+
+#[cfg(not(feature = "draft-api"))]
+struct Backend;
+
+#[cfg(not(feature = "draft-api"))]
+impl Backend {
+    fn new() -> Backend {
+        Backend
+    }
+
+    fn add(&self, _item: &PollItem) -> Result<()> {
+        Ok(())
+    }
+
+    fn modify(&self, _item: &PollItem, _events: PollEvents) -> Result<()> {
+        Ok(())
+    }
+
+    fn remove(&self, _item: &PollItem) -> Result<()> {
+        Ok(())
+    }
+
+    fn wait(&mut self, items: &mut [PollItem], timeout: Option<Duration>) -> Result<usize> {
+        poll_timeout(items, timeout).map(|count| count as usize)
+    }
+}
+
+// Mirrors the registrations in a `zmq_poller`, which is passed a buffer for
+// the events of the ready registrations on each wait.
+#[cfg(feature = "draft-api")]
+struct Backend {
+    poller: *mut c_void,
+    events: Vec<zmq_sys::zmq_poller_event_t>,
+}
+
+#[cfg(feature = "draft-api")]
+impl Backend {
+    fn new() -> Backend {
+        let poller = unsafe { zmq_sys::zmq_poller_new() };
+        assert!(!poller.is_null());
+        Backend {
+            poller,
+            events: Vec::new(),
+        }
+    }
+
+    fn add(&self, item: &PollItem) -> Result<()> {
+        if item.socket.is_null() {
+            zmq_try!(unsafe {
+                zmq_sys::zmq_poller_add_fd(self.poller, item.fd, ptr::null_mut(), item.events)
+            });
+        } else {
+            zmq_try!(unsafe {
+                zmq_sys::zmq_poller_add(self.poller, item.socket, ptr::null_mut(), item.events)
+            });
+        }
+        Ok(())
+    }
+
+    fn modify(&self, item: &PollItem, events: PollEvents) -> Result<()> {
+        if item.socket.is_null() {
+            zmq_try!(unsafe { zmq_sys::zmq_poller_modify_fd(self.poller, item.fd, events.bits()) });
+        } else {
+            zmq_try!(unsafe {
+                zmq_sys::zmq_poller_modify(self.poller, item.socket, events.bits())
+            });
+        }
+        Ok(())
+    }
+
+    fn remove(&self, item: &PollItem) -> Result<()> {
+        if item.socket.is_null() {
+            zmq_try!(unsafe { zmq_sys::zmq_poller_remove_fd(self.poller, item.fd) });
+        } else {
+            zmq_try!(unsafe { zmq_sys::zmq_poller_remove(self.poller, item.socket) });
+        }
+        Ok(())
+    }
+
+    fn wait(&mut self, items: &mut [PollItem], timeout: Option<Duration>) -> Result<usize> {
+        for item in items.iter_mut() {
+            item.revents = 0;
+        }
+        // `zmq_poller` refuses to block indefinitely without registrations.
+        if items.is_empty() {
+            return crate::poll_timeout(items, timeout).map(|_| 0);
+        }
+        self.events.resize(items.len(), unsafe {
+            mem::zeroed::<zmq_sys::zmq_poller_event_t>()
+        });
+        let rc = unsafe {
+            zmq_sys::zmq_poller_wait_all(
+                self.poller,
+                self.events.as_mut_ptr(),
+                self.events.len() as c_int,
+                crate::timeout_millis(timeout) as c_long,
+            )
+        };
+        let count = if rc == -1 {
+            match crate::errno_to_error() {
+                // A timeout is reported as `EAGAIN`.
+                Error::EAGAIN => 0,
+                e => return Err(e),
+            }
+        } else {
+            rc as usize
+        };
+        for event in &self.events[..count] {
+            let item = items.iter_mut().find(|item| {
+                if event.socket.is_null() {
+                    item.socket.is_null() && item.fd == event.fd
+                } else {
+                    item.socket == event.socket
+                }
+            });
+            if let Some(item) = item {
+                item.revents = event.events & item.events;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(feature = "draft-api")]
+impl Drop for Backend {
+    fn drop(&mut self) {
+        let rc = unsafe { zmq_sys::zmq_poller_destroy(&mut self.poller) };
+        assert_eq!(rc, 0);
+    }
+}
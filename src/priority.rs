@@ -0,0 +1,170 @@
+use crate::{poll, Error, Result, Socket, DONTWAIT, POLLIN};
+
+use std::time::{Duration, Instant};
+
+/// Merges the messages of several input sockets, such as `PULL` or `SUB`
+/// sockets, by priority.
+///
+/// `libzmq` fair-queues between the peers of a socket, but cannot express
+/// that e.g. a control channel should always be served before bulk data.
+/// `recv` always delivers from the highest-priority source that has a
+/// message ready. Sources of equal priority are served round-robin, each
+/// delivering up to its weight in consecutive messages before yielding to
+/// the next.
+///
+/// # Examples
+///
+/// ```
+/// let ctx = zmq::Context::new();
+/// let control = ctx.socket(zmq::PULL).unwrap();
+/// let bulk = ctx.socket(zmq::PULL).unwrap();
+/// control.bind("inproc://control").unwrap();
+/// bulk.bind("inproc://bulk").unwrap();
+///
+/// let mut receiver = zmq::PriorityReceiver::new();
+/// let control_id = receiver.add(&control, 10);
+/// let bulk_id = receiver.add(&bulk, 0);
+///
+/// let bulk_sender = ctx.socket(zmq::PUSH).unwrap();
+/// bulk_sender.connect("inproc://bulk").unwrap();
+/// bulk_sender.send("data", 0).unwrap();
+/// let control_sender = ctx.socket(zmq::PUSH).unwrap();
+/// control_sender.connect("inproc://control").unwrap();
+/// control_sender.send("stop", 0).unwrap();
+///
+/// let (id, _) = receiver.recv(-1).unwrap().unwrap();
+/// assert_eq!(id, control_id);
+/// let (id, _) = receiver.recv(-1).unwrap().unwrap();
+/// assert_eq!(id, bulk_id);
+/// ```
+#[derive(Default)]
+pub struct PriorityReceiver<'a> {
+    sources: Vec<Source<'a>>,
+    levels: Vec<Level>,
+}
+
+struct Source<'a> {
+    socket: &'a Socket,
+    weight: u32,
+    credit: u32,
+}
+
+// The sources sharing a priority, in the order they were added.
+struct Level {
+    priority: i32,
+    members: Vec<usize>,
+    cursor: usize,
+}
+
+impl<'a> PriorityReceiver<'a> {
+    /// Create a receiver without any sources.
+    pub fn new() -> PriorityReceiver<'a> {
+        PriorityReceiver::default()
+    }
+
+    /// Add a source with the given priority, higher values being served
+    /// first, and a weight of 1. Returns the identifier reported by `recv`
+    /// for messages from this source.
+    pub fn add(&mut self, socket: &'a Socket, priority: i32) -> usize {
+        self.add_weighted(socket, priority, 1)
+    }
+
+    /// Add a source which, while other sources of the same priority are
+    /// also ready, delivers up to `weight` consecutive messages in turn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is zero.
+    pub fn add_weighted(&mut self, socket: &'a Socket, priority: i32, weight: u32) -> usize {
+        assert!(weight > 0, "weight must be positive");
+        let id = self.sources.len();
+        self.sources.push(Source {
+            socket,
+            weight,
+            credit: weight,
+        });
+        match self.levels.binary_search_by(|l| priority.cmp(&l.priority)) {
+            Ok(pos) => self.levels[pos].members.push(id),
+            Err(pos) => self.levels.insert(
+                pos,
+                Level {
+                    priority,
+                    members: vec![id],
+                    cursor: 0,
+                },
+            ),
+        }
+        id
+    }
+
+    /// Return the number of sources.
+    pub fn len(&self) -> usize {
+        self.sources.len()
+    }
+
+    /// Return whether there are no sources.
+    pub fn is_empty(&self) -> bool {
+        self.sources.is_empty()
+    }
+
+    /// Wait for at most `timeout` milliseconds (forever if `-1`) for a
+    /// message, and return it along with the identifier of its source, or
+    /// `None` on timeout.
+    pub fn recv(&mut self, timeout: i64) -> Result<Option<(usize, Vec<Vec<u8>>)>> {
+        // Spurious wakeups must not restart the full timeout.
+        let deadline = if timeout >= 0 {
+            Some(Instant::now() + Duration::from_millis(timeout as u64))
+        } else {
+            None
+        };
+        loop {
+            let remaining = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    // Round up so that a sub-millisecond remainder still waits.
+                    left.as_micros().div_ceil(1000) as i64
+                }
+                None => -1,
+            };
+            let mut items: Vec<_> = self
+                .sources
+                .iter()
+                .map(|source| source.socket.as_poll_item(POLLIN))
+                .collect();
+            if poll(&mut items, remaining)? == 0 {
+                return Ok(None);
+            }
+            let readable: Vec<bool> = items.iter().map(|item| item.is_readable()).collect();
+            let id = match self.select(&readable) {
+                Some(id) => id,
+                None => continue,
+            };
+            match self.sources[id].socket.recv_multipart(DONTWAIT) {
+                Ok(parts) => return Ok(Some((id, parts))),
+                // The event was spurious; wait for the next one.
+                Err(Error::EAGAIN) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    // Pick the next source to serve among the readable ones.
+    fn select(&mut self, readable: &[bool]) -> Option<usize> {
+        let sources = &mut self.sources;
+        let level = self
+            .levels
+            .iter_mut()
+            .find(|level| level.members.iter().any(|&id| readable[id]))?;
+        loop {
+            let id = level.members[level.cursor];
+            let source = &mut sources[id];
+            if readable[id] && source.credit > 0 {
+                source.credit -= 1;
+                return Some(id);
+            }
+            // Either idle or out of credit: pass the turn on.
+            source.credit = source.weight;
+            level.cursor = (level.cursor + 1) % level.members.len();
+        }
+    }
+}
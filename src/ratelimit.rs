@@ -0,0 +1,258 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::{Error, Message, Result, Socket, SNDMORE};
+
+/// What a `RateLimitedSocket` does with a message that exceeds its limits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnExcess {
+    /// Wait until the message can be sent within the limits.
+    Block,
+    /// Silently discard the message. The number of dropped messages is
+    /// available via `RateLimitedSocket::dropped`.
+    Drop,
+    /// Fail with `Error::EAGAIN`, leaving the decision to the caller.
+    Error,
+}
+
+/// The limits enforced by a `RateLimitedSocket`.
+///
+/// Both limits are token buckets which allow bursts of up to one second's
+/// worth of traffic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Maximum number of messages per second, if any.
+    pub messages_per_sec: Option<f64>,
+    /// Maximum number of payload bytes per second, if any.
+    pub bytes_per_sec: Option<f64>,
+    /// What to do with messages exceeding the limits.
+    pub on_excess: OnExcess,
+}
+
+// Where the wrapper is within a multipart message being sent part by part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Multipart {
+    Idle,
+    Sending,
+    // The first part was dropped or rejected, and so are the following parts
+    // up to the last one, so that the peer never sees part of a message.
+    Discarding,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: f64) -> TokenBucket {
+        assert!(rate > 0.0, "rate limit must be positive");
+        TokenBucket {
+            rate,
+            tokens: rate,
+            updated: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.updated = now;
+    }
+
+    // How long to wait until `cost` tokens are available. A cost exceeding
+    // the burst size only requires a full bucket, and leaves it in debt.
+    fn wait_time(&self, cost: f64) -> Duration {
+        let missing = cost.min(self.rate) - self.tokens;
+        if missing > 0.0 {
+            Duration::from_secs_f64(missing / self.rate)
+        } else {
+            Duration::from_secs(0)
+        }
+    }
+}
+
+/// A socket whose outgoing traffic is throttled by a token bucket, so that
+/// a misbehaving producer cannot saturate a shared broker.
+///
+/// The limits apply to whole messages: all parts of a multipart message
+/// count as one message, and once its first part has been admitted, the
+/// remaining parts are sent regardless of the limits. Conversely, if the
+/// first part is dropped or rejected, so are the remaining parts.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::{OnExcess, RateLimit, RateLimitedSocket};
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PUB).unwrap();
+/// let mut socket = RateLimitedSocket::new(
+///     socket,
+///     RateLimit {
+///         messages_per_sec: Some(2.0),
+///         bytes_per_sec: None,
+///         on_excess: OnExcess::Drop,
+///     },
+/// );
+/// for _ in 0..3 {
+///     socket.send("tick", 0).unwrap();
+/// }
+/// assert_eq!(socket.dropped(), 1);
+/// ```
+pub struct RateLimitedSocket {
+    socket: Socket,
+    messages: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+    on_excess: OnExcess,
+    multipart: Multipart,
+    dropped: u64,
+}
+
+impl RateLimitedSocket {
+    /// Wrap `socket`, enforcing `limit` on everything sent through the
+    /// wrapper.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a rate is not positive.
+    pub fn new(socket: Socket, limit: RateLimit) -> RateLimitedSocket {
+        RateLimitedSocket {
+            socket,
+            messages: limit.messages_per_sec.map(TokenBucket::new),
+            bytes: limit.bytes_per_sec.map(TokenBucket::new),
+            on_excess: limit.on_excess,
+            multipart: Multipart::Idle,
+            dropped: 0,
+        }
+    }
+
+    /// Return a reference to the underlying socket.
+    pub fn as_socket(&self) -> &Socket {
+        &self.socket
+    }
+
+    /// Return the underlying socket.
+    pub fn into_inner(self) -> Socket {
+        self.socket
+    }
+
+    /// Return the number of messages discarded under `OnExcess::Drop`.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    // Charge a message against the buckets, returning whether it may be
+    // sent.
+    fn admit(&mut self, messages: f64, bytes: f64) -> Result<bool> {
+        loop {
+            let now = Instant::now();
+            let mut wait = Duration::from_secs(0);
+            for (bucket, cost) in [(&mut self.messages, messages), (&mut self.bytes, bytes)] {
+                if let Some(bucket) = bucket {
+                    bucket.refill(now);
+                    wait = wait.max(bucket.wait_time(cost));
+                }
+            }
+            if wait == Duration::from_secs(0) {
+                self.charge(messages, bytes);
+                return Ok(true);
+            }
+            match self.on_excess {
+                OnExcess::Block => thread::sleep(wait),
+                OnExcess::Drop => {
+                    self.dropped += 1;
+                    return Ok(false);
+                }
+                OnExcess::Error => return Err(Error::EAGAIN),
+            }
+        }
+    }
+
+    // Decide whether to send a part with the given size, and update the
+    // multipart state accordingly.
+    fn admit_part(&mut self, bytes: f64, flags: i32) -> Result<bool> {
+        let more = flags & SNDMORE != 0;
+        match self.multipart {
+            Multipart::Sending => {
+                self.charge(0.0, bytes);
+                Ok(true)
+            }
+            Multipart::Discarding => {
+                if !more {
+                    self.multipart = Multipart::Idle;
+                }
+                match self.on_excess {
+                    OnExcess::Error => Err(Error::EAGAIN),
+                    _ => Ok(false),
+                }
+            }
+            Multipart::Idle => {
+                let admitted = self.admit(1.0, bytes);
+                if more && !matches!(admitted, Ok(true)) {
+                    self.multipart = Multipart::Discarding;
+                }
+                admitted
+            }
+        }
+    }
+
+    fn charge(&mut self, messages: f64, bytes: f64) {
+        if let Some(ref mut bucket) = self.messages {
+            bucket.tokens -= messages;
+        }
+        if let Some(ref mut bucket) = self.bytes {
+            bucket.tokens -= bytes;
+        }
+    }
+
+    /// Send a message, subject to the rate limits.
+    ///
+    /// Parts following a part sent with `SNDMORE` are sent if the first
+    /// part was, and only count towards the byte limit. Otherwise they are
+    /// discarded as well, failing with `Error::EAGAIN` under `OnExcess::Error`.
+    pub fn send<T>(&mut self, data: T, flags: i32) -> Result<()>
+    where
+        T: Into<Message>,
+    {
+        let msg = data.into();
+        if !self.admit_part(msg.len() as f64, flags)? {
+            return Ok(());
+        }
+        self.socket.send(msg, flags)?;
+        self.multipart = if flags & SNDMORE != 0 {
+            Multipart::Sending
+        } else {
+            Multipart::Idle
+        };
+        Ok(())
+    }
+
+    /// Send a multipart message, subject to the rate limits.
+    ///
+    /// Like `Socket::send_multipart`, an empty iterator sends nothing; it is
+    /// not counted against the limits either.
+    pub fn send_multipart<I, T>(&mut self, iter: I, flags: i32) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Message>,
+    {
+        let parts: Vec<Message> = iter.into_iter().map(Into::into).collect();
+        if parts.is_empty() {
+            return Ok(());
+        }
+        let bytes = parts.iter().map(|part| part.len()).sum::<usize>() as f64;
+        if !self.admit_part(bytes, flags)? {
+            return Ok(());
+        }
+        self.socket.send_multipart(parts, flags)?;
+        self.multipart = if flags & SNDMORE != 0 {
+            Multipart::Sending
+        } else {
+            Multipart::Idle
+        };
+        Ok(())
+    }
+}
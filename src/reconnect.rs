@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::rng::Rng;
+use crate::{Error, Result, Socket, DONTWAIT, PAIR, POLLIN};
+
+// The shortest delay, since a `ZMQ_RECONNECT_IVL` of 0 disables reconnection.
+const MIN_DELAY: Duration = Duration::from_millis(1);
+
+/// A strategy for spacing out reconnection attempts.
+///
+/// `libzmq` reconnects on its own, controlled by the `ZMQ_RECONNECT_IVL` and
+/// `ZMQ_RECONNECT_IVL_MAX` options; `apply` maps a policy onto these options.
+/// `libzmq` already adds a random component of up to `ZMQ_RECONNECT_IVL` to
+/// each interval; the policies add exponential growth and decorrelated
+/// jitter on top of it, so that the peers of a restarted broker spread out
+/// their attempts further. For full control, e.g. when driving explicit
+/// `disconnect`/`connect` cycles, use the delays produced by `backoff()`.
+///
+/// Delays are at least one millisecond, since a `ZMQ_RECONNECT_IVL` of 0
+/// would disable reconnection.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use zmq::ReconnectPolicy;
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::DEALER).unwrap();
+/// let policy = ReconnectPolicy::Exponential {
+///     initial: Duration::from_millis(100),
+///     max: Duration::from_secs(10),
+/// };
+/// policy.apply(&socket).unwrap();
+/// assert_eq!(socket.get_reconnect_ivl().unwrap(), 100);
+/// assert_eq!(socket.get_reconnect_ivl_max().unwrap(), 10_000);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReconnectPolicy {
+    /// Wait the same amount of time before each attempt.
+    Constant(Duration),
+    /// Start with `initial`, doubling the interval after each failed attempt,
+    /// up to `max`.
+    Exponential { initial: Duration, max: Duration },
+    /// "Decorrelated jitter": each delay is chosen at random between `base`
+    /// and three times the previous delay, capped at `cap`.
+    DecorrelatedJitter { base: Duration, cap: Duration },
+}
+
+impl ReconnectPolicy {
+    /// Configure the reconnection options of `socket` according to this
+    /// policy.
+    ///
+    /// For `DecorrelatedJitter`, the socket's initial interval is drawn at
+    /// random between `base` and `3 * base`, so that sockets configured with
+    /// the same policy spread out their attempts; `libzmq` then backs off
+    /// exponentially up to `cap`.
+    pub fn apply(&self, socket: &Socket) -> Result<()> {
+        let (ivl, ivl_max) = match *self {
+            ReconnectPolicy::Constant(ivl) => (ivl, Duration::from_millis(0)),
+            ReconnectPolicy::Exponential { initial, max } => (initial, max),
+            ReconnectPolicy::DecorrelatedJitter { base, cap } => {
+                let mut rng = Rng::from_entropy();
+                let base_ms = duration_to_millis(base);
+                let ivl = rng.range_inclusive(base_ms, base_ms.saturating_mul(3));
+                (Duration::from_millis(ivl), cap)
+            }
+        };
+        socket.set_reconnect_ivl(millis_to_option(duration_to_millis(ivl).max(1)))?;
+        socket.set_reconnect_ivl_max(millis_to_option(duration_to_millis(ivl_max)))
+    }
+
+    /// Return an iterator over the delays to wait before successive
+    /// reconnection attempts.
+    ///
+    /// The iterator never ends; call `Backoff::reset` after a successful
+    /// connection.
+    pub fn backoff(&self) -> Backoff {
+        Backoff {
+            policy: *self,
+            previous: None,
+            rng: Rng::from_entropy(),
+        }
+    }
+}
+
+/// The sequence of delays produced by a `ReconnectPolicy`.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    policy: ReconnectPolicy,
+    previous: Option<Duration>,
+    rng: Rng,
+}
+
+impl Backoff {
+    /// Start over with the initial delay.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+impl Iterator for Backoff {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Duration> {
+        let delay = match (self.policy, self.previous) {
+            (ReconnectPolicy::Constant(ivl), _) => ivl,
+            (ReconnectPolicy::Exponential { initial, .. }, None) => initial,
+            (ReconnectPolicy::Exponential { max, .. }, Some(previous)) => {
+                previous.checked_mul(2).map_or(max, |next| next.min(max))
+            }
+            (ReconnectPolicy::DecorrelatedJitter { base, cap }, previous) => {
+                let base_ms = duration_to_millis(base);
+                let upper = previous
+                    .map_or(base_ms, duration_to_millis)
+                    .saturating_mul(3);
+                let ms = self.rng.range_inclusive(base_ms, upper);
+                Duration::from_millis(ms).min(cap)
+            }
+        };
+        let delay = delay.max(MIN_DELAY);
+        self.previous = Some(delay);
+        Some(delay)
+    }
+}
+
+type ReconnectHook<'a> = Box<dyn FnMut(&str) + 'a>;
+
+/// Invokes hooks whenever a connection of a socket is re-established.
+///
+/// `libzmq` reconnects transparently, but does not replay any state for
+/// the new connection: a `DEALER` worker has to re-send its READY frame, a
+/// client may need to request a fresh snapshot, and so on. The watcher
+/// monitors the socket for `CONNECTED` events, and runs the hooks with the
+/// endpoint concerned for each such event after the first one for that
+/// endpoint. This only covers connections made via `connect`; the peers of
+/// a bound socket are not distinguishable by endpoint.
+///
+/// The watcher does not run by itself: call `process` from the
+/// application's event loop, e.g. when `as_poll_item` signals readiness.
+///
+/// # Examples
+///
+/// ```no_run
+/// let ctx = zmq::Context::new();
+/// let worker = ctx.socket(zmq::DEALER).unwrap();
+/// let mut watcher = zmq::ReconnectWatcher::new(&worker).unwrap();
+/// worker.connect("tcp://broker:5555").unwrap();
+///
+/// watcher.on_reconnect(|endpoint| println!("reconnected to {}", endpoint));
+/// loop {
+///     if watcher.process(1000).unwrap() > 0 {
+///         worker.send("READY", 0).unwrap();
+///     }
+/// }
+/// ```
+pub struct ReconnectWatcher<'a> {
+    monitor: Socket,
+    connected: HashSet<String>,
+    hooks: Vec<ReconnectHook<'a>>,
+}
+
+impl<'a> ReconnectWatcher<'a> {
+    /// Start monitoring `socket`, which must have been created from a
+    /// `Context`, rather than via `Socket::from_raw`. Otherwise,
+    /// `Error::EINVAL` is returned.
+    ///
+    /// The watcher should be created before connecting the socket, so that
+    /// the initial connections are known as such.
+    pub fn new(socket: &Socket) -> Result<ReconnectWatcher<'a>> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        let context = socket.context.as_ref().ok_or(Error::EINVAL)?;
+        let endpoint = format!(
+            "inproc://zmq-reconnect-watcher-{}",
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        );
+        socket.monitor(&endpoint, zmq_sys::ZMQ_EVENT_CONNECTED as i32)?;
+        let monitor = context.socket(PAIR)?;
+        monitor.connect(&endpoint)?;
+        Ok(ReconnectWatcher {
+            monitor,
+            connected: HashSet::new(),
+            hooks: Vec::new(),
+        })
+    }
+
+    /// Add a hook to run with the endpoint of each re-established
+    /// connection.
+    pub fn on_reconnect<F>(&mut self, hook: F)
+    where
+        F: FnMut(&str) + 'a,
+    {
+        self.hooks.push(Box::new(hook));
+    }
+
+    /// Return a `PollItem` signalling when there are events to process.
+    pub fn as_poll_item(&self) -> crate::PollItem<'_> {
+        self.monitor.as_poll_item(POLLIN)
+    }
+
+    /// Wait up to `timeout_ms` milliseconds (forever if `-1`) for monitor
+    /// events, run the hooks for all reconnections seen, and return their
+    /// number.
+    pub fn process(&mut self, timeout_ms: i64) -> Result<usize> {
+        let mut reconnects = 0;
+        if self.monitor.poll(POLLIN, timeout_ms)? == 0 {
+            return Ok(0);
+        }
+        loop {
+            let endpoint = match self.monitor.recv_monitor_event(DONTWAIT) {
+                Ok(event) => event.endpoint,
+                Err(Error::EAGAIN) => return Ok(reconnects),
+                Err(e) => return Err(e),
+            };
+            if !self.connected.insert(endpoint.clone()) {
+                reconnects += 1;
+                for hook in &mut self.hooks {
+                    hook(&endpoint);
+                }
+            }
+        }
+    }
+}
+
+fn duration_to_millis(d: Duration) -> u64 {
+    d.as_secs()
+        .saturating_mul(1000)
+        .saturating_add(u64::from(d.subsec_millis()))
+}
+
+fn millis_to_option(ms: u64) -> i32 {
+    if ms > i32::MAX as u64 {
+        i32::MAX
+    } else {
+        ms as i32
+    }
+}
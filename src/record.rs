@@ -0,0 +1,166 @@
+use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::codec;
+use crate::Socket;
+
+const MAGIC: &[u8; 8] = b"ZMQREC1\n";
+
+/// A recorded multipart message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// The time at which the message was recorded, relative to the start of
+    /// the recording.
+    pub offset: Duration,
+    /// The message parts.
+    pub parts: Vec<Vec<u8>>,
+}
+
+/// Writes timestamped messages to a file, for later replay with a
+/// `Replayer`.
+///
+/// To record the traffic passing through a proxy, connect a socket to the
+/// proxy's capture socket and `tap` it.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::{Recorder, Replayer};
+///
+/// let mut recording = Vec::new();
+/// let mut recorder = Recorder::new(&mut recording).unwrap();
+/// recorder.record(&["topic", "payload"]).unwrap();
+/// recorder.finish().unwrap();
+///
+/// let mut replayer = Replayer::new(&recording[..]).unwrap();
+/// let record = replayer.next().unwrap().unwrap();
+/// assert_eq!(record.parts, vec![b"topic".to_vec(), b"payload".to_vec()]);
+/// assert!(replayer.next().is_none());
+/// ```
+#[derive(Debug)]
+pub struct Recorder<W: Write> {
+    writer: W,
+    start: Instant,
+}
+
+impl Recorder<BufWriter<File>> {
+    /// Create a recording at `path`, truncating any existing file.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Recorder::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> Recorder<W> {
+    /// Start a recording written to `writer`.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        Ok(Recorder {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Record a multipart message, timestamped with the current time.
+    pub fn record<T: AsRef<[u8]>>(&mut self, parts: &[T]) -> io::Result<()> {
+        let micros = u64::try_from(self.start.elapsed().as_micros()).unwrap_or(u64::MAX);
+        self.writer.write_all(&micros.to_be_bytes())?;
+        codec::write_parts(&mut self.writer, parts)
+    }
+
+    /// Receive a multipart message from `socket` and record it, returning
+    /// the message.
+    pub fn tap(&mut self, socket: &Socket, flags: i32) -> io::Result<Vec<Vec<u8>>> {
+        let parts = socket.recv_multipart(flags)?;
+        self.record(&parts)?;
+        Ok(parts)
+    }
+
+    /// Flush the recording and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// Reads a recording made by a `Recorder`.
+///
+/// The records can be iterated over directly, or sent to a socket with
+/// `replay`, which reproduces their relative timing.
+#[derive(Debug)]
+pub struct Replayer<R: Read> {
+    reader: R,
+}
+
+impl Replayer<BufReader<File>> {
+    /// Open the recording at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Replayer::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> Replayer<R> {
+    /// Read a recording from `reader`.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a message recording",
+            ));
+        }
+        Ok(Replayer { reader })
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<Record>> {
+        let mut micros = [0; 8];
+        match self.reader.read_exact(&mut micros) {
+            Ok(()) => {}
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        }
+        let offset = Duration::from_micros(u64::from_be_bytes(micros));
+        match codec::read_parts(&mut self.reader)? {
+            Some(parts) => Ok(Some(Record { offset, parts })),
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    /// Send all remaining records to `socket`, waiting between messages so
+    /// as to preserve the recorded relative timing. Returns the number of
+    /// messages sent.
+    ///
+    /// `speed` scales the pace of the replay: `2.0` replays twice as fast
+    /// as recorded, and `0.0` sends all messages without waiting.
+    pub fn replay(&mut self, socket: &Socket, speed: f64) -> io::Result<usize> {
+        let start = Instant::now();
+        let mut first_offset = None;
+        let mut sent = 0;
+        for record in self {
+            let record = record?;
+            let first = *first_offset.get_or_insert(record.offset);
+            if speed > 0.0 {
+                let due = record.offset.saturating_sub(first).div_f64(speed);
+                let elapsed = start.elapsed();
+                if due > elapsed {
+                    thread::sleep(due - elapsed);
+                }
+            }
+            socket.send_multipart(record.parts, 0)?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+}
+
+impl<R: Read> Iterator for Replayer<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<io::Result<Record>> {
+        self.read_record().transpose()
+    }
+}
@@ -0,0 +1,70 @@
+//! A small, non-cryptographic pseudo-random number generator.
+//!
+//! This is used where the crate needs some randomness (e.g. jitter),
+//! without pulling in a dependency for it. Do not use it for anything
+//! security-related.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static SEED_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// SplitMix64 generator.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator with a fixed seed, yielding a reproducible sequence.
+    pub fn with_seed(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// Create a generator seeded from the clock, a per-process counter and a
+    /// stack address, so that generators created concurrently diverge.
+    pub fn from_entropy() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        let counter = SEED_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let local = 0u8;
+        let addr = &local as *const u8 as u64;
+        let mut rng = Rng::with_seed(nanos ^ addr.rotate_left(32) ^ counter);
+        // Mix the state once, so that similar seeds don't yield similar
+        // initial values.
+        rng.next_u64();
+        rng
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Return a value uniformly distributed in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Return `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        p > 0.0 && self.next_f64() < p
+    }
+
+    /// Return a value in the inclusive range `[low, high]`.
+    pub fn range_inclusive(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        let span = high - low;
+        if span == u64::MAX {
+            return self.next_u64();
+        }
+        low + self.next_u64() % (span + 1)
+    }
+}
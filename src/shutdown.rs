@@ -0,0 +1,207 @@
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::{poll, Context, Error, Result, Socket, DONTWAIT, POLLIN};
+
+type StopHook<'a> = Box<dyn FnOnce() + 'a>;
+type DrainHandler<'a> = Box<dyn FnMut(Vec<Vec<u8>>) + 'a>;
+
+struct Entry<'a> {
+    socket: Socket,
+    drain: Option<DrainHandler<'a>>,
+}
+
+/// The outcome of a `Shutdown`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ShutdownReport {
+    /// The number of messages passed to drain handlers.
+    pub drained: usize,
+    /// Whether the deadline passed before the input queues were empty.
+    pub deadline_exceeded: bool,
+    /// The number of actor threads that panicked.
+    pub panicked_actors: usize,
+}
+
+/// Coordinates the graceful shutdown of a context, its sockets and the
+/// threads ("actors") using them.
+///
+/// `run` proceeds in the following steps:
+///
+/// 1. The stop hooks are called, in the order they were added, to tell
+///    the application to stop accepting new work.
+/// 2. Messages still queued on sockets with a drain handler are received
+///    and passed to the handler, until the queues are empty or the deadline
+///    passes.
+/// 3. The sockets are closed in the order they were added, with a linger
+///    period bounded by the time remaining until the deadline, so that
+///    pending outgoing messages get a chance to be delivered.
+/// 4. The context is shut down, making blocking calls on any other socket
+///    fail with `Error::ETERM`, and the actor threads are joined; they are
+///    expected to close their sockets and exit upon `ETERM`.
+/// 5. The context handle is dropped; the context is terminated once its
+///    last handle and socket are gone.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let ctx = zmq::Context::new();
+/// let frontend = ctx.socket(zmq::PULL).unwrap();
+/// frontend.bind("inproc://work").unwrap();
+///
+/// let socket = ctx.socket(zmq::PULL).unwrap();
+/// socket.bind("inproc://control").unwrap();
+/// let worker = std::thread::spawn(move || {
+///     // Blocks until the context is shut down.
+///     assert_eq!(socket.recv_bytes(0), Err(zmq::Error::ETERM));
+/// });
+///
+/// let mut leftovers = Vec::new();
+/// let mut shutdown = zmq::Shutdown::new(ctx, Duration::from_secs(1));
+/// shutdown.drain(frontend, |msg| leftovers.push(msg));
+/// shutdown.actor(worker);
+/// let report = shutdown.run().unwrap();
+/// assert_eq!(report.panicked_actors, 0);
+/// ```
+pub struct Shutdown<'a> {
+    context: Context,
+    timeout: Duration,
+    stop_hooks: Vec<StopHook<'a>>,
+    sockets: Vec<Entry<'a>>,
+    actors: Vec<JoinHandle<()>>,
+}
+
+impl<'a> Shutdown<'a> {
+    /// Prepare the shutdown of `context`, which should take at most
+    /// `timeout` to drain and flush messages once `run` is called.
+    pub fn new(context: Context, timeout: Duration) -> Shutdown<'a> {
+        Shutdown {
+            context,
+            timeout,
+            stop_hooks: Vec::new(),
+            sockets: Vec::new(),
+            actors: Vec::new(),
+        }
+    }
+
+    /// Add a hook that stops the intake of new work, such as setting a flag
+    /// observed by a server loop.
+    pub fn on_stop<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnOnce() + 'a,
+    {
+        self.stop_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Add a socket to be closed, without draining it.
+    ///
+    /// Sockets are closed in the order they were added, so sockets should
+    /// be added before the sockets they depend on.
+    pub fn socket(&mut self, socket: Socket) -> &mut Self {
+        self.sockets.push(Entry {
+            socket,
+            drain: None,
+        });
+        self
+    }
+
+    /// Add a socket whose queued messages are passed to `handler` before it
+    /// is closed.
+    pub fn drain<F>(&mut self, socket: Socket, handler: F) -> &mut Self
+    where
+        F: FnMut(Vec<Vec<u8>>) + 'a,
+    {
+        self.sockets.push(Entry {
+            socket,
+            drain: Some(Box::new(handler)),
+        });
+        self
+    }
+
+    /// Add a thread to be joined after the context has been shut down.
+    pub fn actor(&mut self, handle: JoinHandle<()>) -> &mut Self {
+        self.actors.push(handle);
+        self
+    }
+
+    /// Perform the shutdown.
+    ///
+    /// All steps are carried out even if one of them fails; the first error
+    /// encountered is returned.
+    pub fn run(self) -> Result<ShutdownReport> {
+        let deadline = Instant::now() + self.timeout;
+        let mut report = ShutdownReport::default();
+        let mut first_error = None;
+        let mut record = |result: Result<()>| {
+            if let Err(e) = result {
+                first_error.get_or_insert(e);
+            }
+        };
+
+        for hook in self.stop_hooks {
+            hook();
+        }
+
+        let mut sockets = self.sockets;
+        record(drain(&mut sockets, deadline, &mut report));
+
+        for entry in sockets {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let linger = remaining.as_millis().min(i32::MAX as u128) as i32;
+            record(entry.socket.close_with_linger(linger));
+        }
+
+        record(self.context.shutdown());
+        for actor in self.actors {
+            if actor.join().is_err() {
+                report.panicked_actors += 1;
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(report),
+        }
+    }
+}
+
+fn drain(entries: &mut [Entry<'_>], deadline: Instant, report: &mut ShutdownReport) -> Result<()> {
+    let mut entries: Vec<_> = entries
+        .iter_mut()
+        .filter_map(|entry| match entry.drain {
+            Some(ref mut handler) => Some((&entry.socket, handler)),
+            None => None,
+        })
+        .collect();
+    loop {
+        let mut items: Vec<_> = entries
+            .iter()
+            .map(|&(socket, _)| socket.as_poll_item(POLLIN))
+            .collect();
+        // Only look at what is already queued.
+        if poll(&mut items, 0)? == 0 {
+            return Ok(());
+        }
+        for (item, (socket, handler)) in items.iter().zip(entries.iter_mut()) {
+            if !item.is_readable() {
+                continue;
+            }
+            loop {
+                if Instant::now() >= deadline {
+                    report.deadline_exceeded = true;
+                    return Ok(());
+                }
+                match socket.recv_multipart(DONTWAIT) {
+                    Ok(msg) => {
+                        report.drained += 1;
+                        handler(msg);
+                    }
+                    Err(Error::EAGAIN) => break,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+}
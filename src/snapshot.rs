@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::result;
+
+use crate::{Result, Socket};
+
+/// The value of a socket option, as recorded in an `OptionSnapshot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionValue {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Str(String),
+    Bytes(Vec<u8>),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            OptionValue::Bool(value) => write!(f, "{}", value),
+            OptionValue::Int(value) => write!(f, "{}", value),
+            OptionValue::UInt(value) => write!(f, "{}", value),
+            OptionValue::Str(ref value) => write!(f, "{:?}", value),
+            OptionValue::Bytes(ref value) => {
+                f.write_str("0x")?;
+                for byte in value {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(value: bool) -> Self {
+        OptionValue::Bool(value)
+    }
+}
+
+impl From<i32> for OptionValue {
+    fn from(value: i32) -> Self {
+        OptionValue::Int(value.into())
+    }
+}
+
+impl From<i64> for OptionValue {
+    fn from(value: i64) -> Self {
+        OptionValue::Int(value)
+    }
+}
+
+impl From<u64> for OptionValue {
+    fn from(value: u64) -> Self {
+        OptionValue::UInt(value)
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(value: String) -> Self {
+        OptionValue::Str(value)
+    }
+}
+
+impl From<Vec<u8>> for OptionValue {
+    fn from(value: Vec<u8>) -> Self {
+        OptionValue::Bytes(value)
+    }
+}
+
+impl From<result::Result<String, Vec<u8>>> for OptionValue {
+    fn from(value: result::Result<String, Vec<u8>>) -> Self {
+        match value {
+            Ok(s) => OptionValue::Str(s),
+            Err(bytes) => OptionValue::Bytes(bytes),
+        }
+    }
+}
+
+/// The readable options of a socket at a point in time, keyed by their
+/// `libzmq` name without the `ZMQ_` prefix, e.g. `"sndhwm"`.
+///
+/// Created by `Socket::option_snapshot`. Options which cannot be read --
+/// write-only ones, or those of a mechanism `libzmq` was built without --
+/// are absent, as are secrets such as `ZMQ_CURVE_SECRETKEY` and
+/// `ZMQ_PLAIN_PASSWORD`. Transient state like `ZMQ_EVENTS` is not
+/// recorded either.
+///
+/// # Examples
+///
+/// ```
+/// let ctx = zmq::Context::new();
+/// let staging = ctx.socket(zmq::DEALER).unwrap();
+/// let production = ctx.socket(zmq::DEALER).unwrap();
+/// production.set_sndhwm(10_000).unwrap();
+///
+/// let before = staging.option_snapshot().unwrap();
+/// let after = production.option_snapshot().unwrap();
+/// for change in before.diff(&after) {
+///     println!("{}", change);
+/// }
+/// assert_eq!(before.diff(&after)[0].name, "sndhwm");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptionSnapshot {
+    options: BTreeMap<&'static str, OptionValue>,
+}
+
+/// A difference between two `OptionSnapshot`s.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OptionChange {
+    pub name: &'static str,
+    /// The value in the first snapshot, if present.
+    pub before: Option<OptionValue>,
+    /// The value in the second snapshot, if present.
+    pub after: Option<OptionValue>,
+}
+
+impl fmt::Display for OptionChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let show = |value: &Option<OptionValue>| match *value {
+            Some(ref value) => value.to_string(),
+            None => "<unset>".to_string(),
+        };
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.name,
+            show(&self.before),
+            show(&self.after)
+        )
+    }
+}
+
+impl OptionSnapshot {
+    /// Return the recorded value of an option.
+    pub fn get(&self, name: &str) -> Option<&OptionValue> {
+        self.options.get(name)
+    }
+
+    /// Iterate over the recorded options, in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &OptionValue)> {
+        self.options.iter().map(|(&name, value)| (name, value))
+    }
+
+    /// Return the number of recorded options.
+    pub fn len(&self) -> usize {
+        self.options.len()
+    }
+
+    /// Return whether no options were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.options.is_empty()
+    }
+
+    /// List the options that differ between `self` and `other`, in
+    /// alphabetical order.
+    pub fn diff(&self, other: &OptionSnapshot) -> Vec<OptionChange> {
+        let mut names: Vec<_> = self.options.keys().chain(other.options.keys()).collect();
+        names.sort();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|&name| {
+                let before = self.options.get(name);
+                let after = other.options.get(name);
+                if before == after {
+                    return None;
+                }
+                Some(OptionChange {
+                    name,
+                    before: before.cloned(),
+                    after: after.cloned(),
+                })
+            })
+            .collect()
+    }
+
+    fn record<T: Into<OptionValue>>(&mut self, name: &'static str, value: Result<T>) {
+        if let Ok(value) = value {
+            self.options.insert(name, value.into());
+        }
+    }
+}
+
+macro_rules! snapshot {
+    ($socket:expr, $snapshot:expr, { $($name:ident => $getter:ident),* $(,)? }) => {
+        $( $snapshot.record(stringify!($name), $socket.$getter()); )*
+    };
+}
+
+pub(crate) fn take(socket: &Socket) -> Result<OptionSnapshot> {
+    let mut snapshot = OptionSnapshot::default();
+    let socket_type = socket.get_socket_type()?;
+    snapshot.record("type", Ok(format!("{:?}", socket_type)));
+    snapshot.record(
+        "mechanism",
+        socket
+            .get_mechanism()
+            .map(|m| format!("{:?}", m).trim_start_matches("ZMQ_").to_string()),
+    );
+    // Write-only options, such as `probe_router`, `router_handover` and
+    // `router_mandatory`, always fail with `EINVAL` and are left out.
+    snapshot!(socket, snapshot, {
+        affinity => get_affinity,
+        backlog => get_backlog,
+        bindtodevice => get_bindtodevice,
+        connect_timeout => get_connect_timeout,
+        conflate => is_conflate,
+        curve_publickey => get_curve_publickey,
+        curve_server => is_curve_server,
+        curve_serverkey => get_curve_serverkey,
+        gssapi_plaintext => is_gssapi_plaintext,
+        gssapi_principal => get_gssapi_principal,
+        gssapi_server => is_gssapi_server,
+        gssapi_service_principal => get_gssapi_service_principal,
+        handshake_ivl => get_handshake_ivl,
+        heartbeat_ivl => get_heartbeat_ivl,
+        heartbeat_timeout => get_heartbeat_timeout,
+        heartbeat_ttl => get_heartbeat_ttl,
+        immediate => is_immediate,
+        invert_matching => is_invert_matching,
+        ipv6 => is_ipv6,
+        last_endpoint => get_last_endpoint,
+        linger => get_linger,
+        maxmsgsize => get_maxmsgsize,
+        multicast_hops => get_multicast_hops,
+        multicast_maxtpdu => get_multicast_maxtpdu,
+        plain_server => is_plain_server,
+        plain_username => get_plain_username,
+        rate => get_rate,
+        rcvbuf => get_rcvbuf,
+        rcvhwm => get_rcvhwm,
+        rcvtimeo => get_rcvtimeo,
+        reconnect_ivl => get_reconnect_ivl,
+        reconnect_ivl_max => get_reconnect_ivl_max,
+        recovery_ivl => get_recovery_ivl,
+        routing_id => get_routing_id,
+        sndbuf => get_sndbuf,
+        sndhwm => get_sndhwm,
+        sndtimeo => get_sndtimeo,
+        socks_proxy => get_socks_proxy,
+        tcp_keepalive => get_tcp_keepalive,
+        tcp_keepalive_cnt => get_tcp_keepalive_cnt,
+        tcp_keepalive_idle => get_tcp_keepalive_idle,
+        tcp_keepalive_intvl => get_tcp_keepalive_intvl,
+        tos => get_tos,
+        use_fd => get_use_fd,
+        vmci_buffer_max_size => get_vmci_buffer_max_size,
+        vmci_buffer_min_size => get_vmci_buffer_min_size,
+        vmci_buffer_size => get_vmci_buffer_size,
+        vmci_connect_timeout => get_vmci_connect_timeout,
+        zap_domain => get_zap_domain,
+    });
+    #[cfg(feature = "draft-api")]
+    snapshot.record("multicast_loop", socket.is_multicast_loop());
+    Ok(snapshot)
+}
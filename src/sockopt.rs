@@ -4,7 +4,8 @@ use std::result;
 use std::string::FromUtf8Error;
 use std::{mem, ptr, str};
 
-use super::{PollEvents, Result};
+use super::SocketType::*;
+use super::{PollEvents, Result, SocketType};
 
 pub trait Getter
 where
@@ -144,12 +145,49 @@ impl<'a> Setter for &'a [u8] {
     }
 }
 
+// Large enough for any byte or string valued option of `libzmq` 4.3; the
+// longest value is that of `ZMQ_LAST_ENDPOINT`.
+const BYTES_MAX: size_t = 1024;
+
+impl Getter for Vec<u8> {
+    fn get(sock: *mut c_void, opt: c_int) -> Result<Self> {
+        get_bytes(sock, opt, BYTES_MAX)
+    }
+}
+
 impl Getter for PollEvents {
     fn get(sock: *mut c_void, opt: c_int) -> Result<Self> {
         get::<c_int>(sock, opt).map(|bits| PollEvents::from_bits_truncate(bits as i16))
     }
 }
 
+/// A type that socket option values can be read as, see
+/// `Socket::getsockopt`.
+///
+/// This trait is sealed, and implemented for `i32`, `i64`, `u64`, `bool`
+/// and `Vec<u8>`.
+pub trait GetSockOpt: Getter {}
+
+impl GetSockOpt for i32 {}
+impl GetSockOpt for i64 {}
+impl GetSockOpt for u64 {}
+impl GetSockOpt for bool {}
+impl GetSockOpt for Vec<u8> {}
+
+/// A type that socket option values can be set from, see
+/// `Socket::setsockopt`.
+///
+/// This trait is sealed, and implemented for `i32`, `i64`, `u64`, `bool`,
+/// `&[u8]` and `&str`.
+pub trait SetSockOpt: Setter {}
+
+impl SetSockOpt for i32 {}
+impl SetSockOpt for i64 {}
+impl SetSockOpt for u64 {}
+impl SetSockOpt for bool {}
+impl SetSockOpt for &[u8] {}
+impl SetSockOpt for &str {}
+
 pub fn get<T: Getter>(sock: *mut c_void, opt: c_int) -> Result<T> {
     T::get(sock, opt)
 }
@@ -157,3 +195,23 @@ pub fn get<T: Getter>(sock: *mut c_void, opt: c_int) -> Result<T> {
 pub fn set<T: Setter>(sock: *mut c_void, opt: c_int, value: T) -> Result<()> {
     T::set(sock, opt, value)
 }
+
+/// Return the socket types an option applies to, or `None` if it applies
+/// to all of them.
+///
+/// Only options that are clearly specific to some socket types are listed;
+/// e.g. `ZMQ_CONFLATE` is documented for a few types only, but accepted by
+/// all.
+pub fn applicable_types(opt: c_int) -> Option<&'static [SocketType]> {
+    let types: &'static [SocketType] = match opt as u32 {
+        zmq_sys::ZMQ_SUBSCRIBE | zmq_sys::ZMQ_UNSUBSCRIBE => &[SUB, XPUB],
+        zmq_sys::ZMQ_INVERT_MATCHING => &[PUB, SUB, XPUB, XSUB],
+        zmq_sys::ZMQ_ROUTER_MANDATORY | zmq_sys::ZMQ_ROUTER_HANDOVER => &[ROUTER],
+        zmq_sys::ZMQ_PROBE_ROUTER => &[ROUTER, DEALER, REQ],
+        zmq_sys::ZMQ_REQ_RELAXED | zmq_sys::ZMQ_REQ_CORRELATE => &[REQ],
+        zmq_sys::ZMQ_XPUB_VERBOSE | zmq_sys::ZMQ_XPUB_WELCOME_MSG => &[XPUB],
+        zmq_sys::ZMQ_XPUB_VERBOSER | zmq_sys::ZMQ_XPUB_MANUAL => &[XPUB],
+        _ => return None,
+    };
+    Some(types)
+}
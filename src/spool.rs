@@ -0,0 +1,199 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::codec;
+use crate::{Error, Socket, DONTWAIT};
+
+/// A bounded, on-disk queue of messages that could not be sent.
+///
+/// `send` tries to send a message without blocking; if the socket cannot
+/// accept it -- e.g. a `PUSH` socket without any connected peer, or whose
+/// peers' queues are full -- the message is appended to the spool file
+/// instead. Spooled messages are sent, in order, before any new message,
+/// and can be replayed explicitly using `flush`, e.g. once a monitor reports
+/// that a peer connected.
+///
+/// Note that a `PUB` socket never blocks, but drops messages when there is
+/// no subscriber; use `spool` to store messages explicitly when the link is
+/// known to be down.
+///
+/// Delivery is at-least-once: the spool file is only rewritten after a
+/// `flush`, so messages sent before a crash in the middle of a flush are
+/// sent again when the spool is reopened.
+///
+/// # Examples
+///
+/// ```
+/// # let dir = std::env::temp_dir().join(format!("zmq-spool-doc-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir).unwrap();
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PUSH).unwrap();
+/// socket.bind("inproc://spooled").unwrap();
+///
+/// let mut spooler = zmq::Spooler::open(dir.join("outbox"), 1 << 20).unwrap();
+/// // There is no peer yet, so the message ends up on disk.
+/// spooler.send(&socket, &["hello"]).unwrap();
+/// assert_eq!(spooler.len(), 1);
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Spooler {
+    path: PathBuf,
+    max_bytes: u64,
+    len: usize,
+    bytes: u64,
+}
+
+impl Spooler {
+    /// Open the spool stored at `path`, creating it if necessary, and limit
+    /// its size to `max_bytes`.
+    pub fn open<P: AsRef<Path>>(path: P, max_bytes: u64) -> io::Result<Spooler> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let mut reader = BufReader::new(file);
+        let mut len = 0;
+        let mut bytes = 0;
+        loop {
+            match codec::read_parts(&mut reader) {
+                Ok(Some(parts)) => {
+                    len += 1;
+                    bytes += codec::encoded_len(&parts);
+                }
+                Ok(None) => break,
+                Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+        }
+        // A crash in the middle of `spool` leaves a partial record at the
+        // end, which would otherwise make the spool unreadable, or corrupt
+        // the records appended after it.
+        let file = reader.into_inner();
+        let file_len = file.metadata()?.len();
+        if file_len > bytes {
+            log::warn!(
+                "truncating {} bytes of a partial record from spool {}",
+                file_len - bytes,
+                path.display()
+            );
+            file.set_len(bytes)?;
+        }
+        Ok(Spooler {
+            path,
+            max_bytes,
+            len,
+            bytes,
+        })
+    }
+
+    /// Return the number of spooled messages.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return whether there are no spooled messages.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the size of the spool file, in bytes.
+    pub fn spooled_bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Send a multipart message, or spool it if the socket cannot accept it
+    /// right now.
+    ///
+    /// If the spool is full, the message is dropped and an error of kind
+    /// `Other` wrapping `Error::ENOBUFS` is returned.
+    pub fn send<T: AsRef<[u8]>>(&mut self, socket: &Socket, parts: &[T]) -> io::Result<()> {
+        if !self.is_empty() {
+            self.flush(socket)?;
+        }
+        if self.is_empty() {
+            match socket.send_multipart(parts.iter().map(|part| part.as_ref()), DONTWAIT) {
+                Ok(()) => return Ok(()),
+                Err(Error::EAGAIN) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.spool(parts)
+    }
+
+    /// Append a multipart message to the spool, without trying to send it.
+    pub fn spool<T: AsRef<[u8]>>(&mut self, parts: &[T]) -> io::Result<()> {
+        let len = codec::encoded_len(parts);
+        if self.bytes.saturating_add(len) > self.max_bytes {
+            return Err(Error::ENOBUFS.into());
+        }
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        let mut writer = BufWriter::new(file);
+        codec::write_parts(&mut writer, parts)?;
+        writer.flush()?;
+        self.len += 1;
+        self.bytes += len;
+        Ok(())
+    }
+
+    /// Send as many spooled messages as the socket accepts without blocking,
+    /// returning how many were sent.
+    pub fn flush(&mut self, socket: &Socket) -> io::Result<usize> {
+        if self.is_empty() {
+            return Ok(0);
+        }
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut sent = 0;
+        let mut sent_bytes = 0;
+        let mut result = Ok(());
+        while let Some(parts) = codec::read_parts(&mut reader)? {
+            let len = codec::encoded_len(&parts);
+            match socket.send_multipart(parts, DONTWAIT) {
+                Ok(()) => {
+                    sent += 1;
+                    sent_bytes += len;
+                }
+                Err(Error::EAGAIN) => break,
+                Err(e) => {
+                    result = Err(e.into());
+                    break;
+                }
+            }
+        }
+        self.remove_front(&mut reader, sent, sent_bytes)?;
+        result.map(|()| sent)
+    }
+
+    // Drop the first `count` messages, occupying `bytes` bytes, from the
+    // spool file.
+    fn remove_front(
+        &mut self,
+        reader: &mut BufReader<File>,
+        count: usize,
+        bytes: u64,
+    ) -> io::Result<()> {
+        if count == 0 {
+            return Ok(());
+        }
+        if count == self.len {
+            OpenOptions::new()
+                .write(true)
+                .open(&self.path)?
+                .set_len(0)?;
+        } else {
+            let mut tmp_path = self.path.clone().into_os_string();
+            tmp_path.push(".tmp");
+            let mut tmp = File::create(&tmp_path)?;
+            reader.seek(SeekFrom::Start(bytes))?;
+            io::copy(&mut reader.by_ref(), &mut tmp)?;
+            tmp.sync_all()?;
+            fs::rename(&tmp_path, &self.path)?;
+        }
+        self.len -= count;
+        self.bytes -= bytes;
+        Ok(())
+    }
+}
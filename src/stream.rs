@@ -0,0 +1,215 @@
+//! Per-connection byte streams over a `STREAM` socket.
+//!
+//! A `STREAM` socket talks raw TCP, but multiplexes all connections over a
+//! single socket: each message is a pair of frames, holding the identity of
+//! the connection and a chunk of data. `StreamConnections` demultiplexes
+//! the received chunks into a buffer per connection, which is read through
+//! an `io::Read` adapter, while an `io::Write` adapter sends to a single
+//! connection.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::io::{Read, Write};
+//! use std::net::TcpStream;
+//! use zmq::stream::{ConnectionEvent, StreamConnections};
+//!
+//! let ctx = zmq::Context::new();
+//! let socket = ctx.socket(zmq::STREAM).unwrap();
+//! socket.bind("tcp://127.0.0.1:*").unwrap();
+//! let endpoint = socket.get_last_endpoint().unwrap().unwrap();
+//! let mut connections = StreamConnections::new(socket).unwrap();
+//!
+//! let mut client = TcpStream::connect(endpoint.trim_start_matches("tcp://")).unwrap();
+//! let peer = match connections.recv(0).unwrap() {
+//!     ConnectionEvent::Connected(peer) => peer,
+//!     other => panic!("unexpected event: {:?}", other),
+//! };
+//!
+//! client.write_all(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+//! let mut request = Vec::new();
+//! while !request.ends_with(b"\r\n\r\n") {
+//!     connections.recv(0).unwrap();
+//!     connections.reader(&peer).read_to_end(&mut request).ok();
+//! }
+//!
+//! write!(connections.writer(&peer), "HTTP/1.0 200 OK\r\n\r\nhello").unwrap();
+//! let mut response = [0; 24];
+//! client.read_exact(&mut response).unwrap();
+//! assert_eq!(&response, b"HTTP/1.0 200 OK\r\n\r\nhello");
+//! connections.disconnect(&peer).unwrap();
+//! ```
+
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::VecDeque;
+use std::io;
+
+use crate::{Error, Result, Socket, SocketType, SNDMORE};
+
+/// An event produced by `StreamConnections::recv`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A peer connected, or a connection made via `connect` was
+    /// established.
+    Connected(Vec<u8>),
+    /// Data from the peer was buffered, and can be read via `reader`.
+    Data(Vec<u8>),
+    /// The peer disconnected. Its remaining data can still be read, after
+    /// which its reader reports the end of the stream.
+    Disconnected(Vec<u8>),
+}
+
+#[derive(Debug, Default)]
+struct Connection {
+    buffer: VecDeque<u8>,
+    closed: bool,
+}
+
+/// Buffers the data of each connection of a `STREAM` socket.
+///
+/// This relies on `ZMQ_STREAM_NOTIFY` being enabled (the default), so that
+/// connects and disconnects are signalled by empty messages.
+pub struct StreamConnections {
+    socket: Socket,
+    connections: HashMap<Vec<u8>, Connection>,
+}
+
+impl StreamConnections {
+    /// Wrap a `STREAM` socket, failing with `Error::EINVAL` for other
+    /// socket types.
+    pub fn new(socket: Socket) -> Result<StreamConnections> {
+        if socket.get_socket_type()? != SocketType::STREAM {
+            return Err(Error::EINVAL);
+        }
+        Ok(StreamConnections {
+            socket,
+            connections: HashMap::new(),
+        })
+    }
+
+    /// Return a reference to the underlying socket, e.g. to bind, connect
+    /// or poll it.
+    pub fn as_socket(&self) -> &Socket {
+        &self.socket
+    }
+
+    /// Return the underlying socket.
+    pub fn into_inner(self) -> Socket {
+        self.socket
+    }
+
+    /// Return the identities of the known connections, including closed
+    /// ones whose data has not been read completely.
+    pub fn peers(&self) -> impl Iterator<Item = &[u8]> {
+        self.connections.keys().map(Vec::as_slice)
+    }
+
+    /// Receive the next chunk or notification from the socket, and buffer
+    /// it for the connection it belongs to.
+    pub fn recv(&mut self, flags: i32) -> Result<ConnectionEvent> {
+        let peer = self.socket.recv_bytes(flags)?;
+        // The second frame is already queued along with the first one.
+        let data = self.socket.recv_bytes(0)?;
+        if !data.is_empty() {
+            let connection = self.connections.entry(peer.clone()).or_default();
+            connection.buffer.extend(data);
+            return Ok(ConnectionEvent::Data(peer));
+        }
+        match self.connections.entry(peer.clone()) {
+            Entry::Occupied(mut entry) => {
+                if entry.get().buffer.is_empty() {
+                    entry.remove();
+                } else {
+                    entry.get_mut().closed = true;
+                }
+                Ok(ConnectionEvent::Disconnected(peer))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(Connection::default());
+                Ok(ConnectionEvent::Connected(peer))
+            }
+        }
+    }
+
+    /// Return a reader for the data buffered for `peer`.
+    ///
+    /// Reading never blocks: when the buffer is empty, the reader fails
+    /// with `io::ErrorKind::WouldBlock` while the connection is open, and
+    /// returns 0 once it is closed or unknown.
+    pub fn reader<'a>(&'a mut self, peer: &'a [u8]) -> ConnectionReader<'a> {
+        ConnectionReader {
+            connections: &mut self.connections,
+            peer,
+        }
+    }
+
+    /// Return a writer sending to `peer`. Each write is sent as one chunk.
+    pub fn writer<'a>(&'a self, peer: &'a [u8]) -> ConnectionWriter<'a> {
+        ConnectionWriter {
+            socket: &self.socket,
+            peer,
+        }
+    }
+
+    /// Close the connection to `peer`, after sending the data written so
+    /// far. Buffered data that has not been read is discarded.
+    ///
+    /// The connection is closed asynchronously, and only once the socket
+    /// has processed the resulting commands, which happens whenever it is
+    /// used, e.g. by the next `recv` or poll.
+    pub fn disconnect(&mut self, peer: &[u8]) -> Result<()> {
+        self.connections.remove(peer);
+        self.socket.send(peer, SNDMORE)?;
+        self.socket.send("", 0)
+    }
+}
+
+/// Reads the data buffered for one connection, see
+/// `StreamConnections::reader`.
+pub struct ConnectionReader<'a> {
+    connections: &'a mut HashMap<Vec<u8>, Connection>,
+    peer: &'a [u8],
+}
+
+impl<'a> io::Read for ConnectionReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let connection = match self.connections.get_mut(self.peer) {
+            Some(connection) => connection,
+            None => return Ok(0),
+        };
+        if connection.buffer.is_empty() {
+            if connection.closed {
+                self.connections.remove(self.peer);
+                return Ok(0);
+            }
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        let len = buf.len().min(connection.buffer.len());
+        for (dst, src) in buf.iter_mut().zip(connection.buffer.drain(..len)) {
+            *dst = src;
+        }
+        Ok(len)
+    }
+}
+
+/// Sends data to one connection, see `StreamConnections::writer`.
+pub struct ConnectionWriter<'a> {
+    socket: &'a Socket,
+    peer: &'a [u8],
+}
+
+impl<'a> io::Write for ConnectionWriter<'a> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            // An empty chunk would close the connection.
+            return Ok(0);
+        }
+        self.socket.send(self.peer, SNDMORE)?;
+        self.socket.send(buf, 0)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
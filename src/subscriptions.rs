@@ -0,0 +1,97 @@
+use std::collections::BTreeSet;
+
+/// A subscription message, as received on an `XPUB` socket.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SubscriptionChange {
+    /// A subscription to messages starting with the given prefix.
+    Subscribe(Vec<u8>),
+    /// The removal of a subscription.
+    Unsubscribe(Vec<u8>),
+}
+
+impl SubscriptionChange {
+    /// Parse a subscription message, which consists of a `1` (subscribe) or
+    /// `0` (unsubscribe) byte followed by the topic prefix.
+    ///
+    /// Returns `None` for other messages, which `libzmq` passes upstream
+    /// unchanged.
+    pub fn parse(msg: &[u8]) -> Option<SubscriptionChange> {
+        match msg.split_first() {
+            Some((1, topic)) => Some(SubscriptionChange::Subscribe(topic.to_vec())),
+            Some((0, topic)) => Some(SubscriptionChange::Unsubscribe(topic.to_vec())),
+            _ => None,
+        }
+    }
+
+    /// Return the topic prefix concerned by the change.
+    pub fn topic(&self) -> &[u8] {
+        match *self {
+            SubscriptionChange::Subscribe(ref topic) => topic,
+            SubscriptionChange::Unsubscribe(ref topic) => topic,
+        }
+    }
+}
+
+/// The set of subscriptions currently active on an `XPUB` socket.
+///
+/// Feed it the changes received via `XPubSocket::recv_subscription`. By
+/// default, `libzmq` forwards only the first subscription to a topic and
+/// the removal of the last one, which is exactly what the tracker needs;
+/// this also holds with `ZMQ_XPUB_VERBOSE`, but not with
+/// `ZMQ_XPUB_VERBOSER`, which forwards every unsubscription.
+///
+/// # Examples
+///
+/// ```
+/// use zmq::{SubscriptionChange, SubscriptionTracker};
+///
+/// let mut tracker = SubscriptionTracker::new();
+/// tracker.apply(&SubscriptionChange::Subscribe(b"weather.".to_vec()));
+/// assert!(tracker.has_subscribers(b"weather.berlin"));
+/// assert!(!tracker.has_subscribers(b"stocks.acme"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SubscriptionTracker {
+    topics: BTreeSet<Vec<u8>>,
+}
+
+impl SubscriptionTracker {
+    /// Create an empty tracker.
+    pub fn new() -> SubscriptionTracker {
+        SubscriptionTracker::default()
+    }
+
+    /// Record a subscription change, returning whether it altered the set.
+    pub fn apply(&mut self, change: &SubscriptionChange) -> bool {
+        match *change {
+            SubscriptionChange::Subscribe(ref topic) => self.topics.insert(topic.clone()),
+            SubscriptionChange::Unsubscribe(ref topic) => self.topics.remove(topic),
+        }
+    }
+
+    /// Iterate over the active subscription prefixes, in lexicographic order.
+    pub fn subscriptions(&self) -> impl Iterator<Item = &[u8]> {
+        self.topics.iter().map(Vec::as_slice)
+    }
+
+    /// Return whether a message with the given topic would be delivered to
+    /// any subscriber, i.e. whether any subscription is a prefix of it.
+    pub fn has_subscribers(&self, topic: &[u8]) -> bool {
+        self.topics.iter().any(|prefix| topic.starts_with(prefix))
+    }
+
+    /// Return the number of active subscriptions.
+    pub fn len(&self) -> usize {
+        self.topics.len()
+    }
+
+    /// Return whether there are no active subscriptions.
+    pub fn is_empty(&self) -> bool {
+        self.topics.is_empty()
+    }
+
+    /// Forget all subscriptions.
+    pub fn clear(&mut self) {
+        self.topics.clear();
+    }
+}
@@ -0,0 +1,184 @@
+use libc::{c_int, c_void};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::ptr;
+use std::time::Duration;
+
+use crate::{errno_to_error, timeout_millis, Error, Result};
+
+/// A set of recurring timers, backed by `libzmq`'s `zmq_timers_*` API.
+///
+/// `Timers` does not run on its own: a poll loop passes `timeout` to
+/// `poll_timeout`, and calls `execute` after each poll, which invokes the
+/// handlers of the timers that are due. A timer keeps firing at its interval
+/// until it is cancelled.
+///
+/// This requires the `draft-api` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::time::Duration;
+///
+/// let ctx = zmq::Context::new();
+/// let socket = ctx.socket(zmq::PULL).unwrap();
+/// socket.bind("inproc://timers-example").unwrap();
+///
+/// let heartbeats = Cell::new(0);
+/// let mut timers = zmq::Timers::new();
+/// timers
+///     .add(Duration::from_millis(10), |_| heartbeats.set(heartbeats.get() + 1))
+///     .unwrap();
+///
+/// while heartbeats.get() < 3 {
+///     let mut items = [socket.as_poll_item(zmq::POLLIN)];
+///     zmq::poll_timeout(&mut items, timers.timeout()).unwrap();
+///     timers.execute().unwrap();
+/// }
+/// ```
+pub struct Timers<'a> {
+    timers: *mut c_void,
+    handlers: HashMap<c_int, Box<dyn FnMut(i32) + 'a>>,
+}
+
+thread_local! {
+    // The timers that fired during the current `zmq_timers_execute` call,
+    // which runs on the calling thread.
+    static FIRED: RefCell<Vec<c_int>> = const { RefCell::new(Vec::new()) };
+}
+
+// Handlers are called from Rust after `zmq_timers_execute` returns, rather
+// than from within it, so that they may panic.
+unsafe extern "C" fn record_timer(timer_id: c_int, _arg: *mut c_void) {
+    FIRED.with(|fired| fired.borrow_mut().push(timer_id));
+}
+
+impl<'a> Timers<'a> {
+    /// Create a set without any timers.
+    pub fn new() -> Timers<'a> {
+        let timers = unsafe { zmq_sys::zmq_timers_new() };
+        if timers.is_null() {
+            panic!("failed to allocate timers");
+        }
+        Timers {
+            timers,
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Add a timer, which calls `handler` with its id every `interval`,
+    /// and return that id.
+    ///
+    /// Fails with `Error::EINVAL` if the interval is zero.
+    pub fn add<F>(&mut self, interval: Duration, handler: F) -> Result<i32>
+    where
+        F: FnMut(i32) + 'a,
+    {
+        let interval = interval_millis(interval)?;
+        let timer_id = unsafe {
+            zmq_sys::zmq_timers_add(self.timers, interval, Some(record_timer), ptr::null_mut())
+        };
+        if timer_id == -1 {
+            return Err(errno_to_error());
+        }
+        self.handlers.insert(timer_id, Box::new(handler));
+        Ok(timer_id)
+    }
+
+    /// Cancel a timer.
+    ///
+    /// Fails with `Error::EINVAL` if there is no timer with that id.
+    pub fn cancel(&mut self, timer_id: i32) -> Result<()> {
+        zmq_try!(unsafe { zmq_sys::zmq_timers_cancel(self.timers, timer_id) });
+        self.handlers.remove(&timer_id);
+        Ok(())
+    }
+
+    /// Change the interval of a timer. It next fires after the new interval
+    /// has elapsed, counting from now.
+    ///
+    /// Fails with `Error::EINVAL` if there is no timer with that id, or the
+    /// interval is zero.
+    pub fn set_interval(&mut self, timer_id: i32, interval: Duration) -> Result<()> {
+        let interval = interval_millis(interval)?;
+        zmq_try!(unsafe { zmq_sys::zmq_timers_set_interval(self.timers, timer_id, interval) });
+        Ok(())
+    }
+
+    /// Restart a timer, so that it next fires after its full interval has
+    /// elapsed, counting from now; e.g. to postpone a heartbeat whenever
+    /// other traffic was sent.
+    ///
+    /// Fails with `Error::EINVAL` if there is no timer with that id.
+    pub fn reset(&mut self, timer_id: i32) -> Result<()> {
+        zmq_try!(unsafe { zmq_sys::zmq_timers_reset(self.timers, timer_id) });
+        Ok(())
+    }
+
+    /// Return the time until the next timer is due, for passing to
+    /// `poll_timeout`, or `None` if there are no timers.
+    pub fn timeout(&self) -> Option<Duration> {
+        let millis = unsafe { zmq_sys::zmq_timers_timeout(self.timers) };
+        if millis < 0 {
+            None
+        } else {
+            Some(Duration::from_millis(millis as u64))
+        }
+    }
+
+    /// Call the handlers of all timers that are due.
+    pub fn execute(&mut self) -> Result<()> {
+        zmq_try!(unsafe { zmq_sys::zmq_timers_execute(self.timers) });
+        let fired = FIRED.with(|fired| fired.take());
+        for timer_id in fired {
+            if let Some(handler) = self.handlers.get_mut(&timer_id) {
+                handler(timer_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the number of timers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Return true if there are no timers.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+// `zmq_timers_execute` never returns if a timer with a zero interval is due,
+// since it keeps rescheduling it for the current time.
+fn interval_millis(interval: Duration) -> Result<usize> {
+    match timeout_millis(Some(interval)) {
+        0 => Err(Error::EINVAL),
+        millis => Ok(millis as usize),
+    }
+}
+
+impl<'a> Default for Timers<'a> {
+    fn default() -> Self {
+        Timers::new()
+    }
+}
+
+impl<'a> fmt::Debug for Timers<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Timers")
+            .field("len", &self.len())
+            .field("timeout", &self.timeout())
+            .finish()
+    }
+}
+
+impl<'a> Drop for Timers<'a> {
+    fn drop(&mut self) {
+        let rc = unsafe { zmq_sys::zmq_timers_destroy(&mut self.timers) };
+        assert_eq!(rc, 0);
+    }
+}
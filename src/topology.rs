@@ -0,0 +1,329 @@
+//! Declarative socket topologies.
+//!
+//! A topology describes a set of named sockets -- their type, options,
+//! endpoints, subscriptions and security settings -- in a JSON or TOML
+//! document, so that the wiring of an application can be changed without
+//! recompiling it. This module is only available with the `topology`
+//! feature enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use zmq::topology::Topology;
+//!
+//! let topology = Topology::from_toml(
+//!     r#"
+//!     [sockets.frontend]
+//!     type = "PULL"
+//!     bind = ["inproc://frontend"]
+//!     options = { rcvhwm = 1000 }
+//!
+//!     [sockets.producer]
+//!     type = "PUSH"
+//!     connect = ["inproc://frontend"]
+//!     options = { linger = 0 }
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! let ctx = zmq::Context::new();
+//! let sockets = topology.instantiate(&ctx).unwrap();
+//! sockets["producer"].send("hello", 0).unwrap();
+//! assert_eq!(sockets["frontend"].recv_bytes(0).unwrap(), b"hello");
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::error;
+use std::fmt;
+
+use serde::de::{self, Deserializer};
+use serde::Deserialize;
+
+use crate::{Context, Socket, SocketType};
+
+/// A set of named socket descriptions.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Topology {
+    /// The sockets, by name.
+    #[serde(default)]
+    pub sockets: BTreeMap<String, SocketConfig>,
+}
+
+/// The description of a single socket.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SocketConfig {
+    /// The socket type, written like the corresponding constant, e.g.
+    /// `"PUB"` or `"ROUTER"`.
+    #[serde(rename = "type", deserialize_with = "deserialize_socket_type")]
+    pub socket_type: SocketType,
+    /// Socket options, applied before any endpoint is bound or connected.
+    #[serde(default)]
+    pub options: SocketOptions,
+    /// Endpoints to bind to.
+    #[serde(default)]
+    pub bind: Vec<String>,
+    /// Endpoints to connect to.
+    #[serde(default)]
+    pub connect: Vec<String>,
+    /// Subscription prefixes, for `SUB` sockets.
+    #[serde(default)]
+    pub subscribe: Vec<String>,
+    /// The security mechanism to use, if any.
+    #[serde(default)]
+    pub security: Option<Security>,
+}
+
+/// Socket options settable from a topology document.
+///
+/// Options left out of the document keep their `libzmq` defaults.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SocketOptions {
+    pub identity: Option<String>,
+    pub linger: Option<i32>,
+    pub sndhwm: Option<i32>,
+    pub rcvhwm: Option<i32>,
+    pub sndtimeo: Option<i32>,
+    pub rcvtimeo: Option<i32>,
+    pub maxmsgsize: Option<i64>,
+    pub immediate: Option<bool>,
+    pub ipv6: Option<bool>,
+    pub conflate: Option<bool>,
+    pub reconnect_ivl: Option<i32>,
+    pub reconnect_ivl_max: Option<i32>,
+    pub tcp_keepalive: Option<i32>,
+}
+
+/// Security settings of a socket.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "mechanism", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Security {
+    /// `ZMQ_PLAIN` authentication. Servers only need `server = true`.
+    Plain {
+        #[serde(default)]
+        server: bool,
+        username: Option<String>,
+        password: Option<String>,
+        zap_domain: Option<String>,
+    },
+    /// `ZMQ_CURVE` encryption. Keys are given in Z85 encoding.
+    Curve {
+        #[serde(default)]
+        server: bool,
+        public_key: Option<String>,
+        secret_key: Option<String>,
+        server_key: Option<String>,
+        zap_domain: Option<String>,
+    },
+}
+
+/// An error encountered while loading or instantiating a topology.
+#[derive(Debug)]
+pub enum TopologyError {
+    /// The JSON document could not be parsed.
+    Json(serde_json::Error),
+    /// The TOML document could not be parsed.
+    Toml(toml::de::Error),
+    /// Creating or configuring the named socket failed.
+    Socket { name: String, error: crate::Error },
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TopologyError::Json(ref e) => write!(f, "invalid JSON topology: {}", e),
+            TopologyError::Toml(ref e) => write!(f, "invalid TOML topology: {}", e),
+            TopologyError::Socket { ref name, error } => {
+                write!(f, "failed to set up socket `{}`: {}", name, error)
+            }
+        }
+    }
+}
+
+impl error::Error for TopologyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            TopologyError::Json(ref e) => Some(e),
+            TopologyError::Toml(ref e) => Some(e),
+            TopologyError::Socket { ref error, .. } => Some(error),
+        }
+    }
+}
+
+impl Topology {
+    /// Parse a topology from a JSON document.
+    pub fn from_json(s: &str) -> Result<Topology, TopologyError> {
+        serde_json::from_str(s).map_err(TopologyError::Json)
+    }
+
+    /// Parse a topology from a TOML document.
+    pub fn from_toml(s: &str) -> Result<Topology, TopologyError> {
+        toml::from_str(s).map_err(TopologyError::Toml)
+    }
+
+    /// Create, configure, bind and connect all sockets of the topology.
+    ///
+    /// Sockets are set up in the order of their names. If any socket fails,
+    /// the sockets created so far are closed and the error is returned.
+    pub fn instantiate(&self, ctx: &Context) -> Result<HashMap<String, Socket>, TopologyError> {
+        let mut sockets = HashMap::with_capacity(self.sockets.len());
+        for (name, config) in &self.sockets {
+            let socket = config
+                .instantiate(ctx)
+                .map_err(|error| TopologyError::Socket {
+                    name: name.clone(),
+                    error,
+                })?;
+            sockets.insert(name.clone(), socket);
+        }
+        Ok(sockets)
+    }
+}
+
+impl SocketConfig {
+    /// Create a socket according to this description.
+    pub fn instantiate(&self, ctx: &Context) -> crate::Result<Socket> {
+        let mut builder = ctx.build_socket(self.socket_type);
+        let options = self.options.clone();
+        builder = builder.option(move |socket| options.apply(socket));
+        if let Some(security) = self.security.clone() {
+            builder = builder.option(move |socket| security.apply(socket));
+        }
+        for prefix in &self.subscribe {
+            let prefix = prefix.clone();
+            builder = builder.option(move |socket| socket.set_subscribe(prefix.as_bytes()));
+        }
+        for endpoint in &self.bind {
+            builder = builder.bind_to(endpoint);
+        }
+        for endpoint in &self.connect {
+            builder = builder.connect_to(endpoint);
+        }
+        builder.build()
+    }
+}
+
+macro_rules! apply_options {
+    ($options:expr, $socket:expr, $( $name:ident => $setter:ident ),*) => {
+        $(
+            if let Some(value) = $options.$name {
+                $socket.$setter(value)?;
+            }
+        )*
+    };
+}
+
+impl SocketOptions {
+    fn apply(&self, socket: &Socket) -> crate::Result<()> {
+        if let Some(ref identity) = self.identity {
+            socket.set_identity(identity.as_bytes())?;
+        }
+        apply_options!(self, socket,
+            linger => set_linger,
+            sndhwm => set_sndhwm,
+            rcvhwm => set_rcvhwm,
+            sndtimeo => set_sndtimeo,
+            rcvtimeo => set_rcvtimeo,
+            maxmsgsize => set_maxmsgsize,
+            immediate => set_immediate,
+            ipv6 => set_ipv6,
+            conflate => set_conflate,
+            reconnect_ivl => set_reconnect_ivl,
+            reconnect_ivl_max => set_reconnect_ivl_max,
+            tcp_keepalive => set_tcp_keepalive
+        );
+        Ok(())
+    }
+}
+
+impl Security {
+    fn apply(&self, socket: &Socket) -> crate::Result<()> {
+        match *self {
+            Security::Plain {
+                server,
+                ref username,
+                ref password,
+                ref zap_domain,
+            } => {
+                if server {
+                    socket.set_plain_server(true)?;
+                }
+                if username.is_some() {
+                    socket.set_plain_username(username.as_deref())?;
+                }
+                if password.is_some() {
+                    socket.set_plain_password(password.as_deref())?;
+                }
+                if let Some(ref domain) = *zap_domain {
+                    socket.set_zap_domain(domain)?;
+                }
+            }
+            Security::Curve {
+                server,
+                ref public_key,
+                ref secret_key,
+                ref server_key,
+                ref zap_domain,
+            } => {
+                if server {
+                    socket.set_curve_server(true)?;
+                }
+                if let Some(ref key) = *public_key {
+                    socket.set_curve_publickey(key.as_bytes())?;
+                }
+                if let Some(ref key) = *secret_key {
+                    socket.set_curve_secretkey(key.as_bytes())?;
+                }
+                if let Some(ref key) = *server_key {
+                    socket.set_curve_serverkey(key.as_bytes())?;
+                }
+                if let Some(ref domain) = *zap_domain {
+                    socket.set_zap_domain(domain)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn deserialize_socket_type<'de, D>(deserializer: D) -> Result<SocketType, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    let socket_type = match name.to_ascii_uppercase().as_str() {
+        "PAIR" => SocketType::PAIR,
+        "PUB" => SocketType::PUB,
+        "SUB" => SocketType::SUB,
+        "REQ" => SocketType::REQ,
+        "REP" => SocketType::REP,
+        "DEALER" => SocketType::DEALER,
+        "ROUTER" => SocketType::ROUTER,
+        "PULL" => SocketType::PULL,
+        "PUSH" => SocketType::PUSH,
+        "XPUB" => SocketType::XPUB,
+        "XSUB" => SocketType::XSUB,
+        "STREAM" => SocketType::STREAM,
+        #[cfg(feature = "draft-api")]
+        "SERVER" => SocketType::SERVER,
+        #[cfg(feature = "draft-api")]
+        "CLIENT" => SocketType::CLIENT,
+        #[cfg(feature = "draft-api")]
+        "RADIO" => SocketType::RADIO,
+        #[cfg(feature = "draft-api")]
+        "DISH" => SocketType::DISH,
+        #[cfg(feature = "draft-api")]
+        "GATHER" => SocketType::GATHER,
+        #[cfg(feature = "draft-api")]
+        "SCATTER" => SocketType::SCATTER,
+        _ => {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Str(&name),
+                &"a socket type such as \"PUB\" or \"ROUTER\"",
+            ))
+        }
+    };
+    Ok(socket_type)
+}
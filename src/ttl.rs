@@ -0,0 +1,152 @@
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{Error, Message, Result, Socket, SNDMORE};
+
+/// The length of an encoded `TtlStamp`, in bytes.
+pub const TTL_STAMP_LEN: usize = 16;
+
+/// A frame recording when a message was sent and how long it stays valid.
+///
+/// It is encoded as two big-endian 64-bit integers: the sending time, in
+/// microseconds since the Unix epoch, and the time to live, in
+/// microseconds. Since expiry is judged by the receiver's clock, the
+/// clocks of sender and receiver must be reasonably synchronized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TtlStamp {
+    pub sent_at: SystemTime,
+    pub ttl: Duration,
+}
+
+fn to_micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+impl TtlStamp {
+    /// Create a stamp for a message sent now.
+    pub fn now(ttl: Duration) -> TtlStamp {
+        TtlStamp {
+            sent_at: SystemTime::now(),
+            ttl,
+        }
+    }
+
+    /// Return the time at which the message expires, or `None` if that
+    /// lies beyond the range of `SystemTime`, e.g. for a TTL of
+    /// `Duration::MAX`, in which case it never expires.
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        self.sent_at.checked_add(self.ttl)
+    }
+
+    /// Return whether the message has expired at `now`.
+    pub fn is_expired_at(&self, now: SystemTime) -> bool {
+        matches!(self.expires_at(), Some(expires_at) if now > expires_at)
+    }
+
+    /// Encode the stamp as a frame.
+    pub fn encode(&self) -> [u8; TTL_STAMP_LEN] {
+        let sent_at = self.sent_at.duration_since(UNIX_EPOCH).map_or(0, to_micros);
+        let mut frame = [0; TTL_STAMP_LEN];
+        frame[..8].copy_from_slice(&sent_at.to_be_bytes());
+        frame[8..].copy_from_slice(&to_micros(self.ttl).to_be_bytes());
+        frame
+    }
+
+    /// Decode a frame produced by `encode`, returning `None` if it has the
+    /// wrong length, or its send time cannot be represented as a
+    /// `SystemTime` on this platform.
+    pub fn decode(frame: &[u8]) -> Option<TtlStamp> {
+        if frame.len() != TTL_STAMP_LEN {
+            return None;
+        }
+        let mut sent_at = [0; 8];
+        let mut ttl = [0; 8];
+        sent_at.copy_from_slice(&frame[..8]);
+        ttl.copy_from_slice(&frame[8..]);
+        Some(TtlStamp {
+            sent_at: UNIX_EPOCH.checked_add(Duration::from_micros(u64::from_be_bytes(sent_at)))?,
+            ttl: Duration::from_micros(u64::from_be_bytes(ttl)),
+        })
+    }
+}
+
+/// Send a multipart message preceded by a `TtlStamp` frame, which marks it
+/// as valid for `ttl` from now.
+///
+/// Use an `ExpiryFilter` on the receiving side to strip the stamp and drop
+/// expired messages.
+///
+/// Fails with `Error::EINVAL` if the message has no parts.
+pub fn send_with_ttl<I, T>(socket: &Socket, iter: I, ttl: Duration, flags: i32) -> Result<()>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<Message>,
+{
+    // Without parts, the stamp would become the first part of the next
+    // message sent on the socket.
+    let parts: Vec<Message> = iter.into_iter().map(Into::into).collect();
+    if parts.is_empty() {
+        return Err(Error::EINVAL);
+    }
+    socket.send(&TtlStamp::now(ttl).encode()[..], flags | SNDMORE)?;
+    socket.send_multipart(parts, flags)
+}
+
+/// Drops expired messages on receive, so that e.g. stale commands queued
+/// during an outage are not executed once the consumer comes back.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// let ctx = zmq::Context::new();
+/// let (sender, receiver) = (ctx.socket(zmq::PAIR).unwrap(), ctx.socket(zmq::PAIR).unwrap());
+/// receiver.bind("inproc://ttl").unwrap();
+/// sender.connect("inproc://ttl").unwrap();
+///
+/// zmq::send_with_ttl(&sender, ["stale"], Duration::from_secs(0), 0).unwrap();
+/// std::thread::sleep(Duration::from_millis(1));
+/// zmq::send_with_ttl(&sender, ["fresh"], Duration::from_secs(60), 0).unwrap();
+///
+/// let mut filter = zmq::ExpiryFilter::new();
+/// assert_eq!(filter.recv_multipart(&receiver, 0).unwrap(), vec![b"fresh".to_vec()]);
+/// assert_eq!(filter.expired(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ExpiryFilter {
+    expired: u64,
+}
+
+impl ExpiryFilter {
+    /// Create a filter.
+    pub fn new() -> ExpiryFilter {
+        ExpiryFilter::default()
+    }
+
+    /// Return the number of expired messages dropped so far.
+    pub fn expired(&self) -> u64 {
+        self.expired
+    }
+
+    /// Receive the next unexpired message sent with `send_with_ttl`, and
+    /// return its parts without the stamp.
+    ///
+    /// Returns `Error::EPROTO` for a message without a valid stamp; the
+    /// message is consumed.
+    pub fn recv_multipart(&mut self, socket: &Socket, flags: i32) -> Result<Vec<Vec<u8>>> {
+        loop {
+            let mut parts = socket.recv_multipart(flags)?;
+            let stamp = match parts.first().and_then(|frame| TtlStamp::decode(frame)) {
+                Some(stamp) => stamp,
+                None => return Err(Error::EPROTO),
+            };
+            if stamp.is_expired_at(SystemTime::now()) {
+                self.expired += 1;
+                continue;
+            }
+            parts.remove(0);
+            return Ok(parts);
+        }
+    }
+}
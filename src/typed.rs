@@ -0,0 +1,591 @@
+//! Socket wrappers restricted to the operations valid for one socket type.
+//!
+//! Each wrapper owns a `Socket` of the corresponding type and only exposes
+//! the methods that make sense for it, so that e.g. trying to send on a `SUB`
+//! socket or subscribing on a `PUSH` socket is caught at compile time instead
+//! of failing with `EFSM`, `ENOTSUP` or `EINVAL` at run-time.
+//!
+//! The wrappers can be converted into a plain `Socket` using `From`, and a
+//! `Socket` can be converted into a wrapper using `TryFrom`, which hands back
+//! the socket if it is not of the expected type.
+
+use std::convert::TryFrom;
+use std::result;
+use std::time::Duration;
+
+use crate::{
+    AsEndpoint, Context, Error, Message, PollEvents, PollItem, Result, Sendable, Socket,
+    SocketType, SubscriptionChange,
+};
+
+macro_rules! delegate {
+    ( $( $(#[$meta:meta])* fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty; )* ) => {
+        $(
+            $(#[$meta])*
+            pub fn $name(&self $(, $arg: $ty)*) -> $ret {
+                self.socket.$name($($arg),*)
+            }
+        )*
+    };
+}
+
+macro_rules! capability {
+    (send, $name:ident) => {
+        impl $name {
+            /// Send a message; see `Socket::send`.
+            pub fn send<T>(&self, data: T, flags: i32) -> Result<()>
+            where
+                T: Sendable,
+            {
+                self.socket.send(data, flags)
+            }
+
+            /// Send a multipart message; see `Socket::send_multipart`.
+            pub fn send_multipart<I, T>(&self, iter: I, flags: i32) -> Result<()>
+            where
+                I: IntoIterator<Item = T>,
+                T: Into<Message>,
+            {
+                self.socket.send_multipart(iter, flags)
+            }
+        }
+    };
+    (recv, $name:ident) => {
+        impl $name {
+            delegate! {
+                /// See `Socket::recv`.
+                fn recv(&self, msg: &mut Message, flags: i32) -> Result<()>;
+                /// See `Socket::recv_into`.
+                fn recv_into(&self, bytes: &mut [u8], flags: i32) -> Result<usize>;
+                /// See `Socket::recv_msg`.
+                fn recv_msg(&self, flags: i32) -> Result<Message>;
+                /// See `Socket::recv_bytes`.
+                fn recv_bytes(&self, flags: i32) -> Result<Vec<u8>>;
+                /// See `Socket::recv_string`.
+                fn recv_string(&self, flags: i32) -> Result<result::Result<String, Vec<u8>>>;
+                /// See `Socket::recv_multipart`.
+                fn recv_multipart(&self, flags: i32) -> Result<Vec<Vec<u8>>>;
+                /// See `Socket::recv_multipart_msg`.
+                fn recv_multipart_msg(&self, flags: i32) -> Result<Vec<Message>>;
+                /// See `Socket::get_rcvmore`.
+                fn get_rcvmore(&self) -> Result<bool>;
+            }
+        }
+    };
+    (subscribe, $name:ident) => {
+        impl $name {
+            /// Subscribe to messages starting with `prefix`.
+            pub fn subscribe(&self, prefix: &[u8]) -> Result<()> {
+                self.socket.set_subscribe(prefix)
+            }
+
+            /// Remove a subscription previously made with `subscribe`.
+            pub fn unsubscribe(&self, prefix: &[u8]) -> Result<()> {
+                self.socket.set_unsubscribe(prefix)
+            }
+        }
+    };
+    (conflate, $name:ident) => {
+        impl $name {
+            delegate! {
+                /// See `Socket::is_conflate`.
+                fn is_conflate(&self) -> Result<bool>;
+                /// See `Socket::set_conflate`.
+                fn set_conflate(&self, value: bool) -> Result<()>;
+            }
+        }
+    };
+    (send_single, $name:ident) => {
+        impl $name {
+            /// Send a single-part message; see `Socket::send`.
+            ///
+            /// Thread-safe sockets do not support multipart messages, so
+            /// `SNDMORE` fails with `Error::EINVAL`.
+            pub fn send<T>(&self, data: T, flags: i32) -> Result<()>
+            where
+                T: Sendable,
+            {
+                self.socket.send(data, flags)
+            }
+        }
+    };
+    (recv_single, $name:ident) => {
+        impl $name {
+            delegate! {
+                /// See `Socket::recv`.
+                fn recv(&self, msg: &mut Message, flags: i32) -> Result<()>;
+                /// See `Socket::recv_into`.
+                fn recv_into(&self, bytes: &mut [u8], flags: i32) -> Result<usize>;
+                /// See `Socket::recv_msg`.
+                fn recv_msg(&self, flags: i32) -> Result<Message>;
+                /// See `Socket::recv_bytes`.
+                fn recv_bytes(&self, flags: i32) -> Result<Vec<u8>>;
+                /// See `Socket::recv_string`.
+                fn recv_string(&self, flags: i32) -> Result<result::Result<String, Vec<u8>>>;
+            }
+        }
+    };
+    (threadsafe, $name:ident) => {
+        // `libzmq` serializes all operations on thread-safe socket types,
+        // and the state kept by `Socket` itself is behind mutexes.
+        unsafe impl Sync for $name {}
+    };
+    (probe_router, $name:ident) => {
+        impl $name {
+            delegate! {
+                /// See `Socket::set_probe_router`.
+                fn set_probe_router(&self, value: bool) -> Result<()>;
+            }
+        }
+    };
+}
+
+macro_rules! typed_socket {
+    ( $(#[$meta:meta])* $name:ident => $socket_type:ident, [ $($cap:ident),* ] ) => {
+        $(#[$meta])*
+        pub struct $name {
+            socket: Socket,
+        }
+
+        impl $name {
+            /// Create a new socket of this type.
+            pub fn new(context: &Context) -> Result<$name> {
+                context
+                    .socket(SocketType::$socket_type)
+                    .map(|socket| $name { socket })
+            }
+
+            /// Return the underlying, untyped socket.
+            pub fn into_socket(self) -> Socket {
+                self.socket
+            }
+
+            /// Return a reference to the underlying, untyped socket.
+            pub fn as_socket(&self) -> &Socket {
+                &self.socket
+            }
+
+            delegate! {
+                /// See `Socket::bind`.
+                fn bind(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()>;
+                /// See `Socket::unbind`.
+                fn unbind(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()>;
+                /// See `Socket::connect`.
+                fn connect(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()>;
+                /// See `Socket::disconnect`.
+                fn disconnect(&self, endpoint: &(impl AsEndpoint + ?Sized)) -> Result<()>;
+                /// See `Socket::monitor`.
+                fn monitor(&self, monitor_endpoint: &str, events: i32) -> Result<()>;
+                /// See `Socket::get_last_endpoint`.
+                fn get_last_endpoint(&self) -> Result<result::Result<String, Vec<u8>>>;
+                /// See `Socket::as_poll_item`.
+                fn as_poll_item(&self, events: PollEvents) -> PollItem<'_>;
+                /// See `Socket::poll`.
+                fn poll(&self, events: PollEvents, timeout_ms: i64) -> Result<i32>;
+                /// See `Socket::poll_timeout`.
+                fn poll_timeout(&self, events: PollEvents, timeout: Option<Duration>) -> Result<i32>;
+                /// See `Socket::get_linger`.
+                fn get_linger(&self) -> Result<i32>;
+                /// See `Socket::set_linger`.
+                fn set_linger(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_sndhwm`.
+                fn get_sndhwm(&self) -> Result<i32>;
+                /// See `Socket::set_sndhwm`.
+                fn set_sndhwm(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_rcvhwm`.
+                fn get_rcvhwm(&self) -> Result<i32>;
+                /// See `Socket::set_rcvhwm`.
+                fn set_rcvhwm(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_sndtimeo`.
+                fn get_sndtimeo(&self) -> Result<i32>;
+                /// See `Socket::set_sndtimeo`.
+                fn set_sndtimeo(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_rcvtimeo`.
+                fn get_rcvtimeo(&self) -> Result<i32>;
+                /// See `Socket::set_rcvtimeo`.
+                fn set_rcvtimeo(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_reconnect_ivl`.
+                fn get_reconnect_ivl(&self) -> Result<i32>;
+                /// See `Socket::set_reconnect_ivl`.
+                fn set_reconnect_ivl(&self, value: i32) -> Result<()>;
+                /// See `Socket::get_reconnect_ivl_max`.
+                fn get_reconnect_ivl_max(&self) -> Result<i32>;
+                /// See `Socket::set_reconnect_ivl_max`.
+                fn set_reconnect_ivl_max(&self, value: i32) -> Result<()>;
+                /// See `Socket::is_immediate`.
+                fn is_immediate(&self) -> Result<bool>;
+                /// See `Socket::set_immediate`.
+                fn set_immediate(&self, value: bool) -> Result<()>;
+                /// See `Socket::is_ipv6`.
+                fn is_ipv6(&self) -> Result<bool>;
+                /// See `Socket::set_ipv6`.
+                fn set_ipv6(&self, value: bool) -> Result<()>;
+            }
+        }
+
+        impl From<$name> for Socket {
+            fn from(typed: $name) -> Socket {
+                typed.socket
+            }
+        }
+
+        impl TryFrom<Socket> for $name {
+            /// The socket is handed back if it is not of the expected type.
+            type Error = Socket;
+
+            fn try_from(socket: Socket) -> result::Result<$name, Socket> {
+                match socket.get_socket_type() {
+                    Ok(SocketType::$socket_type) => Ok($name { socket }),
+                    _ => Err(socket),
+                }
+            }
+        }
+
+        $( capability!($cap, $name); )*
+    };
+}
+
+typed_socket! {
+    /// A `PAIR` socket.
+    PairSocket => PAIR, [send, recv]
+}
+
+typed_socket! {
+    /// A `PUB` socket, which can only send.
+    PubSocket => PUB, [send, conflate]
+}
+
+typed_socket! {
+    /// A `SUB` socket, which can only receive, and manages its subscriptions
+    /// via `subscribe` and `unsubscribe`.
+    SubSocket => SUB, [recv, subscribe, conflate]
+}
+
+typed_socket! {
+    /// A `REQ` socket.
+    ReqSocket => REQ, [send, recv, probe_router]
+}
+
+typed_socket! {
+    /// A `REP` socket.
+    RepSocket => REP, [send, recv]
+}
+
+typed_socket! {
+    /// A `DEALER` socket.
+    DealerSocket => DEALER, [send, recv, conflate, probe_router]
+}
+
+typed_socket! {
+    /// A `ROUTER` socket.
+    ///
+    /// Besides the plain `send` and `recv` methods, which require handling
+    /// the routing envelope by hand, `send_to` and `recv_from` take care of
+    /// the identity frame.
+    RouterSocket => ROUTER, [send, recv, probe_router]
+}
+
+typed_socket! {
+    /// A `PULL` socket, which can only receive.
+    PullSocket => PULL, [recv, conflate]
+}
+
+typed_socket! {
+    /// A `PUSH` socket, which can only send.
+    PushSocket => PUSH, [send, conflate]
+}
+
+typed_socket! {
+    /// An `XPUB` socket, which receives subscription messages.
+    XPubSocket => XPUB, [send, recv]
+}
+
+typed_socket! {
+    /// An `XSUB` socket, which sends subscription messages.
+    XSubSocket => XSUB, [send, recv]
+}
+
+typed_socket! {
+    /// A `STREAM` socket, exchanging raw data with TCP peers.
+    StreamSocket => STREAM, [send, recv]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `SERVER` socket, the thread-safe counterpart of `ROUTER`, which
+    /// addresses clients by the routing id of each message.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`.
+    ServerSocket => SERVER, [send_single, recv_single, threadsafe]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `CLIENT` socket, the thread-safe counterpart of `DEALER`, talking
+    /// to `SERVER` sockets.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`.
+    ClientSocket => CLIENT, [send_single, recv_single, threadsafe]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `RADIO` socket, which sends each message to the `DISH` sockets that
+    /// joined the message's group, see `Message::set_group`.
+    ///
+    /// Besides the usual transports, `RADIO` and `DISH` sockets support
+    /// `udp://`, where the dish binds and the radio connects. A multicast
+    /// address, e.g. `udp://239.0.0.1:5555`, reaches all dishes bound to it,
+    /// and an interface can be chosen with `udp://eth0;239.0.0.1:5555`; see
+    /// `Socket::set_multicast_hops` and `Socket::set_multicast_loop`.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`.
+    RadioSocket => RADIO, [send_single, threadsafe]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `DISH` socket, which receives the messages of the groups it joined
+    /// from `RADIO` sockets.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`.
+    DishSocket => DISH, [recv_single, threadsafe]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `SCATTER` socket, the thread-safe counterpart of `PUSH`, which
+    /// distributes messages round-robin among the connected `GATHER`
+    /// sockets.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`.
+    ScatterSocket => SCATTER, [send_single, threadsafe]
+}
+
+#[cfg(feature = "draft-api")]
+typed_socket! {
+    /// A `GATHER` socket, the thread-safe counterpart of `PULL`, which
+    /// receives the messages of all connected `SCATTER` sockets.
+    ///
+    /// The socket can be shared between threads, e.g. via `Arc`, so that a
+    /// pool of workers can receive from a single socket.
+    GatherSocket => GATHER, [recv_single, threadsafe]
+}
+
+impl ReqSocket {
+    delegate! {
+        /// See `Socket::set_req_relaxed`.
+        fn set_req_relaxed(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_req_correlate`.
+        fn set_req_correlate(&self, value: bool) -> Result<()>;
+    }
+}
+
+impl RouterSocket {
+    delegate! {
+        /// See `Socket::is_router_mandatory`.
+        fn is_router_mandatory(&self) -> Result<bool>;
+        /// See `Socket::set_router_mandatory`.
+        fn set_router_mandatory(&self, value: bool) -> Result<()>;
+        /// See `Socket::is_router_handover`.
+        fn is_router_handover(&self) -> Result<bool>;
+        /// See `Socket::set_router_handover`.
+        fn set_router_handover(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_connect_routing_id`.
+        fn set_connect_routing_id(&self, value: &[u8]) -> Result<()>;
+    }
+
+    /// Send a single-part message to the peer with the given identity.
+    pub fn send_to<T>(&self, identity: &[u8], data: T, flags: i32) -> Result<()>
+    where
+        T: Sendable,
+    {
+        self.socket.send(identity, flags | crate::SNDMORE)?;
+        self.socket.send(data, flags)
+    }
+
+    /// Send a multipart message to the peer with the given identity.
+    pub fn send_multipart_to<I, T>(&self, identity: &[u8], iter: I, flags: i32) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Message>,
+    {
+        self.socket.send(identity, flags | crate::SNDMORE)?;
+        self.socket.send_multipart(iter, flags)
+    }
+
+    /// Start a message to the peer with the given identity, if it can be
+    /// sent right now, returning a `RouteGuard` through which the message
+    /// body is sent.
+    ///
+    /// This sends the identity frame without blocking. With
+    /// `ZMQ_ROUTER_MANDATORY` enabled via `set_router_mandatory`, `Ok(None)`
+    /// means that the peer is not connected, or that its queue is full.
+    /// Without it, a `ROUTER` socket silently drops messages to unknown
+    /// peers, and so only a full queue is detected.
+    pub fn begin_send_to(&self, identity: &[u8]) -> Result<Option<RouteGuard<'_>>> {
+        match self.socket.send(identity, crate::SNDMORE | crate::DONTWAIT) {
+            Ok(()) => Ok(Some(RouteGuard {
+                socket: &self.socket,
+                sent: false,
+            })),
+            Err(Error::EHOSTUNREACH) | Err(Error::EAGAIN) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Receive a multipart message, returning the identity of the sending
+    /// peer separately from the message parts.
+    pub fn recv_from(&self, flags: i32) -> Result<(Vec<u8>, Vec<Vec<u8>>)> {
+        let mut parts = self.socket.recv_multipart(flags)?;
+        let identity = parts.remove(0);
+        Ok((identity, parts))
+    }
+}
+
+/// A message to a `ROUTER` peer whose identity frame has been sent, as
+/// returned by `RouterSocket::begin_send_to`.
+///
+/// `libzmq` offers no way to take back a frame that has been sent, so if the
+/// guard is dropped without sending a body, an empty frame is sent to
+/// complete the message.
+#[must_use = "the message is completed with an empty frame if the guard is dropped"]
+pub struct RouteGuard<'a> {
+    socket: &'a Socket,
+    sent: bool,
+}
+
+impl<'a> RouteGuard<'a> {
+    /// Send a single-part body, completing the message.
+    pub fn send<T>(mut self, data: T, flags: i32) -> Result<()>
+    where
+        T: Sendable,
+    {
+        self.sent = true;
+        self.socket.send(data, flags & !crate::SNDMORE)
+    }
+
+    /// Send a multipart body, completing the message.
+    pub fn send_multipart<I, T>(mut self, iter: I, flags: i32) -> Result<()>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Message>,
+    {
+        self.sent = true;
+        self.socket.send_multipart(iter, flags & !crate::SNDMORE)
+    }
+}
+
+impl<'a> Drop for RouteGuard<'a> {
+    fn drop(&mut self) {
+        if !self.sent {
+            if let Err(e) = self.socket.send(&b""[..], 0) {
+                log::warn!("failed to complete a routed message: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "draft-api")]
+impl ServerSocket {
+    /// Send a message to the client with the given routing id.
+    pub fn send_to<T>(&self, routing_id: u32, data: T, flags: i32) -> Result<()>
+    where
+        T: Into<Message>,
+    {
+        let mut msg = data.into();
+        msg.set_routing_id(routing_id)?;
+        self.socket.send(msg, flags)
+    }
+
+    /// Receive a message, returning the routing id of the sending client
+    /// along with it.
+    pub fn recv_from(&self, flags: i32) -> Result<(u32, Message)> {
+        let msg = self.socket.recv_msg(flags)?;
+        let routing_id = msg
+            .routing_id()
+            .expect("SERVER socket messages carry a routing id");
+        Ok((routing_id, msg))
+    }
+}
+
+#[cfg(feature = "draft-api")]
+impl RadioSocket {
+    /// Send a message to the members of `group`.
+    pub fn send_to_group<T>(&self, group: &str, data: T, flags: i32) -> Result<()>
+    where
+        T: Into<Message>,
+    {
+        let mut msg = data.into();
+        msg.set_group(group)?;
+        self.socket.send(msg, flags)
+    }
+}
+
+#[cfg(feature = "draft-api")]
+impl DishSocket {
+    delegate! {
+        /// See `Socket::join`.
+        fn join(&self, group: &str) -> Result<()>;
+        /// See `Socket::leave`.
+        fn leave(&self, group: &str) -> Result<()>;
+    }
+}
+
+impl XPubSocket {
+    delegate! {
+        /// See `Socket::set_xpub_verbose`.
+        fn set_xpub_verbose(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_xpub_verboser`.
+        fn set_xpub_verboser(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_xpub_manual`.
+        fn set_xpub_manual(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_xpub_manual_last_value`.
+        #[cfg(feature = "draft-api")]
+        fn set_xpub_manual_last_value(&self, value: bool) -> Result<()>;
+        /// See `Socket::set_xpub_welcome_msg`.
+        fn set_xpub_welcome_msg(&self, value: Option<&str>) -> Result<()>;
+    }
+
+    /// Add `prefix` to the subscriptions of the peer whose subscription
+    /// message was received last. Only meaningful in manual mode, see
+    /// `set_xpub_manual`.
+    pub fn subscribe(&self, prefix: &[u8]) -> Result<()> {
+        self.socket.set_subscribe(prefix)
+    }
+
+    /// Remove `prefix` from the subscriptions of the peer whose subscription
+    /// message was received last. Only meaningful in manual mode, see
+    /// `set_xpub_manual`.
+    pub fn unsubscribe(&self, prefix: &[u8]) -> Result<()> {
+        self.socket.set_unsubscribe(prefix)
+    }
+
+    /// Receive the next message and parse it as a subscription change.
+    ///
+    /// Returns `None` for messages which are not subscription messages.
+    pub fn recv_subscription(&self, flags: i32) -> Result<Option<SubscriptionChange>> {
+        let msg = self.socket.recv_msg(flags)?;
+        Ok(SubscriptionChange::parse(&msg))
+    }
+}
+
+impl XSubSocket {
+    /// Subscribe to messages starting with `prefix`, by sending a
+    /// subscription message upstream.
+    pub fn subscribe(&self, prefix: &[u8]) -> Result<()> {
+        self.send_subscription(1, prefix)
+    }
+
+    /// Remove a subscription, by sending an unsubscription message upstream.
+    pub fn unsubscribe(&self, prefix: &[u8]) -> Result<()> {
+        self.send_subscription(0, prefix)
+    }
+
+    fn send_subscription(&self, kind: u8, prefix: &[u8]) -> Result<()> {
+        let mut msg = Vec::with_capacity(prefix.len() + 1);
+        msg.push(kind);
+        msg.extend_from_slice(prefix);
+        self.socket.send(msg, 0)
+    }
+}
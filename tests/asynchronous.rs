@@ -0,0 +1,194 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod common;
+
+use std::io;
+use std::os::unix::io::RawFd;
+use std::task::{Context, Poll};
+use std::thread;
+
+use zmq::asynchronous::{AsyncSocket, FdWaker};
+
+#[cfg(feature = "tokio")]
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[cfg(feature = "tokio")]
+test!(test_async_send_recv, {
+    block_on(async {
+        let ctx = zmq::Context::new();
+        let receiver = ctx.socket(zmq::PULL).unwrap();
+        receiver.bind("tcp://127.0.0.1:*").unwrap();
+        let endpoint = receiver.get_last_endpoint().unwrap().unwrap();
+        let sender = ctx.socket(zmq::PUSH).unwrap();
+        sender.connect(&endpoint).unwrap();
+
+        let mut receiver = AsyncSocket::new(receiver).unwrap();
+        let mut sender = AsyncSocket::new(sender).unwrap();
+        let recv = tokio::spawn(async move {
+            let first = receiver.recv_bytes().await.unwrap();
+            let second = receiver.recv_multipart().await.unwrap();
+            (first, second)
+        });
+        sender.send("single", 0).await.unwrap();
+        sender.send_multipart(["multi", "part"], 0).await.unwrap();
+
+        let (first, second) = recv.await.unwrap();
+        assert_eq!(first, b"single");
+        assert_eq!(second, vec![b"multi".to_vec(), b"part".to_vec()]);
+    });
+});
+
+#[cfg(feature = "tokio")]
+test!(test_async_send_waits_for_peer, {
+    block_on(async {
+        let ctx = zmq::Context::new();
+        let sender = ctx.socket(zmq::PUSH).unwrap();
+        sender.set_sndhwm(1).unwrap();
+        sender.bind("inproc://async-hwm").unwrap();
+        let mut sender = AsyncSocket::new(sender).unwrap();
+
+        // Without a peer, sending is pending rather than failing.
+        let send = tokio::spawn(async move {
+            for i in 0..10 {
+                sender.send(i.to_string(), 0).await.unwrap();
+            }
+            sender
+        });
+        tokio::task::yield_now().await;
+        assert!(!send.is_finished());
+
+        let receiver = ctx.socket(zmq::PULL).unwrap();
+        receiver.set_rcvhwm(1).unwrap();
+        receiver.connect("inproc://async-hwm").unwrap();
+        let mut receiver = AsyncSocket::new(receiver).unwrap();
+        for i in 0..10 {
+            assert_eq!(
+                receiver.recv_bytes().await.unwrap(),
+                i.to_string().as_bytes()
+            );
+        }
+        let sender = send.await.unwrap().into_inner();
+        assert_eq!(sender.get_socket_type().unwrap(), zmq::PUSH);
+    });
+});
+
+#[cfg(all(feature = "tokio", feature = "futures"))]
+test!(test_async_stream_sink_forward, {
+    use futures_util::{SinkExt, StreamExt};
+
+    block_on(async {
+        let ctx = zmq::Context::new();
+        let input = ctx.socket(zmq::PULL).unwrap();
+        input.bind("inproc://async-input").unwrap();
+        let output = ctx.socket(zmq::PUSH).unwrap();
+        output.bind("inproc://async-output").unwrap();
+        let producer = ctx.socket(zmq::PUSH).unwrap();
+        producer.connect("inproc://async-input").unwrap();
+        let consumer = ctx.socket(zmq::PULL).unwrap();
+        consumer.connect("inproc://async-output").unwrap();
+
+        let mut producer = AsyncSocket::new(producer).unwrap();
+        let mut consumer = AsyncSocket::new(consumer).unwrap();
+        let input = AsyncSocket::new(input).unwrap();
+        let output = AsyncSocket::new(output).unwrap();
+        // Relay the first two messages from `input` to `output`.
+        let relay = tokio::spawn(input.take(2).forward(output));
+
+        for i in 0..2 {
+            let parts = vec![zmq::Message::from("part"), i.to_string().into()];
+            // `SinkExt::send`, rather than the inherent `send`.
+            SinkExt::send(&mut producer, parts).await.unwrap();
+        }
+        for i in 0..2 {
+            let parts = consumer.next().await.unwrap().unwrap();
+            let parts: Vec<_> = parts.iter().map(|part| part.to_vec()).collect();
+            assert_eq!(parts, vec![b"part".to_vec(), i.to_string().into_bytes()]);
+        }
+        relay.await.unwrap().unwrap();
+    });
+});
+
+#[cfg(all(feature = "tokio", feature = "futures"))]
+test!(test_async_sink_error_on_first_part, {
+    use futures_util::SinkExt;
+
+    block_on(async {
+        let ctx = zmq::Context::new();
+        let router = ctx.socket(zmq::ROUTER).unwrap();
+        router.set_router_mandatory(true).unwrap();
+        router.bind("inproc://async-sink-error").unwrap();
+        let dealer = ctx.socket(zmq::DEALER).unwrap();
+        dealer.set_identity(b"dealer").unwrap();
+        dealer.connect("inproc://async-sink-error").unwrap();
+        dealer.send("ready", 0).unwrap();
+        router.recv_multipart(0).unwrap();
+
+        // Nothing of a message whose first part fails has been queued, so
+        // the sink remains usable.
+        let mut router = AsyncSocket::new(router).unwrap();
+        let unroutable = vec![zmq::Message::from("nobody"), "lost".into()];
+        assert_eq!(
+            SinkExt::send(&mut router, unroutable).await,
+            Err(zmq::Error::EHOSTUNREACH)
+        );
+        let routed = vec![zmq::Message::from("dealer"), "found".into()];
+        SinkExt::send(&mut router, routed).await.unwrap();
+        assert_eq!(dealer.recv_multipart(0).unwrap(), vec![b"found".to_vec()]);
+    });
+});
+
+// Waits for the file descriptor on a separate thread, independently of the
+// runtime that polls the task.
+struct ThreadWaker(RawFd);
+
+impl FdWaker for ThreadWaker {
+    fn poll_readable(&self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let mut pollfd = libc::pollfd {
+            fd: self.0,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        if unsafe { libc::poll(&mut pollfd, 1, 0) } == 1 {
+            return Poll::Ready(Ok(()));
+        }
+        let waker = cx.waker().clone();
+        thread::spawn(move || {
+            unsafe { libc::poll(&mut pollfd, 1, -1) };
+            waker.wake();
+        });
+        Poll::Pending
+    }
+}
+
+test!(test_async_custom_waker, {
+    // A runtime without a reactor.
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let ctx = zmq::Context::new();
+        let server = ctx.socket(zmq::REP).unwrap();
+        server.bind("tcp://127.0.0.1:*").unwrap();
+        let endpoint = server.get_last_endpoint().unwrap().unwrap();
+        let client = ctx.socket(zmq::REQ).unwrap();
+        client.connect(&endpoint).unwrap();
+
+        let waker = |fd| Ok(ThreadWaker(fd));
+        let mut server = AsyncSocket::with_waker(server, waker).unwrap();
+        let mut client = AsyncSocket::with_waker(client, waker).unwrap();
+        let reply = tokio::spawn(async move {
+            let request = server.recv_bytes().await.unwrap();
+            server.send(request, 0).await.unwrap();
+        });
+        client.send("echo", 0).await.unwrap();
+        assert_eq!(client.recv_bytes().await.unwrap(), b"echo");
+        reply.await.unwrap();
+    });
+});
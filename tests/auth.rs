@@ -0,0 +1,84 @@
+#[macro_use]
+mod common;
+
+use zmq::auth::Authenticator;
+use zmq::{Context, EventDetail, Socket, SocketEvent};
+
+// Bind a PULL server monitored for handshake results, returning it, its
+// monitor and its endpoint.
+fn server(ctx: &Context, name: &str, configure: impl Fn(&Socket)) -> (Socket, Socket, String) {
+    let server = ctx.socket(zmq::PULL).unwrap();
+    configure(&server);
+    let monitor_endpoint = format!("inproc://auth-monitor-{}", name);
+    let events = SocketEvent::HANDSHAKE_SUCCEEDED.to_raw()
+        | SocketEvent::HANDSHAKE_FAILED_AUTH.to_raw()
+        | SocketEvent::HANDSHAKE_FAILED_NO_DETAIL.to_raw();
+    server
+        .monitor(&monitor_endpoint, i32::from(events))
+        .unwrap();
+    let monitor = ctx.socket(zmq::PAIR).unwrap();
+    monitor.connect(&monitor_endpoint).unwrap();
+    server.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = server.get_last_endpoint().unwrap().unwrap();
+    (server, monitor, endpoint)
+}
+
+fn plain_client(ctx: &Context, endpoint: &str, username: &str, password: &str) -> Socket {
+    let client = ctx.socket(zmq::PUSH).unwrap();
+    client.set_plain_username(Some(username)).unwrap();
+    client.set_plain_password(Some(password)).unwrap();
+    client.set_linger(0).unwrap();
+    client.connect(endpoint).unwrap();
+    client
+}
+
+test!(test_plain_credentials, {
+    let ctx = Context::new();
+    let auth = Authenticator::new(&ctx).unwrap();
+    auth.add_plain_user("admin", "secret");
+    let (server, monitor, endpoint) = server(&ctx, "plain", |s| s.set_plain_server(true).unwrap());
+
+    let _intruder = plain_client(&ctx, &endpoint, "admin", "guess");
+    let event = monitor.recv_monitor_event(0).unwrap();
+    assert_eq!(event.event, SocketEvent::HANDSHAKE_FAILED_AUTH);
+    assert_eq!(event.detail(), EventDetail::AuthStatus(400));
+
+    let client = plain_client(&ctx, &endpoint, "admin", "secret");
+    let event = monitor.recv_monitor_event(0).unwrap();
+    assert_eq!(event.event, SocketEvent::HANDSHAKE_SUCCEEDED);
+    client.send("hello", 0).unwrap();
+    let msg = server.recv_msg(0).unwrap();
+    assert_eq!(msg.gets("User-Id"), Some("admin"));
+
+    auth.stop().unwrap();
+});
+
+test!(test_address_lists, {
+    let ctx = Context::new();
+    let auth = Authenticator::new(&ctx).unwrap();
+    auth.allow("192.0.2.1");
+    let (_server, monitor, endpoint) = server(&ctx, "null", |s| s.set_zap_domain("test").unwrap());
+
+    let connect = || {
+        let client = ctx.socket(zmq::PUSH).unwrap();
+        client.set_linger(0).unwrap();
+        client.connect(&endpoint).unwrap();
+        monitor.recv_monitor_event(0).unwrap().event
+    };
+    // Not on the allow list.
+    assert_eq!(connect(), SocketEvent::HANDSHAKE_FAILED_AUTH);
+    auth.allow("127.0.0.1");
+    assert_eq!(connect(), SocketEvent::HANDSHAKE_SUCCEEDED);
+    // The deny list takes precedence.
+    auth.deny("127.0.0.1");
+    assert_eq!(connect(), SocketEvent::HANDSHAKE_FAILED_AUTH);
+});
+
+test!(test_single_handler_per_context, {
+    let ctx = Context::new();
+    let auth = Authenticator::new(&ctx).unwrap();
+    assert_eq!(Authenticator::new(&ctx).err(), Some(zmq::Error::EADDRINUSE));
+    drop(auth);
+    // The endpoint is released once the handler has stopped.
+    Authenticator::new(&ctx).unwrap();
+});
@@ -0,0 +1,90 @@
+#[macro_use]
+mod common;
+
+use std::cell::Cell;
+
+use zmq::{Context, MemoryBudget};
+
+test!(test_budget_estimate, {
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.set_sndhwm(100).unwrap();
+    push.set_rcvhwm(100).unwrap();
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.set_sndhwm(10).unwrap();
+    pull.set_rcvhwm(10).unwrap();
+
+    let mut budget = MemoryBudget::new(10_000);
+    budget.register(&push, 10);
+    budget.register(&pull, 100);
+    let report = budget.estimate().unwrap();
+    assert_eq!(report.estimated_bytes, 200 * 10 + 20 * 100);
+    assert_eq!(report.unbounded_sockets, 0);
+    assert!(!report.is_exceeded());
+
+    budget.unregister(&pull);
+    assert_eq!(budget.estimate().unwrap().estimated_bytes, 200 * 10);
+});
+
+test!(test_budget_peers_and_observe, {
+    let ctx = Context::new();
+    let publisher = ctx.socket(zmq::PUB).unwrap();
+    publisher.set_sndhwm(100).unwrap();
+    publisher.set_rcvhwm(100).unwrap();
+
+    let mut budget = MemoryBudget::new(usize::MAX);
+    budget.register(&publisher, 4);
+    budget.set_peers(&publisher, 3);
+    assert_eq!(budget.estimate().unwrap().estimated_bytes, 3 * 200 * 4);
+
+    // Small messages move the average rather than truncating it to zero.
+    budget.set_peers(&publisher, 1);
+    budget.observe(&publisher, 0);
+    assert_eq!(budget.estimate().unwrap().estimated_bytes, 700);
+});
+
+test!(test_budget_unbounded, {
+    let ctx = Context::new();
+    let socket = ctx.socket(zmq::PUB).unwrap();
+    socket.set_sndhwm(0).unwrap();
+
+    let mut budget = MemoryBudget::new(usize::MAX);
+    budget.register(&socket, 1);
+    let report = budget.estimate().unwrap();
+    assert_eq!(report.unbounded_sockets, 1);
+    assert!(report.is_exceeded());
+});
+
+test!(test_budget_callbacks, {
+    let ctx = Context::new();
+    let socket = ctx.socket(zmq::DEALER).unwrap();
+    socket.set_sndhwm(10).unwrap();
+    socket.set_rcvhwm(10).unwrap();
+
+    let calls = Cell::new(0);
+    let mut budget = MemoryBudget::new(5000);
+    budget.on_exceeded(|report| {
+        assert!(report.estimated_bytes > 5000);
+        calls.set(calls.get() + 1);
+    });
+    budget.register(&socket, 10);
+
+    assert!(!budget.check().unwrap().is_exceeded());
+    assert_eq!(calls.get(), 0);
+
+    // Observing much larger messages raises the estimate.
+    for _ in 0..20 {
+        budget.observe(&socket, 1000);
+    }
+    assert!(budget.check().unwrap().is_exceeded());
+    assert!(budget.check().unwrap().is_exceeded());
+    assert_eq!(calls.get(), 1);
+
+    // Only fires again after recovering.
+    socket.set_sndhwm(1).unwrap();
+    socket.set_rcvhwm(1).unwrap();
+    assert!(!budget.check().unwrap().is_exceeded());
+    socket.set_sndhwm(10).unwrap();
+    assert!(budget.check().unwrap().is_exceeded());
+    assert_eq!(calls.get(), 2);
+});
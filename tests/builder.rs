@@ -0,0 +1,99 @@
+#[macro_use]
+mod common;
+
+use zmq::Context;
+
+test!(test_build_bind_connect, {
+    let ctx = Context::new();
+    let receiver = ctx
+        .build_socket(zmq::PULL)
+        .rcvhwm(10)
+        .linger(0)
+        .bind("tcp://127.0.0.1:*")
+        .unwrap();
+    assert_eq!(receiver.get_rcvhwm().unwrap(), 10);
+    assert_eq!(receiver.get_linger().unwrap(), 0);
+
+    let endpoint = receiver.get_last_endpoint().unwrap().unwrap();
+    let sender = ctx
+        .build_socket(zmq::PUSH)
+        .sndhwm(20)
+        .immediate(true)
+        .connect(&endpoint)
+        .unwrap();
+    assert_eq!(sender.get_sndhwm().unwrap(), 20);
+    assert!(sender.is_immediate().unwrap());
+
+    sender.send("hello", 0).unwrap();
+    assert_eq!(receiver.recv_bytes(0).unwrap(), b"hello");
+});
+
+test!(test_build_custom_option, {
+    let ctx = Context::new();
+    let socket = ctx
+        .build_socket(zmq::REQ)
+        .option(|socket| socket.set_req_relaxed(true))
+        .build()
+        .unwrap();
+    assert_eq!(socket.get_socket_type().unwrap(), zmq::REQ);
+});
+
+test!(test_build_error, {
+    let ctx = Context::new();
+    let err = ctx
+        .build_socket(zmq::PUSH)
+        .option(|socket| socket.set_subscribe(b"topic"))
+        .build()
+        .err();
+    assert_eq!(err, Some(zmq::Error::ENOTSUP));
+});
+
+test!(test_build_routing_id_subscribe, {
+    let ctx = Context::new();
+    let router = ctx
+        .build_socket(zmq::ROUTER)
+        .linger(0)
+        .bind("tcp://127.0.0.1:*")
+        .unwrap();
+    let endpoint = router.get_last_endpoint().unwrap().unwrap();
+    // The routing id is set before connecting, so the `ROUTER` sees it.
+    let dealer = ctx
+        .build_socket(zmq::DEALER)
+        .routing_id(b"dealer")
+        .connect(&endpoint)
+        .unwrap();
+    dealer.send("hello", 0).unwrap();
+    assert_eq!(
+        router.recv_multipart(0).unwrap(),
+        vec![b"dealer".to_vec(), b"hello".to_vec()]
+    );
+
+    let publisher = ctx
+        .build_socket(zmq::PUB)
+        .bind("inproc://builder-subscribe")
+        .unwrap();
+    let subscriber = ctx
+        .build_socket(zmq::SUB)
+        .subscribe(b"a")
+        .subscribe(b"c")
+        .connect("inproc://builder-subscribe")
+        .unwrap();
+    loop {
+        publisher.send("a", 0).unwrap();
+        if subscriber.poll(zmq::POLLIN, 10).unwrap() > 0 {
+            break;
+        }
+    }
+    while subscriber.get_rcvmore().unwrap() || subscriber.poll(zmq::POLLIN, 0).unwrap() > 0 {
+        subscriber.recv_bytes(0).unwrap();
+    }
+    publisher.send("b", 0).unwrap();
+    publisher.send("c", 0).unwrap();
+    assert_eq!(subscriber.recv_bytes(0).unwrap(), b"c");
+});
+
+test!(test_build_subscribe_xsub, {
+    let ctx = Context::new();
+    let err = ctx.build_socket(zmq::XSUB).subscribe(b"").build().err();
+    assert_eq!(err, Some(zmq::Error::ENOTSUP));
+});
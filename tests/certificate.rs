@@ -0,0 +1,165 @@
+#[macro_use]
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use zmq::Certificate;
+
+fn temp_path(name: &str) -> PathBuf {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "zmq-certificate-{}-{}",
+        std::process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir.join(name)
+}
+
+test!(test_certificate_save_load, {
+    let path = temp_path("client.key");
+    let mut cert = Certificate::from_keys([7; 32], Some([9; 32]));
+    cert.set_meta("name", "client");
+    cert.set_meta("email", "client@example.com");
+    cert.save(&path).unwrap();
+
+    let loaded = Certificate::load(&path).unwrap();
+    assert_eq!(loaded, cert);
+    assert_eq!(
+        loaded.metadata().collect::<Vec<_>>(),
+        vec![("name", "client"), ("email", "client@example.com")]
+    );
+
+    // Without the secret file, only the public certificate is loaded.
+    let mut secret_path = path.clone().into_os_string();
+    secret_path.push("_secret");
+    let secret = fs::read_to_string(&secret_path).unwrap();
+    assert!(secret.contains(&cert.secret_key_z85().unwrap()));
+    fs::remove_file(&secret_path).unwrap();
+    let public = Certificate::load(&path).unwrap();
+    assert_eq!(public.public_key(), &[7; 32]);
+    assert_eq!(public.secret_key(), None);
+    assert_eq!(public.meta("name"), Some("client"));
+
+    let public_text = fs::read_to_string(&path).unwrap();
+    assert!(public_text.contains(&cert.public_key_z85()));
+    assert!(!public_text.contains("secret-key"));
+});
+
+#[cfg(unix)]
+test!(test_certificate_secret_permissions, {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = temp_path("server.key");
+    Certificate::from_keys([1; 32], Some([2; 32]))
+        .save(&path)
+        .unwrap();
+    let mut secret_path = path.into_os_string();
+    secret_path.push("_secret");
+    let mode = fs::metadata(&secret_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o077, 0);
+});
+
+#[cfg(unix)]
+test!(test_certificate_secret_permissions_existing_file, {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = temp_path("server.key");
+    let mut secret_path = path.clone().into_os_string();
+    secret_path.push("_secret");
+    fs::write(&secret_path, "").unwrap();
+    fs::set_permissions(&secret_path, fs::Permissions::from_mode(0o644)).unwrap();
+
+    Certificate::from_keys([1; 32], Some([2; 32]))
+        .save(&path)
+        .unwrap();
+    let mode = fs::metadata(&secret_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o077, 0);
+});
+
+test!(test_certificate_parse_zcert, {
+    // As written by CZMQ's zcert_save_secret.
+    let path = temp_path("zcert.key");
+    fs::write(
+        &path,
+        "#   ****  Generated on 2020-01-01 00:00:00 by CZMQ  ****\n\
+         #   ZeroMQ CURVE **Secret** Certificate\n\
+         #   DO NOT PROVIDE THIS FILE TO OTHER USERS nor change its permissions.\n\
+         \n\
+         metadata\n\
+         \x20   name = \"zcert\"\n\
+         curve\n\
+         \x20   public-key = \"rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7\"\n\
+         \x20   secret-key = \"JTKVSB%%)wK0E.X)V>+}o?pNmC{O&4W4b!Ni{Lh6\"\n",
+    )
+    .unwrap();
+    let cert = Certificate::load(&path).unwrap();
+    assert_eq!(cert.meta("name"), Some("zcert"));
+    assert_eq!(
+        cert.public_key_z85(),
+        "rq:rM>}U?@Lns47E1%kR.o@n%FcmmsL/@{H8]yf7"
+    );
+    assert_eq!(
+        cert.secret_key_z85().unwrap(),
+        "JTKVSB%%)wK0E.X)V>+}o?pNmC{O&4W4b!Ni{Lh6"
+    );
+});
+
+test!(test_certificate_invalid, {
+    let path = temp_path("invalid.key");
+    fs::write(&path, "curve\n    public-key = \"tooshort\"\n").unwrap();
+    let err = Certificate::load(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    fs::write(&path, "metadata\n    name = \"x\"\n").unwrap();
+    let err = Certificate::load(&path).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut cert = Certificate::from_keys([0; 32], None);
+    assert_eq!(
+        cert.save_secret(&path).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+    cert.set_meta("name", "has \"quotes\"");
+    assert_eq!(
+        cert.save_public(&path).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+});
+
+test_capability!(test_certificate_apply, "curve", {
+    let ctx = zmq::Context::new();
+    let server_cert = Certificate::new().unwrap();
+    let client_cert = Certificate::new().unwrap();
+
+    let server = ctx.socket(zmq::PULL).unwrap();
+    server.set_curve_server(true).unwrap();
+    server_cert.apply(&server).unwrap();
+    server.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = server.get_last_endpoint().unwrap().unwrap();
+
+    let client = ctx.socket(zmq::PUSH).unwrap();
+    client_cert.apply(&client).unwrap();
+    client
+        .set_curve_serverkey(server_cert.public_key())
+        .unwrap();
+    client.connect(&endpoint).unwrap();
+
+    client.send("hello", 0).unwrap();
+    assert_eq!(server.recv_bytes(0).unwrap(), b"hello");
+});
+
+test!(test_certificate_save_new, {
+    let path = temp_path("new.key");
+    let cert = Certificate::from_keys([3; 32], Some([4; 32]));
+    cert.save_new(&path).unwrap();
+    assert_eq!(Certificate::load(&path).unwrap(), cert);
+
+    let err = Certificate::from_keys([5; 32], Some([6; 32]))
+        .save_new(&path)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(Certificate::load(&path).unwrap(), cert);
+});
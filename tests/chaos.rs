@@ -0,0 +1,115 @@
+#[macro_use]
+mod common;
+
+use std::time::Duration;
+
+use zmq::{ChaosConfig, ChaosProxy, Context, Socket};
+
+struct Harness {
+    client: Socket,
+    frontend: Socket,
+    backend: Socket,
+    server: Socket,
+}
+
+fn harness(ctx: &Context, name: &str) -> Harness {
+    let pair = |endpoint: String| {
+        let a = ctx.socket(zmq::PAIR).unwrap();
+        let b = ctx.socket(zmq::PAIR).unwrap();
+        a.bind(&endpoint).unwrap();
+        b.connect(&endpoint).unwrap();
+        (a, b)
+    };
+    let (client, frontend) = pair(format!("inproc://chaos-{}-front", name));
+    let (server, backend) = pair(format!("inproc://chaos-{}-back", name));
+    Harness {
+        client,
+        frontend,
+        backend,
+        server,
+    }
+}
+
+// Send `count` numbered messages through the proxy and collect what arrives
+// within a short period.
+fn run(config: ChaosConfig, name: &str, count: usize) -> Vec<String> {
+    let ctx = Context::new();
+    let h = harness(&ctx, name);
+    let mut proxy = ChaosProxy::new(config);
+    for i in 0..count {
+        h.client.send(i.to_string(), 0).unwrap();
+    }
+    let mut received = Vec::new();
+    for _ in 0..20 {
+        proxy
+            .step(&h.frontend, &h.backend, Some(Duration::from_millis(5)))
+            .unwrap();
+        while let Ok(msg) = h.server.recv_string(zmq::DONTWAIT) {
+            received.push(msg.unwrap());
+        }
+    }
+    received
+}
+
+fn numbers(values: &[usize]) -> Vec<String> {
+    values.iter().map(|i| i.to_string()).collect()
+}
+
+test!(test_passthrough_both_directions, {
+    let ctx = Context::new();
+    let h = harness(&ctx, "passthrough");
+    let mut proxy = ChaosProxy::new(ChaosConfig::default());
+    h.client.send("request", 0).unwrap();
+    proxy.step(&h.frontend, &h.backend, None).unwrap();
+    assert_eq!(h.server.recv_bytes(0).unwrap(), b"request");
+    h.server.send("reply", 0).unwrap();
+    proxy.step(&h.frontend, &h.backend, None).unwrap();
+    assert_eq!(h.client.recv_bytes(0).unwrap(), b"reply");
+});
+
+test!(test_drop_and_duplicate, {
+    let dropped = ChaosConfig {
+        drop: 1.0,
+        ..ChaosConfig::default()
+    };
+    assert!(run(dropped, "drop", 3).is_empty());
+
+    let duplicated = ChaosConfig {
+        duplicate: 1.0,
+        ..ChaosConfig::default()
+    };
+    assert_eq!(run(duplicated, "duplicate", 2), numbers(&[0, 0, 1, 1]));
+});
+
+test!(test_reorder, {
+    let config = ChaosConfig {
+        reorder: 1.0,
+        reorder_window: Duration::from_secs(10),
+        ..ChaosConfig::default()
+    };
+    assert_eq!(run(config, "reorder", 4), numbers(&[1, 0, 3, 2]));
+});
+
+test!(test_delay, {
+    let config = ChaosConfig {
+        delay: 1.0,
+        max_delay: Duration::from_millis(30),
+        seed: 7,
+        ..ChaosConfig::default()
+    };
+    let mut received = run(config, "delay", 10);
+    received.sort_by_key(|s| s.parse::<usize>().unwrap());
+    assert_eq!(received, numbers(&(0..10).collect::<Vec<_>>()));
+});
+
+test!(test_seed_is_deterministic, {
+    let config = ChaosConfig {
+        drop: 0.5,
+        seed: 1234,
+        ..ChaosConfig::default()
+    };
+    let first = run(config.clone(), "seed-1", 50);
+    let second = run(config, "seed-2", 50);
+    assert!(!first.is_empty() && first.len() < 50);
+    assert_eq!(first, second);
+});
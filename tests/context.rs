@@ -15,3 +15,50 @@ fn context_io_threads() {
 
     assert!(ctx.set_io_threads(-1).is_err());
 }
+
+#[test]
+fn context_default_linger() {
+    let ctx = zmq::Context::new();
+    assert_eq!(ctx.get_default_linger(), None);
+    assert_eq!(ctx.socket(zmq::PUSH).unwrap().get_linger().unwrap(), -1);
+
+    ctx.clone().set_default_linger(Some(0));
+    assert_eq!(ctx.get_default_linger(), Some(0));
+    let socket = ctx.socket(zmq::PUSH).unwrap();
+    assert_eq!(socket.get_linger().unwrap(), 0);
+
+    // A socket with pending messages must not block context termination.
+    socket.connect("tcp://127.0.0.1:1").unwrap();
+    socket.send("pending", zmq::DONTWAIT).unwrap();
+    drop(socket);
+    drop(ctx);
+}
+
+#[test]
+fn context_shutdown_interrupts_recv() {
+    let ctx = zmq::Context::new();
+    let socket = ctx.socket(zmq::PULL).unwrap();
+    socket.bind("inproc://shutdown").unwrap();
+    let receiver = std::thread::spawn(move || socket.recv_bytes(0));
+
+    std::thread::sleep(std::time::Duration::from_millis(50));
+    ctx.shutdown().unwrap();
+    assert_eq!(receiver.join().unwrap(), Err(zmq::Error::ETERM));
+    assert_eq!(ctx.socket(zmq::PUSH).err(), Some(zmq::Error::ETERM));
+}
+
+#[test]
+fn context_max_sockets() {
+    let ctx = zmq::Context::new();
+    assert_eq!(
+        ctx.get_max_sockets().unwrap(),
+        zmq_sys::ZMQ_MAX_SOCKETS_DFLT as i32
+    );
+    assert!(ctx.get_socket_limit().unwrap() >= ctx.get_max_sockets().unwrap());
+
+    ctx.set_max_sockets(2).unwrap();
+    assert_eq!(ctx.get_max_sockets().unwrap(), 2);
+    let _a = ctx.socket(zmq::PAIR).unwrap();
+    let _b = ctx.socket(zmq::PAIR).unwrap();
+    assert_eq!(ctx.socket(zmq::PAIR).err(), Some(zmq::Error::EMFILE));
+}
@@ -0,0 +1,35 @@
+#[macro_use]
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+
+use zmq::AtomicCounter;
+
+test!(test_counter_basics, {
+    let counter = AtomicCounter::new();
+    assert_eq!(counter.value(), 0);
+    counter.set(5);
+    assert_eq!(counter.increment(), 5);
+    assert_eq!(counter.value(), 6);
+    assert!(counter.decrement());
+    assert_eq!(counter.value(), 5);
+});
+
+test!(test_counter_threads, {
+    let counter = Arc::new(AtomicCounter::new());
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let counter = Arc::clone(&counter);
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    counter.increment();
+                }
+            })
+        })
+        .collect();
+    for t in threads {
+        t.join().unwrap();
+    }
+    assert_eq!(counter.value(), 4000);
+});
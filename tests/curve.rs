@@ -46,6 +46,22 @@ test_capability!(test_curve_keypair, "curve", {
     assert!(keypair.secret_key.len() == 32);
 });
 
+test_capability!(test_curve_keypair_from_secret_key, "curve", {
+    let keypair = CurveKeyPair::new().unwrap();
+    let restored = CurveKeyPair::from_secret_key(keypair.secret_key).unwrap();
+    assert_eq!(restored.public_key, keypair.public_key);
+    assert_eq!(restored.secret_key, keypair.secret_key);
+});
+
+test!(test_curve_keypair_without_curve, {
+    if !zmq::has("curve") {
+        assert_eq!(
+            CurveKeyPair::from_secret_key([0; 32]).err(),
+            Some(zmq::Error::ENOTSUP)
+        );
+    }
+});
+
 test_capability!(test_getset_curve_server, "curve", {
     let ctx = Context::new();
     let sock = ctx.socket(zmq::REQ).unwrap();
@@ -61,6 +77,22 @@ test_capability!(test_getset_curve_publickey, "curve", {
     assert_eq!(sock.get_curve_publickey().unwrap(), key);
 });
 
+test_capability!(test_set_curve_keys_z85, "curve", {
+    let ctx = Context::new();
+    let sock = ctx.socket(zmq::REQ).unwrap();
+    let z85 = "FX5b8g5ZnOk7$Q}^)Y&?.v3&MIe+]OU7DTKynkUL";
+    sock.set_curve_publickey(z85.as_bytes()).unwrap();
+    sock.set_curve_serverkey(z85.as_bytes()).unwrap();
+    assert_eq!(
+        sock.get_curve_publickey().unwrap(),
+        z85_decode(z85).unwrap()
+    );
+    assert_eq!(
+        sock.get_curve_serverkey().unwrap(),
+        z85_decode(z85).unwrap()
+    );
+});
+
 test_capability!(test_getset_curve_secretkey, "curve", {
     let ctx = Context::new();
     let sock = ctx.socket(zmq::REQ).unwrap();
@@ -0,0 +1,34 @@
+use zmq::Deduplicator;
+
+#[test]
+fn test_deduplicator_evicts_least_recently_seen() {
+    let mut seen = Deduplicator::new(2);
+    assert!(seen.is_empty());
+    assert!(!seen.check_and_record(b"a"));
+    assert!(!seen.check_and_record(b"b"));
+    // Seeing "a" again makes "b" the least recently seen id.
+    assert!(seen.check_and_record(b"a"));
+    assert!(!seen.check_and_record(b"c"));
+    assert_eq!(seen.len(), 2);
+    assert!(seen.contains(b"a"));
+    assert!(!seen.contains(b"b"));
+    assert!(seen.contains(b"c"));
+
+    seen.clear();
+    assert!(!seen.check_and_record(b"a"));
+}
+
+#[test]
+fn test_deduplicator_repeated_ids() {
+    let mut seen = Deduplicator::new(3);
+    for _ in 0..100 {
+        seen.check_and_record(b"hot");
+    }
+    for id in 0..10u8 {
+        seen.check_and_record(&[id]);
+    }
+    assert_eq!(seen.len(), 3);
+    assert!(!seen.contains(b"hot"));
+    assert!(seen.contains(&[9]));
+    assert!(!seen.contains(&[6]));
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+mod common;
+
+use std::thread;
+use std::time::Duration;
+
+use zmq::{device, Context};
+
+test!(test_queue, {
+    let ctx = Context::new();
+    let device = device::queue(&ctx, "inproc://queue-front", "inproc://queue-back").unwrap();
+    let client = ctx.socket(zmq::REQ).unwrap();
+    client.connect("inproc://queue-front").unwrap();
+    let worker = ctx.socket(zmq::REP).unwrap();
+    worker.connect("inproc://queue-back").unwrap();
+
+    client.send("ping", 0).unwrap();
+    assert_eq!(worker.recv_bytes(0).unwrap(), b"ping");
+    worker.send("pong", 0).unwrap();
+    assert_eq!(client.recv_bytes(0).unwrap(), b"pong");
+    device.terminate().unwrap();
+});
+
+test!(test_forwarder, {
+    let ctx = Context::new();
+    let _device =
+        device::forwarder(&ctx, "inproc://forwarder-front", "inproc://forwarder-back").unwrap();
+    let publisher = ctx.socket(zmq::PUB).unwrap();
+    publisher.connect("inproc://forwarder-front").unwrap();
+    let subscriber = ctx.socket(zmq::SUB).unwrap();
+    subscriber.connect("inproc://forwarder-back").unwrap();
+    subscriber.set_subscribe(b"news").unwrap();
+
+    // The subscription has to travel through the forwarder first.
+    loop {
+        publisher.send("news flash", 0).unwrap();
+        if subscriber.poll(zmq::POLLIN, 10).unwrap() > 0 {
+            break;
+        }
+    }
+    assert_eq!(subscriber.recv_bytes(0).unwrap(), b"news flash");
+});
+
+test!(test_streamer_pause, {
+    let ctx = Context::new();
+    let device =
+        device::streamer(&ctx, "inproc://streamer-front", "inproc://streamer-back").unwrap();
+    let producer = ctx.socket(zmq::PUSH).unwrap();
+    producer.connect("inproc://streamer-front").unwrap();
+    let consumer = ctx.socket(zmq::PULL).unwrap();
+    consumer.connect("inproc://streamer-back").unwrap();
+
+    device.pause().unwrap();
+    producer.send("task", 0).unwrap();
+    thread::sleep(Duration::from_millis(50));
+    assert_eq!(consumer.poll(zmq::POLLIN, 0).unwrap(), 0);
+    device.resume().unwrap();
+    assert_eq!(consumer.recv_bytes(0).unwrap(), b"task");
+});
+
+test!(test_bind_failure, {
+    let ctx = Context::new();
+    let _device = device::streamer(&ctx, "inproc://taken-front", "inproc://taken-back").unwrap();
+    assert_eq!(
+        device::streamer(&ctx, "inproc://other-front", "inproc://taken-back").err(),
+        Some(zmq::Error::EADDRINUSE)
+    );
+});
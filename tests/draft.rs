@@ -0,0 +1,348 @@
+#![cfg(feature = "draft-api")]
+
+#[macro_use]
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use zmq::{
+    ClientSocket, Context, DishSocket, GatherSocket, Message, RadioSocket, ScatterSocket,
+    ServerSocket,
+};
+
+test!(test_message_routing_id, {
+    let mut msg = Message::from("hello");
+    assert_eq!(msg.routing_id(), None);
+    msg.set_routing_id(42).unwrap();
+    assert_eq!(msg.routing_id(), Some(42));
+    assert_eq!(msg.set_routing_id(0), Err(zmq::Error::EINVAL));
+});
+
+test!(test_client_server, {
+    let ctx = Context::new();
+    let server = ServerSocket::new(&ctx).unwrap();
+    server.bind("inproc://client-server").unwrap();
+    let client = ClientSocket::new(&ctx).unwrap();
+    client.connect("inproc://client-server").unwrap();
+
+    client.send("ping", 0).unwrap();
+    let (routing_id, msg) = server.recv_from(0).unwrap();
+    assert_eq!(&msg[..], b"ping");
+    server.send_to(routing_id, "pong", 0).unwrap();
+    assert_eq!(client.recv_bytes(0).unwrap(), b"pong");
+
+    // Replying with the received message keeps its routing id.
+    client.send("echo", 0).unwrap();
+    let msg = server.recv_msg(0).unwrap();
+    server.send(msg, 0).unwrap();
+    assert_eq!(client.recv_bytes(0).unwrap(), b"echo");
+
+    assert_eq!(client.send("part", zmq::SNDMORE), Err(zmq::Error::EINVAL));
+});
+
+test!(test_server_shared_between_threads, {
+    let ctx = Context::new();
+    let server = Arc::new(ServerSocket::new(&ctx).unwrap());
+    server.bind("inproc://shared-server").unwrap();
+
+    let workers: Vec<_> = (0..2)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                let (routing_id, msg) = server.recv_from(0).unwrap();
+                server.send_to(routing_id, &msg[..], 0).unwrap();
+            })
+        })
+        .collect();
+
+    let clients: Vec<_> = (0..2)
+        .map(|i| {
+            let client = ClientSocket::new(&ctx).unwrap();
+            client.connect("inproc://shared-server").unwrap();
+            client.send(format!("request {}", i), 0).unwrap();
+            client
+        })
+        .collect();
+    for (i, client) in clients.iter().enumerate() {
+        assert_eq!(
+            client.recv_string(0).unwrap().unwrap(),
+            format!("request {}", i)
+        );
+    }
+    for worker in workers {
+        worker.join().unwrap();
+    }
+});
+
+test!(test_server_settings_shared_between_threads, {
+    let ctx = Context::new();
+    let server = Arc::new(ServerSocket::new(&ctx).unwrap());
+
+    // The settings kept by `Socket` itself may be changed through a shared
+    // reference from several threads at once.
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                for _ in 0..100 {
+                    let socket = server.as_socket();
+                    socket.set_on_interrupt(zmq::OnInterrupt::Retry);
+                    socket.set_multipart_limits(Some(zmq::MultipartLimits::default()));
+                    socket.get_on_interrupt();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(
+        server.as_socket().get_on_interrupt(),
+        zmq::OnInterrupt::Retry
+    );
+});
+
+test!(test_message_group, {
+    let mut msg = Message::from("hello");
+    assert_eq!(msg.group(), None);
+    msg.set_group("weather").unwrap();
+    assert_eq!(msg.group(), Some("weather"));
+    assert_eq!(msg.set_group(&"x".repeat(256)), Err(zmq::Error::EINVAL));
+});
+
+test!(test_radio_dish, {
+    let ctx = Context::new();
+    let radio = RadioSocket::new(&ctx).unwrap();
+    radio.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = radio.get_last_endpoint().unwrap().unwrap();
+
+    let dish = DishSocket::new(&ctx).unwrap();
+    dish.join("weather").unwrap();
+    assert_eq!(dish.join("weather"), Err(zmq::Error::EINVAL));
+    dish.connect(&endpoint).unwrap();
+
+    // Wait until the join has reached the radio.
+    loop {
+        radio.send_to_group("weather", "probe", 0).unwrap();
+        if dish.poll(zmq::POLLIN, 10).unwrap() > 0 {
+            break;
+        }
+    }
+    while dish.recv_msg(zmq::DONTWAIT).is_ok() {}
+
+    radio.send_to_group("sports", "goal", 0).unwrap();
+    radio.send_to_group("weather", "rain", 0).unwrap();
+    let msg = dish.recv_msg(0).unwrap();
+    assert_eq!(msg.group(), Some("weather"));
+    assert_eq!(&msg[..], b"rain");
+
+    dish.leave("weather").unwrap();
+    assert_eq!(dish.leave("weather"), Err(zmq::Error::EINVAL));
+});
+
+test!(test_scatter_gather_worker_pool, {
+    let ctx = Context::new();
+    let gather = Arc::new(GatherSocket::new(&ctx).unwrap());
+    gather.bind("inproc://scatter-gather").unwrap();
+    let scatter = ScatterSocket::new(&ctx).unwrap();
+    scatter.connect("inproc://scatter-gather").unwrap();
+
+    let workers: Vec<_> = (0..4)
+        .map(|_| {
+            let gather = Arc::clone(&gather);
+            thread::spawn(move || {
+                let mut tasks = Vec::new();
+                loop {
+                    let task = gather.recv_string(0).unwrap().unwrap();
+                    if task == "done" {
+                        return tasks;
+                    }
+                    tasks.push(task);
+                }
+            })
+        })
+        .collect();
+
+    for i in 0..100 {
+        scatter.send(format!("task {}", i), 0).unwrap();
+    }
+    for _ in &workers {
+        scatter.send("done", 0).unwrap();
+    }
+    let mut tasks: Vec<String> = workers
+        .into_iter()
+        .flat_map(|worker| worker.join().unwrap())
+        .collect();
+    tasks.sort_by_key(|task| task[5..].parse::<u32>().unwrap());
+    let expected: Vec<String> = (0..100).map(|i| format!("task {}", i)).collect();
+    assert_eq!(tasks, expected);
+});
+
+test!(test_radio_dish_udp, {
+    // Find a free port; `udp://` does not support binding to port 0.
+    let port = std::net::UdpSocket::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let endpoint = format!("udp://127.0.0.1:{}", port);
+
+    let ctx = Context::new();
+    let dish = DishSocket::new(&ctx).unwrap();
+    dish.bind(&endpoint).unwrap();
+    dish.join("telemetry").unwrap();
+    let radio = RadioSocket::new(&ctx).unwrap();
+    radio.connect(&endpoint).unwrap();
+
+    // Datagrams sent before the connection is set up are lost.
+    loop {
+        radio.send_to_group("telemetry", "cpu=42", 0).unwrap();
+        if dish.poll(zmq::POLLIN, 10).unwrap() > 0 {
+            break;
+        }
+    }
+    let msg = dish.recv_msg(0).unwrap();
+    assert_eq!(msg.group(), Some("telemetry"));
+    assert_eq!(&msg[..], b"cpu=42");
+});
+
+test!(test_multicast_options, {
+    let ctx = Context::new();
+    let radio = RadioSocket::new(&ctx).unwrap().into_socket();
+    radio.set_multicast_loop(false).unwrap();
+    assert!(!radio.is_multicast_loop().unwrap());
+    radio.set_multicast_hops(4).unwrap();
+    assert_eq!(radio.get_multicast_hops().unwrap(), 4);
+    radio.set_multicast_maxtpdu(1400).unwrap();
+    assert_eq!(radio.get_multicast_maxtpdu().unwrap(), 1400);
+});
+
+test!(test_xpub_manual_last_value, {
+    let ctx = Context::new();
+    let xpub = ctx.socket(zmq::XPUB).unwrap();
+    xpub.bind("inproc://xpub-manual-last-value").unwrap();
+    xpub.set_xpub_manual_last_value(true).unwrap();
+
+    let subs = [ctx.socket(zmq::SUB).unwrap(), ctx.socket(zmq::SUB).unwrap()];
+    for sub in &subs {
+        sub.connect("inproc://xpub-manual-last-value").unwrap();
+        sub.set_subscribe(b"topic").unwrap();
+        assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x01topic");
+        xpub.set_subscribe(b"topic").unwrap();
+        // The cached value only goes to the new subscriber.
+        xpub.send("topic cached", 0).unwrap();
+        assert_eq!(sub.recv_bytes(0).unwrap(), b"topic cached");
+    }
+
+    xpub.send("topic live", 0).unwrap();
+    for sub in &subs {
+        assert_eq!(sub.recv_bytes(0).unwrap(), b"topic live");
+    }
+});
+
+test!(test_has_draft, {
+    assert!(zmq::has("draft"));
+});
+
+test!(test_poller_thread_safe_socket, {
+    let ctx = Context::new();
+    let server = ServerSocket::new(&ctx).unwrap();
+    server.bind("inproc://draft-poller").unwrap();
+    let client = ClientSocket::new(&ctx).unwrap();
+    client.connect("inproc://draft-poller").unwrap();
+
+    // Thread-safe sockets have no file descriptor, so they can only be
+    // polled with `zmq_poller`.
+    let mut server = server.into_socket();
+    unsafe {
+        let mut poller = zmq_sys::zmq_poller_new();
+        assert!(!poller.is_null());
+        let rc = zmq_sys::zmq_poller_add(
+            poller,
+            server.as_mut_ptr(),
+            std::ptr::null_mut(),
+            zmq_sys::ZMQ_POLLIN as i16,
+        );
+        assert_eq!(rc, 0);
+
+        let mut event: zmq_sys::zmq_poller_event_t = std::mem::zeroed();
+        assert_eq!(zmq_sys::zmq_poller_wait(poller, &mut event, 0), -1);
+        assert_eq!(
+            zmq::Error::from_raw(zmq_sys::zmq_errno()),
+            zmq::Error::EAGAIN
+        );
+
+        client.send("ping", 0).unwrap();
+        assert_eq!(zmq_sys::zmq_poller_wait(poller, &mut event, -1), 0);
+        assert_eq!(event.socket, server.as_mut_ptr());
+        assert_eq!(event.events, zmq_sys::ZMQ_POLLIN as i16);
+
+        assert_eq!(zmq_sys::zmq_poller_destroy(&mut poller), 0);
+    }
+    assert_eq!(server.recv_bytes(0).unwrap(), b"ping");
+});
+
+test!(test_poller_registry_thread_safe_socket, {
+    let ctx = Context::new();
+    let server = ServerSocket::new(&ctx).unwrap();
+    server.bind("inproc://draft-poller-registry").unwrap();
+    let client = ClientSocket::new(&ctx).unwrap();
+    client.connect("inproc://draft-poller-registry").unwrap();
+
+    let mut poller = zmq::Poller::new();
+    poller
+        .register(server.as_socket(), "server", zmq::POLLIN)
+        .unwrap();
+    assert_eq!(poller.poll(Some(Duration::from_millis(0))).unwrap(), 0);
+
+    client.send("ping", 0).unwrap();
+    assert_eq!(poller.poll(None).unwrap(), 1);
+    assert_eq!(
+        poller.ready().collect::<Vec<_>>(),
+        vec![(&"server", zmq::POLLIN)]
+    );
+    let (_, msg) = server.recv_from(0).unwrap();
+    assert_eq!(&msg[..], b"ping");
+});
+
+test!(test_timers, {
+    use std::cell::RefCell;
+
+    let fired = RefCell::new(Vec::new());
+    let mut timers = zmq::Timers::new();
+    assert_eq!(timers.timeout(), None);
+    assert_eq!(
+        timers.add(Duration::from_millis(0), |_| {}),
+        Err(zmq::Error::EINVAL)
+    );
+    let fast = timers
+        .add(Duration::from_millis(10), |id| fired.borrow_mut().push(id))
+        .unwrap();
+    let slow = timers
+        .add(Duration::from_secs(60), |id| fired.borrow_mut().push(id))
+        .unwrap();
+    assert_eq!(timers.len(), 2);
+    assert!(timers.timeout().unwrap() <= Duration::from_millis(10));
+
+    while fired.borrow().len() < 2 {
+        thread::sleep(timers.timeout().unwrap());
+        timers.execute().unwrap();
+    }
+    assert_eq!(*fired.borrow(), vec![fast, fast]);
+
+    timers.cancel(fast).unwrap();
+    assert_eq!(timers.cancel(fast), Err(zmq::Error::EINVAL));
+    assert!(timers.timeout().unwrap() > Duration::from_secs(50));
+    assert_eq!(
+        timers.set_interval(slow, Duration::from_millis(0)),
+        Err(zmq::Error::EINVAL)
+    );
+    timers.set_interval(slow, Duration::from_millis(1)).unwrap();
+    thread::sleep(Duration::from_millis(5));
+    timers.execute().unwrap();
+    assert_eq!(fired.borrow().last(), Some(&slow));
+    timers.reset(slow).unwrap();
+    assert_eq!(timers.reset(fast), Err(zmq::Error::EINVAL));
+});
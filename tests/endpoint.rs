@@ -0,0 +1,90 @@
+#[macro_use]
+mod common;
+
+use zmq::{Endpoint, EndpointError};
+
+fn parse(s: &str) -> Result<Endpoint, EndpointError> {
+    s.parse()
+}
+
+test!(test_parse_endpoints, {
+    let endpoints = [
+        (
+            "tcp://127.0.0.1:5555",
+            Endpoint::Tcp {
+                host: "127.0.0.1".into(),
+                port: Some(5555),
+            },
+        ),
+        (
+            "tcp://[::1]:*",
+            Endpoint::Tcp {
+                host: "[::1]".into(),
+                port: None,
+            },
+        ),
+        (
+            "udp://*:5556",
+            Endpoint::Udp {
+                host: "*".into(),
+                port: 5556,
+            },
+        ),
+        ("ipc:///tmp/feed", Endpoint::Ipc("/tmp/feed".into())),
+        ("inproc://workers", Endpoint::Inproc("workers".into())),
+        (
+            "epgm://eth0;239.192.1.1:5555",
+            Endpoint::Epgm("eth0;239.192.1.1:5555".into()),
+        ),
+    ];
+    for (s, endpoint) in endpoints.iter() {
+        assert_eq!(parse(s).as_ref(), Ok(endpoint));
+        assert_eq!(endpoint.to_string(), *s);
+    }
+});
+
+test!(test_parse_endpoint_errors, {
+    assert_eq!(
+        parse("tpc://127.0.0.1:5555"),
+        Err(EndpointError::UnknownTransport("tpc".into()))
+    );
+    assert_eq!(
+        parse("127.0.0.1:5555"),
+        Err(EndpointError::MissingTransport)
+    );
+    assert_eq!(parse("inproc://"), Err(EndpointError::MissingAddress));
+    assert_eq!(parse("tcp://:5555"), Err(EndpointError::MissingAddress));
+    assert_eq!(parse("tcp://localhost"), Err(EndpointError::MissingPort));
+    assert_eq!(parse("tcp://localhost:"), Err(EndpointError::MissingPort));
+    assert_eq!(
+        parse("udp://localhost:*"),
+        Err(EndpointError::InvalidPort("*".into()))
+    );
+    assert_eq!(
+        parse("tcp://localhost:65536"),
+        Err(EndpointError::InvalidPort("65536".into()))
+    );
+    assert_eq!(
+        EndpointError::UnknownTransport("tpc".into()).to_string(),
+        "unknown transport tpc://"
+    );
+});
+
+test!(test_bind_connect_endpoint, {
+    let ctx = zmq::Context::new();
+    let receiver = ctx.socket(zmq::PULL).unwrap();
+    receiver
+        .bind(&Endpoint::Tcp {
+            host: "127.0.0.1".into(),
+            port: None,
+        })
+        .unwrap();
+    let endpoint = parse(&receiver.get_last_endpoint().unwrap().unwrap()).unwrap();
+
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    sender.connect(&endpoint).unwrap();
+    sender.send("hello", 0).unwrap();
+    assert_eq!(receiver.recv_bytes(0).unwrap(), b"hello");
+    sender.disconnect(&endpoint).unwrap();
+    receiver.unbind(&endpoint).unwrap();
+});
@@ -6,3 +6,28 @@ fn from_raw_eintr() {
     let error = Error::from_raw(errno::EINTR);
     assert_eq!(error, Error::EINTR);
 }
+
+#[test]
+fn error_kinds() {
+    assert_eq!(Error::EAGAIN.kind(), ErrorKind::WouldBlock);
+    assert_eq!(Error::EINTR.kind(), ErrorKind::Interrupted);
+    assert_eq!(Error::ETERM.kind(), ErrorKind::Terminated);
+    assert_eq!(Error::ENOMEM.kind(), ErrorKind::Resource);
+    assert_eq!(Error::EMFILE.kind(), ErrorKind::Resource);
+    assert_eq!(Error::EINVAL.kind(), ErrorKind::Other);
+    assert_eq!(Error::EPROTONOSUPPORT.kind(), ErrorKind::Unsupported);
+    assert_eq!(Error::EUNKNOWN(123_456).kind(), ErrorKind::Other);
+
+    let ctx = Context::new();
+    let socket = ctx.socket(REQ).unwrap();
+    // A `REQ` socket must send a request before receiving.
+    assert_eq!(
+        socket.recv_bytes(DONTWAIT).unwrap_err().kind(),
+        ErrorKind::Protocol
+    );
+    // Subscriptions do not apply to a `REQ` socket.
+    assert_eq!(
+        socket.set_subscribe(b"").unwrap_err().kind(),
+        ErrorKind::Unsupported
+    );
+}
@@ -0,0 +1,49 @@
+#![cfg(all(unix, feature = "mio"))]
+
+#[macro_use]
+mod common;
+
+use std::time::Duration;
+
+use mio::{Events, Interest, Poll, Token};
+
+test!(test_mio_wakeups, {
+    let ctx = zmq::Context::new();
+    let mut first = ctx.socket(zmq::PULL).unwrap();
+    let mut second = ctx.socket(zmq::PULL).unwrap();
+    first.bind("tcp://127.0.0.1:*").unwrap();
+    second.bind("inproc://mio-wakeups").unwrap();
+
+    let mut poll = Poll::new().unwrap();
+    let registry = poll.registry();
+    registry
+        .register(&mut first, Token(1), Interest::READABLE)
+        .unwrap();
+    // The interest is ignored, since `ZMQ_FD` only signals readability.
+    registry
+        .register(&mut second, Token(2), Interest::WRITABLE)
+        .unwrap();
+
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    sender
+        .connect(&first.get_last_endpoint().unwrap().unwrap())
+        .unwrap();
+    for i in 0..3 {
+        sender.send(format!("message {}", i), 0).unwrap();
+    }
+
+    let mut events = Events::with_capacity(8);
+    let mut received = Vec::new();
+    while received.len() < 3 {
+        poll.poll(&mut events, Some(Duration::from_secs(5)))
+            .unwrap();
+        assert!(!events.is_empty());
+        while first.get_events().unwrap().contains(zmq::POLLIN) {
+            received.push(first.recv_string(zmq::DONTWAIT).unwrap().unwrap());
+        }
+    }
+    assert_eq!(received, vec!["message 0", "message 1", "message 2"]);
+
+    poll.registry().deregister(&mut first).unwrap();
+    poll.registry().deregister(&mut second).unwrap();
+});
@@ -0,0 +1,121 @@
+#[macro_use]
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use zmq::framing::{Framing, PrefixWidth, StreamEvent, StreamFramer};
+use zmq::{Context, StreamSocket};
+
+test!(test_length_prefixed_push, {
+    let mut framer = StreamFramer::new(Framing::LengthPrefixed(PrefixWidth::U16));
+    assert!(framer.push(b"a", &[0, 5, b'h', b'e']).unwrap().is_empty());
+    // Data from other peers is buffered separately.
+    assert_eq!(
+        framer.push(b"b", &[0, 1, b'x', 0]).unwrap(),
+        vec![b"x".to_vec()]
+    );
+    assert_eq!(
+        framer.push(b"a", &[b'l', b'l', b'o', 0, 0]).unwrap(),
+        vec![b"hello".to_vec(), b"".to_vec()]
+    );
+    assert_eq!(framer.remove_peer(b"b"), vec![0]);
+    assert_eq!(framer.remove_peer(b"a"), Vec::<u8>::new());
+});
+
+test!(test_max_record_len, {
+    let mut framer =
+        StreamFramer::new(Framing::LengthPrefixed(PrefixWidth::U32)).with_max_record_len(4);
+    assert_eq!(framer.push(b"a", &[0, 0, 0, 5]), Err(zmq::Error::EMSGSIZE));
+    assert_eq!(framer.encode(b"hello"), Err(zmq::Error::EMSGSIZE));
+
+    let mut framer = StreamFramer::new(Framing::Delimited(b"\n".to_vec())).with_max_record_len(4);
+    assert_eq!(framer.push(b"a", b"abcd"), Ok(vec![]));
+    assert_eq!(framer.push(b"a", b"e"), Err(zmq::Error::EMSGSIZE));
+    assert_eq!(framer.push(b"a", b"ab\n"), Ok(vec![b"ab".to_vec()]));
+
+    // Records preceding an oversized one are returned, and the error is
+    // reported by the next call.
+    assert_eq!(framer.push(b"a", b"cd\nefghi"), Ok(vec![b"cd".to_vec()]));
+    assert_eq!(framer.push(b"a", b""), Err(zmq::Error::EMSGSIZE));
+    assert_eq!(framer.remove_peer(b"a"), Vec::<u8>::new());
+
+    let mut framer =
+        StreamFramer::new(Framing::LengthPrefixed(PrefixWidth::U8)).with_max_record_len(4);
+    assert_eq!(
+        framer.push(b"a", &[1, b'x', 5, b'h']),
+        Ok(vec![b"x".to_vec()])
+    );
+    assert_eq!(framer.push(b"a", b"ello"), Err(zmq::Error::EMSGSIZE));
+});
+
+test!(test_delimiter_split_across_chunks, {
+    let mut framer = StreamFramer::new(Framing::Delimited(b"\r\n".to_vec()));
+    assert!(framer.push(b"a", b"ab").unwrap().is_empty());
+    assert!(framer.push(b"a", b"c\r").unwrap().is_empty());
+    assert_eq!(
+        framer.push(b"a", b"\nd\r\n").unwrap(),
+        vec![b"abc".to_vec(), b"d".to_vec()]
+    );
+    assert!(framer.push(b"a", b"\r").unwrap().is_empty());
+    assert!(framer.push(b"a", b"\r").unwrap().is_empty());
+    assert_eq!(framer.push(b"a", b"\n").unwrap(), vec![b"\r".to_vec()]);
+});
+
+test!(test_encode, {
+    let framer = StreamFramer::new(Framing::LengthPrefixed(PrefixWidth::U8));
+    assert_eq!(framer.encode(b"ab").unwrap(), vec![2, b'a', b'b']);
+    assert_eq!(framer.encode(&[0; 256]), Err(zmq::Error::EMSGSIZE));
+
+    let framer = StreamFramer::new(Framing::Delimited(b"\r\n".to_vec()));
+    assert_eq!(framer.encode(b"ab").unwrap(), b"ab\r\n");
+    assert_eq!(framer.encode(b"a\r\nb"), Err(zmq::Error::EINVAL));
+});
+
+test!(test_stream_socket_framing, {
+    let ctx = Context::new();
+    let socket = StreamSocket::new(&ctx).unwrap();
+    socket.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = socket.get_last_endpoint().unwrap().unwrap();
+    let address = endpoint.trim_start_matches("tcp://");
+
+    let mut framer = StreamFramer::new(Framing::Delimited(b"\n".to_vec()));
+    let mut client = TcpStream::connect(address).unwrap();
+
+    let peer = match framer.recv(socket.as_socket(), 0).unwrap() {
+        StreamEvent::Connected(peer) => peer,
+        other => panic!("unexpected event: {:?}", other),
+    };
+
+    client.write_all(b"first\nsec").unwrap();
+    client.flush().unwrap();
+    assert_eq!(
+        framer.recv(socket.as_socket(), 0).unwrap(),
+        StreamEvent::Record {
+            peer: peer.clone(),
+            data: b"first".to_vec()
+        }
+    );
+    client.write_all(b"ond\nthi").unwrap();
+    assert_eq!(
+        framer.recv(socket.as_socket(), 0).unwrap(),
+        StreamEvent::Record {
+            peer: peer.clone(),
+            data: b"second".to_vec()
+        }
+    );
+
+    framer.send(socket.as_socket(), &peer, b"reply").unwrap();
+    let mut reply = [0; 6];
+    client.read_exact(&mut reply).unwrap();
+    assert_eq!(&reply, b"reply\n");
+
+    drop(client);
+    assert_eq!(
+        framer.recv(socket.as_socket(), 0).unwrap(),
+        StreamEvent::Disconnected {
+            peer,
+            incomplete: b"thi".to_vec()
+        }
+    );
+});
@@ -0,0 +1,62 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod common;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use nix::sys::pthread::{pthread_kill, pthread_self, Pthread};
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+
+use zmq::OnInterrupt;
+
+extern "C" fn ignore_signal(_: libc::c_int) {}
+
+// Install a handler for `SIGUSR1` without `SA_RESTART`, so that it
+// interrupts blocking calls, and keep sending it to `thread` until `done`
+// is set.
+fn interrupt_until(thread: Pthread, done: Arc<AtomicBool>) -> thread::JoinHandle<()> {
+    let action = SigAction::new(
+        SigHandler::Handler(ignore_signal),
+        SaFlags::empty(),
+        SigSet::empty(),
+    );
+    unsafe { sigaction(Signal::SIGUSR1, &action) }.unwrap();
+    thread::spawn(move || {
+        while !done.load(Ordering::SeqCst) {
+            thread::sleep(Duration::from_millis(20));
+            pthread_kill(thread, Signal::SIGUSR1).unwrap();
+        }
+    })
+}
+
+test!(test_on_interrupt, {
+    let ctx = zmq::Context::new();
+    let receiver = ctx.socket(zmq::PULL).unwrap();
+    receiver.bind("inproc://interrupt").unwrap();
+    assert_eq!(receiver.get_on_interrupt(), OnInterrupt::Fail);
+
+    let done = Arc::new(AtomicBool::new(false));
+    let interrupter = interrupt_until(pthread_self(), done.clone());
+    assert_eq!(receiver.recv_bytes(0), Err(zmq::Error::EINTR));
+    assert_eq!(receiver.poll(zmq::POLLIN, -1), Err(zmq::Error::EINTR));
+
+    // Deliver a message only after the receiver has been interrupted a few
+    // times.
+    receiver.set_on_interrupt(OnInterrupt::Retry);
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    sender.connect("inproc://interrupt").unwrap();
+    let delayed = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(200));
+        sender.send("hello", 0).unwrap();
+    });
+    assert_eq!(receiver.poll(zmq::POLLIN, -1), Ok(1));
+    assert_eq!(receiver.recv_bytes(0).unwrap(), b"hello");
+
+    done.store(true, Ordering::SeqCst);
+    interrupter.join().unwrap();
+    delayed.join().unwrap();
+});
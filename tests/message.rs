@@ -32,4 +32,33 @@ quickcheck! {
         let original = Message::from(&input);
         Message::from(input) == original
     }
+
+    fn msg_string_roundtrip(input: String) -> bool {
+        let original = Message::from(&input);
+        let msg = Message::from(input.clone());
+        msg == original && msg.as_str() == Some(input.as_str())
+    }
+}
+
+// quickcheck initializes the logger itself, so `test!` cannot be used here.
+#[test]
+fn test_move_from() {
+    let mut dest = Message::from("old");
+    let mut src = Message::from(vec![1, 2, 3]);
+    dest.move_from(&mut src);
+    assert_eq!(&dest[..], &[1, 2, 3]);
+    assert!(src.is_empty());
+}
+
+#[test]
+fn test_from_references() {
+    let expected = Message::from(&b"bytes"[..]);
+    assert_eq!(Message::from(b"bytes"), expected);
+    assert_eq!(Message::from(&b"bytes".to_vec()), expected);
+    assert_eq!(Message::from(&"bytes"), expected);
+    assert_eq!(Message::from(&String::from("bytes")), expected);
+
+    // Larger than the data `libzmq` stores inline.
+    let large = "x".repeat(100);
+    assert_eq!(Message::from(large.clone()).as_str(), Some(&large[..]));
 }
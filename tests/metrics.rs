@@ -0,0 +1,37 @@
+use zmq::MetricsRegistry;
+
+#[test]
+fn test_prometheus_rendering() {
+    let registry = MetricsRegistry::new();
+    let frontend = registry.register("frontend");
+    let backend = registry.register("back\"end");
+    frontend.record_sent(10);
+    frontend.record_sent(5);
+    frontend.record_queue_full();
+    backend.record_received(3);
+    backend.record_dropped();
+    backend.record_reconnect();
+    assert_eq!(frontend.messages_sent(), 2);
+    assert_eq!(frontend.bytes_sent(), 15);
+
+    // Registering an existing name returns the same counters.
+    registry.register("frontend").record_sent(1);
+    assert_eq!(frontend.messages_sent(), 3);
+
+    let text = registry.render_prometheus();
+    let expected = [
+        "# TYPE zmq_messages_sent_total counter",
+        "zmq_messages_sent_total{socket=\"frontend\"} 3",
+        "zmq_bytes_sent_total{socket=\"frontend\"} 16",
+        "zmq_queue_full_total{socket=\"frontend\"} 1",
+        "zmq_messages_received_total{socket=\"back\\\"end\"} 1",
+        "zmq_messages_dropped_total{socket=\"back\\\"end\"} 1",
+        "zmq_reconnects_total{socket=\"back\\\"end\"} 1",
+    ];
+    for line in &expected {
+        assert!(text.lines().any(|l| l == *line), "missing {:?}", line);
+    }
+
+    registry.unregister("frontend");
+    assert!(!registry.render_prometheus().contains("frontend"));
+}
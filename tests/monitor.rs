@@ -132,3 +132,71 @@ test!(test_monitor_events, {
     close_zero_linger(client_mon);
     close_zero_linger(server_mon);
 });
+
+test!(test_recv_monitor_event, {
+    let ctx = zmq::Context::new();
+    let socket = ctx.socket(zmq::PULL).unwrap();
+    socket
+        .monitor(
+            "inproc://recv-monitor-event",
+            zmq::SocketEvent::LISTENING as i32,
+        )
+        .unwrap();
+    let monitor = ctx.socket(zmq::PAIR).unwrap();
+    monitor.connect("inproc://recv-monitor-event").unwrap();
+
+    socket.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = socket.get_last_endpoint().unwrap().unwrap();
+    let event = monitor.recv_monitor_event(0).unwrap();
+    assert_eq!(event.event, zmq::SocketEvent::LISTENING);
+    assert_eq!(event.endpoint, endpoint);
+    assert!(!monitor.get_rcvmore().unwrap());
+});
+
+test!(test_monitor_event_from_parts, {
+    let mut header = zmq::SocketEvent::LISTENING.to_raw().to_ne_bytes().to_vec();
+    header.extend_from_slice(&42u32.to_ne_bytes());
+    let event = zmq::MonitorEvent::from_parts(&[header.clone(), b"tcp://127.0.0.1:1".to_vec()]);
+    assert_eq!(
+        event,
+        Ok(zmq::MonitorEvent {
+            event: zmq::SocketEvent::LISTENING,
+            value: 42,
+            endpoint: "tcp://127.0.0.1:1".to_owned(),
+        })
+    );
+    assert_eq!(event.unwrap().detail(), zmq::EventDetail::Fd(42));
+
+    let mut retried = zmq::SocketEvent::CONNECT_RETRIED
+        .to_raw()
+        .to_ne_bytes()
+        .to_vec();
+    retried.extend_from_slice(&250u32.to_ne_bytes());
+    let event = zmq::MonitorEvent::from_parts(&[retried, b"tcp://127.0.0.1:1".to_vec()]);
+    assert_eq!(
+        event.unwrap().detail(),
+        zmq::EventDetail::ReconnectInterval(std::time::Duration::from_millis(250))
+    );
+
+    assert_eq!(
+        zmq::MonitorEvent::from_parts(&[header]),
+        Err(zmq::Error::EPROTO)
+    );
+    let mut unknown = 0x8000u16.to_ne_bytes().to_vec();
+    unknown.extend_from_slice(&[0; 4]);
+    assert_eq!(
+        zmq::MonitorEvent::from_parts(&[unknown, vec![]]),
+        Err(zmq::Error::EPROTO)
+    );
+});
+
+test!(test_socket_event_try_from_raw, {
+    let raw = zmq::SocketEvent::DISCONNECTED.to_raw();
+    assert_eq!(
+        zmq::SocketEvent::try_from_raw(raw),
+        Some(zmq::SocketEvent::DISCONNECTED)
+    );
+    // Not an event type known to this crate, e.g. one added by a newer
+    // `libzmq`.
+    assert_eq!(zmq::SocketEvent::try_from_raw(0x8000), None);
+});
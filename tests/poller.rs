@@ -0,0 +1,95 @@
+#[macro_use]
+mod common;
+
+use std::time::Duration;
+
+use zmq::{Context, Poller, POLLIN, POLLOUT};
+
+const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+
+test!(test_poller_ready_keys, {
+    let ctx = Context::new();
+    let first = ctx.socket(zmq::PULL).unwrap();
+    let second = ctx.socket(zmq::PULL).unwrap();
+    first.bind("inproc://poller-first").unwrap();
+    second.bind("inproc://poller-second").unwrap();
+
+    let mut poller = Poller::new();
+    poller.register(&first, 1, POLLIN).unwrap();
+    poller.register(&second, 2, POLLIN).unwrap();
+    assert_eq!(poller.len(), 2);
+    assert_eq!(poller.poll(Some(Duration::from_millis(0))).unwrap(), 0);
+    assert_eq!(poller.ready().count(), 0);
+
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    sender.connect("inproc://poller-second").unwrap();
+    sender.send("hello", 0).unwrap();
+
+    assert_eq!(poller.poll(TIMEOUT).unwrap(), 1);
+    assert_eq!(poller.ready().collect::<Vec<_>>(), vec![(&2, POLLIN)]);
+    assert_eq!(second.recv_bytes(0).unwrap(), b"hello");
+    assert_eq!(poller.poll(Some(Duration::from_millis(0))).unwrap(), 0);
+});
+
+test!(test_poller_duplicates, {
+    let ctx = Context::new();
+    let socket = ctx.socket(zmq::PULL).unwrap();
+    let other = ctx.socket(zmq::PULL).unwrap();
+
+    let mut poller = Poller::new();
+    poller.register(&socket, "socket", POLLIN).unwrap();
+    assert_eq!(
+        poller.register(&socket, "again", POLLIN),
+        Err(zmq::Error::EINVAL)
+    );
+    assert_eq!(
+        poller.register(&other, "socket", POLLIN),
+        Err(zmq::Error::EINVAL)
+    );
+    assert_eq!(poller.len(), 1);
+});
+
+test!(test_poller_modify_unregister, {
+    let ctx = Context::new();
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    let receiver = ctx.socket(zmq::PULL).unwrap();
+    receiver.bind("inproc://poller-modify").unwrap();
+    sender.connect("inproc://poller-modify").unwrap();
+
+    let mut poller = Poller::new();
+    poller.register(&sender, "sender", POLLIN).unwrap();
+    assert_eq!(poller.poll(Some(Duration::from_millis(0))).unwrap(), 0);
+
+    poller.modify(&"sender", POLLIN | POLLOUT).unwrap();
+    assert_eq!(poller.poll(TIMEOUT).unwrap(), 1);
+    assert_eq!(
+        poller.ready().collect::<Vec<_>>(),
+        vec![(&"sender", POLLOUT)]
+    );
+
+    poller.unregister(&"sender").unwrap();
+    assert!(poller.is_empty());
+    assert_eq!(poller.unregister(&"sender"), Err(zmq::Error::EINVAL));
+    assert_eq!(poller.modify(&"sender", POLLIN), Err(zmq::Error::EINVAL));
+});
+
+#[cfg(unix)]
+test!(test_poller_fd, {
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::UnixStream;
+
+    let (mut writer, reader) = UnixStream::pair().unwrap();
+    let mut poller = Poller::new();
+    poller
+        .register_fd(reader.as_raw_fd(), "pipe", POLLIN)
+        .unwrap();
+    assert_eq!(
+        poller.register_fd(reader.as_raw_fd(), "again", POLLIN),
+        Err(zmq::Error::EINVAL)
+    );
+
+    writer.write_all(b"X").unwrap();
+    assert_eq!(poller.poll(TIMEOUT).unwrap(), 1);
+    assert_eq!(poller.ready().collect::<Vec<_>>(), vec![(&"pipe", POLLIN)]);
+});
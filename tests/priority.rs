@@ -0,0 +1,80 @@
+#[macro_use]
+mod common;
+
+use std::time::{Duration, Instant};
+
+use zmq::{Context, PriorityReceiver, Socket};
+
+fn pipe(ctx: &Context, name: &str) -> (Socket, Socket) {
+    let endpoint = format!("inproc://priority-{}", name);
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.bind(&endpoint).unwrap();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.connect(&endpoint).unwrap();
+    (push, pull)
+}
+
+test!(test_priority_receiver_prefers_higher_priority, {
+    let ctx = Context::new();
+    let (low_tx, low_rx) = pipe(&ctx, "low");
+    let (high_tx, high_rx) = pipe(&ctx, "high");
+    let mut receiver = PriorityReceiver::new();
+    let low = receiver.add(&low_rx, -1);
+    let high = receiver.add(&high_rx, 1);
+    assert_eq!(receiver.len(), 2);
+
+    for i in 0..3 {
+        low_tx.send(format!("low {}", i), 0).unwrap();
+        high_tx.send(format!("high {}", i), 0).unwrap();
+    }
+    let mut order = Vec::new();
+    while let Some((id, parts)) = receiver.recv(100).unwrap() {
+        order.push((id, String::from_utf8(parts.concat()).unwrap()));
+    }
+    let expected: Vec<_> = (0..3)
+        .map(|i| (high, format!("high {}", i)))
+        .chain((0..3).map(|i| (low, format!("low {}", i))))
+        .collect();
+    assert_eq!(order, expected);
+});
+
+test!(test_priority_receiver_weighted_round_robin, {
+    let ctx = Context::new();
+    let (a_tx, a_rx) = pipe(&ctx, "a");
+    let (b_tx, b_rx) = pipe(&ctx, "b");
+    let mut receiver = PriorityReceiver::new();
+    let a = receiver.add_weighted(&a_rx, 0, 2);
+    let b = receiver.add(&b_rx, 0);
+
+    for _ in 0..4 {
+        a_tx.send("a", 0).unwrap();
+    }
+    for _ in 0..4 {
+        b_tx.send("b", 0).unwrap();
+    }
+    let mut order = Vec::new();
+    while let Some((id, _)) = receiver.recv(100).unwrap() {
+        order.push(id);
+    }
+    assert_eq!(order, vec![a, a, b, a, a, b, b, b]);
+});
+
+test!(test_priority_receiver_timeout, {
+    let ctx = Context::new();
+    let (_tx, rx) = pipe(&ctx, "idle");
+    let mut receiver = PriorityReceiver::new();
+    receiver.add(&rx, 0);
+    assert_eq!(receiver.recv(0).unwrap(), None);
+});
+
+test!(test_priority_receiver_deadline, {
+    let ctx = Context::new();
+    let (_tx, rx) = pipe(&ctx, "deadline");
+    let mut receiver = PriorityReceiver::new();
+    receiver.add(&rx, 0);
+    let start = Instant::now();
+    assert_eq!(receiver.recv(50).unwrap(), None);
+    let elapsed = start.elapsed();
+    assert!(elapsed >= Duration::from_millis(45));
+    assert!(elapsed < Duration::from_secs(5));
+});
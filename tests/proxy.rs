@@ -0,0 +1,59 @@
+#[macro_use]
+mod common;
+
+use std::thread;
+
+use zmq::Context;
+
+test!(test_proxy_queue, {
+    let ctx = Context::new();
+    let frontend = ctx.socket(zmq::ROUTER).unwrap();
+    frontend.bind("inproc://proxy-frontend").unwrap();
+    let backend = ctx.socket(zmq::DEALER).unwrap();
+    backend.bind("inproc://proxy-backend").unwrap();
+    // The proxy only returns once the context is terminated, which does not
+    // happen before the test process exits.
+    thread::spawn(move || zmq::proxy(&frontend, &backend));
+
+    let client = ctx.socket(zmq::REQ).unwrap();
+    client.connect("inproc://proxy-frontend").unwrap();
+    let worker = ctx.socket(zmq::REP).unwrap();
+    worker.connect("inproc://proxy-backend").unwrap();
+
+    client.send("ping", 0).unwrap();
+    assert_eq!(worker.recv_string(0).unwrap().unwrap(), "ping");
+    worker.send("pong", 0).unwrap();
+    assert_eq!(client.recv_string(0).unwrap().unwrap(), "pong");
+});
+
+test!(test_proxy_steerable_terminate, {
+    let ctx = Context::new();
+    let mut frontend = ctx.socket(zmq::PULL).unwrap();
+    frontend.bind("inproc://steerable-frontend").unwrap();
+    let mut backend = ctx.socket(zmq::PUSH).unwrap();
+    backend.bind("inproc://steerable-backend").unwrap();
+    let mut control = ctx.socket(zmq::PAIR).unwrap();
+    control.bind("inproc://steerable-control").unwrap();
+    let proxy =
+        thread::spawn(move || zmq::proxy_steerable(&mut frontend, &mut backend, &mut control));
+
+    let producer = ctx.socket(zmq::PUSH).unwrap();
+    producer.connect("inproc://steerable-frontend").unwrap();
+    let consumer = ctx.socket(zmq::PULL).unwrap();
+    consumer.connect("inproc://steerable-backend").unwrap();
+    let commands = ctx.socket(zmq::PAIR).unwrap();
+    commands.connect("inproc://steerable-control").unwrap();
+
+    producer.send("first", 0).unwrap();
+    assert_eq!(consumer.recv_string(0).unwrap().unwrap(), "first");
+
+    // While paused, messages stay queued in the frontend.
+    commands.send("PAUSE", 0).unwrap();
+    producer.send("second", 0).unwrap();
+    assert_eq!(consumer.poll(zmq::POLLIN, 100).unwrap(), 0);
+    commands.send("RESUME", 0).unwrap();
+    assert_eq!(consumer.recv_string(0).unwrap().unwrap(), "second");
+
+    commands.send("TERMINATE", 0).unwrap();
+    assert_eq!(proxy.join().unwrap(), Ok(()));
+});
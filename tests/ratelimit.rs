@@ -0,0 +1,147 @@
+#[macro_use]
+mod common;
+
+use std::time::{Duration, Instant};
+
+use zmq::{Context, OnExcess, RateLimit, RateLimitedSocket, Socket};
+
+fn push_pull(ctx: &Context, name: &str, limit: RateLimit) -> (RateLimitedSocket, Socket) {
+    let endpoint = format!("inproc://ratelimit-{}", name);
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.bind(&endpoint).unwrap();
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.connect(&endpoint).unwrap();
+    (RateLimitedSocket::new(push, limit), pull)
+}
+
+fn received(pull: &Socket) -> usize {
+    let mut count = 0;
+    while pull.recv_multipart(zmq::DONTWAIT).is_ok() {
+        count += 1;
+    }
+    count
+}
+
+test!(test_rate_limit_drop, {
+    let ctx = Context::new();
+    let (mut push, pull) = push_pull(
+        &ctx,
+        "drop",
+        RateLimit {
+            messages_per_sec: Some(5.0),
+            bytes_per_sec: None,
+            on_excess: OnExcess::Drop,
+        },
+    );
+    for _ in 0..8 {
+        push.send_multipart(["a", "b"], 0).unwrap();
+    }
+    assert_eq!(push.dropped(), 3);
+    assert_eq!(received(&pull), 5);
+});
+
+test!(test_rate_limit_error_on_bytes, {
+    let ctx = Context::new();
+    let (mut push, pull) = push_pull(
+        &ctx,
+        "error",
+        RateLimit {
+            messages_per_sec: None,
+            bytes_per_sec: Some(10.0),
+            on_excess: OnExcess::Error,
+        },
+    );
+    push.send("0123456789", 0).unwrap();
+    assert_eq!(push.send("x", 0), Err(zmq::Error::EAGAIN));
+    assert_eq!(push.dropped(), 0);
+    assert_eq!(received(&pull), 1);
+});
+
+test!(test_rate_limit_empty_multipart, {
+    let ctx = Context::new();
+    let (mut push, pull) = push_pull(
+        &ctx,
+        "empty",
+        RateLimit {
+            messages_per_sec: Some(1.0),
+            bytes_per_sec: None,
+            on_excess: OnExcess::Error,
+        },
+    );
+    push.send_multipart(Vec::<Vec<u8>>::new(), 0).unwrap();
+    // The empty message took no tokens, so this one is admitted.
+    push.send("a", 0).unwrap();
+    assert_eq!(push.dropped(), 0);
+    assert_eq!(received(&pull), 1);
+});
+
+test!(test_rate_limit_multipart_parts_not_limited, {
+    let ctx = Context::new();
+    let (mut push, pull) = push_pull(
+        &ctx,
+        "parts",
+        RateLimit {
+            messages_per_sec: Some(1.0),
+            bytes_per_sec: None,
+            on_excess: OnExcess::Error,
+        },
+    );
+    push.send("head", zmq::SNDMORE).unwrap();
+    push.send("body", zmq::SNDMORE).unwrap();
+    push.send("tail", 0).unwrap();
+    assert_eq!(push.send("next", 0), Err(zmq::Error::EAGAIN));
+    assert_eq!(
+        pull.recv_multipart(0).unwrap(),
+        vec![b"head".to_vec(), b"body".to_vec(), b"tail".to_vec()]
+    );
+});
+
+test!(test_rate_limit_multipart_over_limit, {
+    let ctx = Context::new();
+    for on_excess in [OnExcess::Drop, OnExcess::Error] {
+        let (mut push, pull) = push_pull(
+            &ctx,
+            &format!("over-{:?}", on_excess),
+            RateLimit {
+                messages_per_sec: Some(1.0),
+                bytes_per_sec: None,
+                on_excess,
+            },
+        );
+        push.send("first", 0).unwrap();
+        // The whole second message is discarded, not just its first part.
+        let results = [
+            push.send("head", zmq::SNDMORE),
+            push.send("body", zmq::SNDMORE),
+            push.send("tail", 0),
+        ];
+        for result in results {
+            match on_excess {
+                OnExcess::Drop => assert_eq!(result, Ok(())),
+                _ => assert_eq!(result, Err(zmq::Error::EAGAIN)),
+            }
+        }
+        assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"first".to_vec()]);
+        assert_eq!(received(&pull), 0);
+    }
+});
+
+test!(test_rate_limit_block, {
+    let ctx = Context::new();
+    let (mut push, pull) = push_pull(
+        &ctx,
+        "block",
+        RateLimit {
+            messages_per_sec: Some(20.0),
+            bytes_per_sec: None,
+            on_excess: OnExcess::Block,
+        },
+    );
+    let start = Instant::now();
+    for _ in 0..25 {
+        push.send("tick", 0).unwrap();
+    }
+    // The first 20 messages are a burst; the other 5 take 50ms each.
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(received(&pull), 25);
+});
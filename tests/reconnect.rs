@@ -0,0 +1,116 @@
+#[macro_use]
+mod common;
+
+use std::time::Duration;
+use zmq::ReconnectPolicy;
+
+test!(test_apply_constant, {
+    let ctx = zmq::Context::new();
+    let sock = ctx.socket(zmq::DEALER).unwrap();
+    ReconnectPolicy::Constant(Duration::from_millis(250))
+        .apply(&sock)
+        .unwrap();
+    assert_eq!(sock.get_reconnect_ivl().unwrap(), 250);
+    assert_eq!(sock.get_reconnect_ivl_max().unwrap(), 0);
+});
+
+test!(test_apply_jitter, {
+    let ctx = zmq::Context::new();
+    let sock = ctx.socket(zmq::DEALER).unwrap();
+    let policy = ReconnectPolicy::DecorrelatedJitter {
+        base: Duration::from_millis(100),
+        cap: Duration::from_secs(5),
+    };
+    policy.apply(&sock).unwrap();
+    let ivl = sock.get_reconnect_ivl().unwrap();
+    assert!((100..=300).contains(&ivl));
+    assert_eq!(sock.get_reconnect_ivl_max().unwrap(), 5000);
+});
+
+test!(test_exponential_backoff, {
+    let policy = ReconnectPolicy::Exponential {
+        initial: Duration::from_millis(100),
+        max: Duration::from_millis(500),
+    };
+    let mut backoff = policy.backoff();
+    let delays: Vec<_> = backoff.by_ref().take(5).collect();
+    assert_eq!(
+        delays,
+        [100, 200, 400, 500, 500]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect::<Vec<_>>()
+    );
+    backoff.reset();
+    assert_eq!(backoff.next(), Some(Duration::from_millis(100)));
+});
+
+test!(test_jitter_backoff_bounds, {
+    let base = Duration::from_millis(10);
+    let cap = Duration::from_millis(1000);
+    let policy = ReconnectPolicy::DecorrelatedJitter { base, cap };
+    let mut previous = base;
+    for delay in policy.backoff().take(100) {
+        assert!(delay >= base);
+        assert!(delay <= cap);
+        assert!(delay <= previous * 3);
+        previous = delay;
+    }
+});
+
+test!(test_reconnect_watcher, {
+    let ctx = zmq::Context::new();
+    let server = ctx.socket(zmq::ROUTER).unwrap();
+    server.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = server.get_last_endpoint().unwrap().unwrap();
+
+    let client = ctx.socket(zmq::DEALER).unwrap();
+    client.set_reconnect_ivl(10).unwrap();
+    let mut reconnected = Vec::new();
+    let mut watcher = zmq::ReconnectWatcher::new(&client).unwrap();
+    watcher.on_reconnect(|endpoint| reconnected.push(endpoint.to_owned()));
+    client.connect(&endpoint).unwrap();
+
+    // The initial connection does not count.
+    assert_eq!(watcher.process(5000).unwrap(), 0);
+
+    server.close_with_linger(0).unwrap();
+    let server = ctx.socket(zmq::ROUTER).unwrap();
+    // The old socket's listener is closed asynchronously.
+    while server.bind(&endpoint) == Err(zmq::Error::EADDRINUSE) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let mut reconnects = 0;
+    for _ in 0..10 {
+        reconnects += watcher.process(1000).unwrap();
+        if reconnects > 0 {
+            break;
+        }
+    }
+    assert_eq!(reconnects, 1);
+
+    drop(watcher);
+    assert_eq!(reconnected, vec![endpoint]);
+});
+
+test!(test_sub_millisecond_delays, {
+    // A `ZMQ_RECONNECT_IVL` of 0 would disable reconnection.
+    let ctx = zmq::Context::new();
+    let sock = ctx.socket(zmq::DEALER).unwrap();
+    let policy = ReconnectPolicy::Constant(Duration::from_micros(100));
+    policy.apply(&sock).unwrap();
+    assert_eq!(sock.get_reconnect_ivl().unwrap(), 1);
+
+    let policy = ReconnectPolicy::Exponential {
+        initial: Duration::from_micros(0),
+        max: Duration::from_millis(4),
+    };
+    let delays: Vec<_> = policy.backoff().take(4).collect();
+    assert_eq!(
+        delays,
+        [1, 2, 4, 4]
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect::<Vec<_>>()
+    );
+});
@@ -0,0 +1,60 @@
+#[macro_use]
+mod common;
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use zmq::{Context, Recorder, Replayer};
+
+test!(test_record_replay_file, {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("recording");
+    let ctx = Context::new();
+
+    let source = ctx.socket(zmq::PUSH).unwrap();
+    let tap = ctx.socket(zmq::PULL).unwrap();
+    tap.bind("inproc://record-tap").unwrap();
+    source.connect("inproc://record-tap").unwrap();
+
+    let mut recorder = Recorder::create(&path).unwrap();
+    source.send_multipart(["a", "1"], 0).unwrap();
+    assert_eq!(recorder.tap(&tap, 0).unwrap(), vec![b"a", b"1"]);
+    std::thread::sleep(Duration::from_millis(50));
+    source.send("b", 0).unwrap();
+    recorder.tap(&tap, 0).unwrap();
+    recorder.finish().unwrap();
+
+    let records = Replayer::open(&path)
+        .unwrap()
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].parts, vec![b"b".to_vec()]);
+    assert!(records[1].offset - records[0].offset >= Duration::from_millis(50));
+
+    let sink = ctx.socket(zmq::PULL).unwrap();
+    sink.bind("inproc://record-sink").unwrap();
+    let replay = ctx.socket(zmq::PUSH).unwrap();
+    replay.connect("inproc://record-sink").unwrap();
+
+    let start = Instant::now();
+    let sent = Replayer::open(&path).unwrap().replay(&replay, 1.0).unwrap();
+    assert_eq!(sent, 2);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(sink.recv_multipart(0).unwrap(), vec![b"a", b"1"]);
+    assert_eq!(sink.recv_multipart(0).unwrap(), vec![b"b"]);
+});
+
+test!(test_replay_invalid, {
+    let err = Replayer::new(&b"garbage!"[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let mut recording = Vec::new();
+    let mut recorder = Recorder::new(&mut recording).unwrap();
+    recorder.record(&["truncated"]).unwrap();
+    recorder.finish().unwrap();
+    recording.pop();
+    let mut replayer = Replayer::new(&recording[..]).unwrap();
+    let err = replayer.next().unwrap().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+});
@@ -0,0 +1,59 @@
+#[macro_use]
+mod common;
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+use zmq::{Context, Shutdown};
+
+test!(test_shutdown_drains_and_joins, {
+    let ctx = Context::new();
+    let intake = ctx.socket(zmq::PULL).unwrap();
+    intake.bind("inproc://shutdown-intake").unwrap();
+    let producer = ctx.socket(zmq::PUSH).unwrap();
+    producer.connect("inproc://shutdown-intake").unwrap();
+    for i in 0..3 {
+        producer.send(format!("job {}", i), 0).unwrap();
+    }
+
+    let worker_socket = ctx.socket(zmq::REP).unwrap();
+    worker_socket.bind("inproc://shutdown-worker").unwrap();
+    let worker = std::thread::spawn(move || {
+        assert_eq!(worker_socket.recv_bytes(0), Err(zmq::Error::ETERM));
+    });
+    let panicking = std::thread::spawn(|| panic!("actor failure"));
+
+    let events = RefCell::new(Vec::new());
+    let mut shutdown = Shutdown::new(ctx, Duration::from_secs(5));
+    shutdown
+        .on_stop(|| events.borrow_mut().push("stop".to_string()))
+        .drain(intake, |msg| {
+            events
+                .borrow_mut()
+                .push(String::from_utf8(msg.concat()).unwrap())
+        })
+        .socket(producer)
+        .actor(worker)
+        .actor(panicking);
+    let report = shutdown.run().unwrap();
+
+    assert_eq!(report.drained, 3);
+    assert!(!report.deadline_exceeded);
+    assert_eq!(report.panicked_actors, 1);
+    assert_eq!(events.into_inner(), vec!["stop", "job 0", "job 1", "job 2"]);
+});
+
+test!(test_shutdown_bounds_linger, {
+    let ctx = Context::new();
+    let sender = ctx.socket(zmq::PUSH).unwrap();
+    sender.connect("tcp://127.0.0.1:1").unwrap();
+    sender.send("undeliverable", zmq::DONTWAIT).unwrap();
+
+    let start = Instant::now();
+    let mut shutdown = Shutdown::new(ctx, Duration::from_millis(100));
+    shutdown.socket(sender);
+    shutdown.run().unwrap();
+    // Dropping the last socket and context handle terminates the context,
+    // which must not wait beyond the deadline for the pending message.
+    assert!(start.elapsed() < Duration::from_secs(5));
+});
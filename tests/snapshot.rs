@@ -0,0 +1,63 @@
+#[macro_use]
+mod common;
+
+use zmq::{OptionChange, OptionValue};
+
+test!(test_option_snapshot, {
+    let ctx = zmq::Context::new();
+    let socket = ctx.socket(zmq::DEALER).unwrap();
+    socket.set_sndhwm(42).unwrap();
+    socket.set_identity(b"worker").unwrap();
+
+    let snapshot = socket.option_snapshot().unwrap();
+    assert_eq!(
+        snapshot.get("type"),
+        Some(&OptionValue::Str("DEALER".into()))
+    );
+    assert_eq!(
+        snapshot.get("mechanism"),
+        Some(&OptionValue::Str("NULL".into()))
+    );
+    assert_eq!(snapshot.get("sndhwm"), Some(&OptionValue::Int(42)));
+    assert_eq!(snapshot.get("immediate"), Some(&OptionValue::Bool(false)));
+    assert_eq!(
+        snapshot.get("routing_id"),
+        Some(&OptionValue::Bytes(b"worker".to_vec()))
+    );
+    assert!(snapshot.get("curve_secretkey").is_none());
+    assert_eq!(snapshot.iter().count(), snapshot.len());
+});
+
+test!(test_option_snapshot_diff, {
+    let ctx = zmq::Context::new();
+    let a = ctx.socket(zmq::PUSH).unwrap();
+    let b = ctx.socket(zmq::PUSH).unwrap();
+    assert!(a
+        .option_snapshot()
+        .unwrap()
+        .diff(&b.option_snapshot().unwrap())
+        .is_empty());
+
+    b.set_linger(0).unwrap();
+    b.set_sndhwm(10).unwrap();
+    let diff = a
+        .option_snapshot()
+        .unwrap()
+        .diff(&b.option_snapshot().unwrap());
+    assert_eq!(
+        diff,
+        vec![
+            OptionChange {
+                name: "linger",
+                before: Some(OptionValue::Int(-1)),
+                after: Some(OptionValue::Int(0)),
+            },
+            OptionChange {
+                name: "sndhwm",
+                before: Some(OptionValue::Int(1000)),
+                after: Some(OptionValue::Int(10)),
+            },
+        ]
+    );
+    assert_eq!(diff[0].to_string(), "linger: -1 -> 0");
+});
@@ -0,0 +1,118 @@
+#[macro_use]
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+
+use zmq::{Context, Spooler};
+
+test!(test_spool_and_flush, {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spool");
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.bind("inproc://spool").unwrap();
+
+    let mut spooler = Spooler::open(&path, 1024).unwrap();
+    spooler.send(&push, &["1", "a"]).unwrap();
+    spooler.send(&push, &["2"]).unwrap();
+    assert_eq!(spooler.len(), 2);
+
+    // The spool survives reopening.
+    drop(spooler);
+    let mut spooler = Spooler::open(&path, 1024).unwrap();
+    assert_eq!(spooler.len(), 2);
+
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.connect("inproc://spool").unwrap();
+    spooler.send(&push, &["3"]).unwrap();
+    while !spooler.is_empty() {
+        spooler.flush(&push).unwrap();
+    }
+    assert_eq!(spooler.spooled_bytes(), 0);
+
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"1", b"a"]);
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"2"]);
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"3"]);
+});
+
+test!(test_partial_flush, {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spool");
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.set_sndhwm(1).unwrap();
+    push.bind("inproc://spool-partial").unwrap();
+
+    let mut spooler = Spooler::open(&path, 1024).unwrap();
+    for i in 0..10 {
+        spooler.spool(&[i.to_string()]).unwrap();
+    }
+
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.set_rcvhwm(1).unwrap();
+    pull.connect("inproc://spool-partial").unwrap();
+
+    let mut received = Vec::new();
+    while received.len() < 10 {
+        spooler.flush(&push).unwrap();
+        // Reopening reflects the messages sent so far.
+        assert_eq!(Spooler::open(&path, 1024).unwrap().len(), spooler.len());
+        if let Ok(msg) = pull.recv_string(zmq::DONTWAIT) {
+            received.push(msg.unwrap());
+        }
+    }
+    let expected: Vec<_> = (0..10).map(|i| i.to_string()).collect();
+    assert_eq!(received, expected);
+});
+
+test!(test_spool_full, {
+    let dir = tempfile::tempdir().unwrap();
+    let mut spooler = Spooler::open(dir.path().join("spool"), 20).unwrap();
+    spooler.spool(&["0123456789"]).unwrap();
+    let err = spooler.spool(&["0123456789"]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+    assert_eq!(spooler.len(), 1);
+});
+
+test!(test_spool_partial_record, {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("spool");
+    let mut spooler = Spooler::open(&path, 1024).unwrap();
+    spooler.spool(&["1"]).unwrap();
+    spooler.spool(&["2", "b"]).unwrap();
+    spooler.spool(&["3"]).unwrap();
+    drop(spooler);
+
+    // A crash in the middle of appending the last record.
+    let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(file.metadata().unwrap().len() - 3).unwrap();
+    drop(file);
+    let spooler = Spooler::open(&path, 1024).unwrap();
+    assert_eq!(spooler.len(), 2);
+    assert_eq!(fs::metadata(&path).unwrap().len(), spooler.spooled_bytes());
+    drop(spooler);
+
+    // A crash in the middle of the header of a record.
+    let mut file = fs::OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[0, 0]).unwrap();
+    drop(file);
+    let mut spooler = Spooler::open(&path, 1024).unwrap();
+    assert_eq!(spooler.len(), 2);
+
+    spooler.spool(&["4"]).unwrap();
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    push.bind("inproc://spool-partial").unwrap();
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.connect("inproc://spool-partial").unwrap();
+    while !spooler.is_empty() {
+        spooler.flush(&push).unwrap();
+    }
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"1".to_vec()]);
+    assert_eq!(
+        pull.recv_multipart(0).unwrap(),
+        vec![b"2".to_vec(), b"b".to_vec()]
+    );
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"4".to_vec()]);
+});
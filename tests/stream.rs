@@ -0,0 +1,96 @@
+#[macro_use]
+mod common;
+
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use zmq::stream::{ConnectionEvent, StreamConnections};
+use zmq::Context;
+
+fn bind_connections(ctx: &Context) -> (StreamConnections, String) {
+    let socket = ctx.socket(zmq::STREAM).unwrap();
+    socket.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = socket.get_last_endpoint().unwrap().unwrap();
+    let address = endpoint.trim_start_matches("tcp://").to_owned();
+    (StreamConnections::new(socket).unwrap(), address)
+}
+
+fn expect_connected(connections: &mut StreamConnections) -> Vec<u8> {
+    match connections.recv(0).unwrap() {
+        ConnectionEvent::Connected(peer) => peer,
+        other => panic!("unexpected event: {:?}", other),
+    }
+}
+
+test!(test_stream_connections_wrong_type, {
+    let ctx = Context::new();
+    let socket = ctx.socket(zmq::ROUTER).unwrap();
+    assert!(StreamConnections::new(socket).err() == Some(zmq::Error::EINVAL));
+});
+
+test!(test_stream_connections_read_write, {
+    let ctx = Context::new();
+    let (mut connections, address) = bind_connections(&ctx);
+
+    let mut first = TcpStream::connect(&address).unwrap();
+    let first_peer = expect_connected(&mut connections);
+    let mut second = TcpStream::connect(&address).unwrap();
+    let second_peer = expect_connected(&mut connections);
+    assert_ne!(first_peer, second_peer);
+
+    let mut buf = [0; 16];
+    let err = connections.reader(&first_peer).read(&mut buf).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+
+    second.write_all(b"from second").unwrap();
+    assert_eq!(
+        connections.recv(0).unwrap(),
+        ConnectionEvent::Data(second_peer.clone())
+    );
+    let len = connections.reader(&second_peer).read(&mut buf).unwrap();
+    assert_eq!(&buf[..len], b"from second");
+
+    connections
+        .writer(&first_peer)
+        .write_all(b"to first")
+        .unwrap();
+    let mut reply = [0; 8];
+    first.read_exact(&mut reply).unwrap();
+    assert_eq!(&reply, b"to first");
+
+    // Data buffered before a disconnect remains readable.
+    first.write_all(b"bye").unwrap();
+    drop(first);
+    assert_eq!(
+        connections.recv(0).unwrap(),
+        ConnectionEvent::Data(first_peer.clone())
+    );
+    assert_eq!(
+        connections.recv(0).unwrap(),
+        ConnectionEvent::Disconnected(first_peer.clone())
+    );
+    let mut rest = Vec::new();
+    connections
+        .reader(&first_peer)
+        .read_to_end(&mut rest)
+        .unwrap();
+    assert_eq!(rest, b"bye");
+    assert_eq!(
+        connections.peers().collect::<Vec<_>>(),
+        vec![&second_peer[..]]
+    );
+
+    // The connection is closed once the socket processes its commands,
+    // which polling does.
+    connections.disconnect(&second_peer).unwrap();
+    second
+        .set_read_timeout(Some(Duration::from_millis(50)))
+        .unwrap();
+    let mut eof = Vec::new();
+    while let Err(e) = second.read_to_end(&mut eof) {
+        assert_eq!(e.kind(), io::ErrorKind::WouldBlock);
+        connections.as_socket().poll(zmq::POLLIN, 0).unwrap();
+    }
+    assert!(eof.is_empty());
+});
@@ -1,8 +1,9 @@
 #[macro_use]
 mod common;
 
-use std::io;
+use std::io::{self, Read};
 use std::net::TcpStream;
+use std::time::Duration;
 use zmq::*;
 
 fn version_ge_4_2() -> bool {
@@ -52,7 +53,8 @@ test!(test_exchanging_bytes, {
 
     receiver.send("a quite long string", 0).unwrap();
     let mut buf = [0_u8; 10];
-    sender.recv_into(&mut buf, 0).unwrap(); // this should truncate the message
+    // this should truncate the message, and report its full length
+    assert_eq!(sender.recv_into(&mut buf, 0).unwrap(), 19);
     assert_eq!(&buf[..], b"a quite lo");
 });
 
@@ -87,6 +89,85 @@ test!(test_exchanging_multipart, {
     assert_eq!(&msg2[..], b"bar");
 });
 
+test!(test_forwarding_message, {
+    let ctx = Context::new();
+    let frontend = ctx.socket(PAIR).unwrap();
+    frontend.bind("inproc://forward-front").unwrap();
+    let backend = ctx.socket(PAIR).unwrap();
+    backend.bind("inproc://forward-back").unwrap();
+    let client = ctx.socket(PAIR).unwrap();
+    client.connect("inproc://forward-front").unwrap();
+    let worker = ctx.socket(PAIR).unwrap();
+    worker.connect("inproc://forward-back").unwrap();
+
+    client.send_multipart(["header", "body"], 0).unwrap();
+    loop {
+        let msg = frontend.recv_msg(0).unwrap();
+        let flags = if msg.get_more() { SNDMORE } else { 0 };
+        backend.send(msg, flags).unwrap();
+        if flags == 0 {
+            break;
+        }
+    }
+    assert_eq!(
+        worker.recv_multipart(0).unwrap(),
+        vec![b"header".to_vec(), b"body".to_vec()]
+    );
+});
+
+test!(test_multipart_limits, {
+    let ctx = Context::new();
+    let receiver = ctx.socket(PULL).unwrap();
+    receiver.bind("inproc://multipart-limits").unwrap();
+    let sender = ctx.socket(PUSH).unwrap();
+    sender.connect("inproc://multipart-limits").unwrap();
+    assert_eq!(receiver.get_multipart_limits(), None);
+
+    receiver.set_multipart_limits(Some(MultipartLimits {
+        max_parts: Some(2),
+        max_bytes: Some(6),
+    }));
+
+    sender.send_multipart(["a", "b", "c"], 0).unwrap();
+    sender.send_multipart(["long", "parts"], 0).unwrap();
+    sender.send_multipart(["foo", "bar"], 0).unwrap();
+
+    // Offending messages are discarded entirely, leaving the socket ready for
+    // the next message.
+    assert_eq!(receiver.recv_multipart(0), Err(Error::EMSGSIZE));
+    assert_eq!(receiver.recv_multipart(0), Err(Error::EMSGSIZE));
+    assert_eq!(receiver.recv_multipart(0).unwrap(), vec![b"foo", b"bar"]);
+});
+
+test!(test_recv_multipart_msg, {
+    let ctx = Context::new();
+    let receiver = ctx.socket(PAIR).unwrap();
+    receiver.bind("inproc://recv-multipart-msg").unwrap();
+    let sender = ctx.socket(PAIR).unwrap();
+    sender.connect("inproc://recv-multipart-msg").unwrap();
+    sender.send_multipart(["id", "", "body"], 0).unwrap();
+    sender.send("next", 0).unwrap();
+
+    let parts = receiver.recv_multipart_msg(0).unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].as_str(), Some("id"));
+    assert!(parts[1].is_empty());
+    assert_eq!(parts[2].as_str(), Some("body"));
+    assert!(!parts[2].get_more());
+
+    // The whole message was consumed.
+    assert_eq!(receiver.recv_string(0).unwrap().unwrap(), "next");
+});
+
+test!(test_message_metadata, {
+    let (sender, receiver) = create_socketpair();
+    sender.send("foo", 0).unwrap();
+    let msg = receiver.recv_msg(0).unwrap();
+    assert_eq!(msg.gets("Peer-Address"), Some("127.0.0.1"));
+    assert_eq!(msg.gets("Socket-Type"), Some("REQ"));
+    assert_eq!(msg.gets("No-Such-Property"), None);
+});
+
 test!(test_polling, {
     let (sender, receiver) = create_socketpair();
 
@@ -103,6 +184,25 @@ test!(test_polling, {
     assert!(!poll_items[0].is_error());
     assert!(poll_items[0].has_socket(&receiver));
     assert!(!poll_items[0].has_fd(0));
+    assert_eq!(
+        ready_items(&poll_items).collect::<Vec<_>>(),
+        vec![(0, POLLIN)]
+    );
+});
+
+test!(test_polling_timeout, {
+    let (sender, receiver) = create_socketpair();
+
+    // A sub-millisecond timeout still waits, rather than returning at once.
+    let start = std::time::Instant::now();
+    let timeout = Some(Duration::from_micros(100));
+    assert_eq!(receiver.poll_timeout(POLLIN, timeout).unwrap(), 0);
+    assert!(start.elapsed() >= Duration::from_micros(100));
+
+    sender.send("Hello!", 0).unwrap();
+    let mut poll_items = vec![receiver.as_poll_item(POLLIN)];
+    assert_eq!(poll_timeout(&mut poll_items, None).unwrap(), 1);
+    assert!(poll_items[0].is_readable());
 });
 
 test!(test_raw_roundtrip, {
@@ -117,6 +217,118 @@ test!(test_raw_roundtrip, {
     let _ = unsafe { Socket::from_raw(raw) };
 });
 
+test!(test_close, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+    sock.set_linger(0).unwrap();
+    sock.connect("tcp://127.0.0.1:1").unwrap();
+    sock.close().unwrap();
+    // Terminating the context must not wait for the closed socket.
+    drop(ctx);
+});
+
+test!(test_close_with_linger, {
+    let ctx = Context::new();
+    let sock = ctx.socket(SocketType::PUSH).unwrap();
+    sock.connect("tcp://127.0.0.1:1").unwrap();
+    sock.send("undeliverable", DONTWAIT).unwrap();
+    sock.close_with_linger(0).unwrap();
+    // With a zero linger period, terminating the context must not block on
+    // the pending message.
+    drop(ctx);
+});
+
+test!(test_close_with_timeout, {
+    // The peer cannot be reached, so the message is discarded.
+    let sock = Context::new().socket(PUSH).unwrap();
+    sock.connect("tcp://127.0.0.1:1").unwrap();
+    sock.send("undeliverable", DONTWAIT).unwrap();
+    let outcome = sock.close_with_timeout(Duration::from_millis(100));
+    assert_eq!(outcome, Ok(CloseOutcome::TimedOut));
+
+    let receiver = Context::new().socket(PULL).unwrap();
+    receiver.bind("tcp://127.0.0.1:*").unwrap();
+    let endpoint = receiver.get_last_endpoint().unwrap().unwrap();
+    let receiver = std::thread::spawn(move || {
+        let mut received = vec![receiver.recv_bytes(0).unwrap()];
+        received.push(receiver.recv_bytes(0).unwrap());
+        received.sort();
+        received
+    });
+    let timeout = Duration::from_secs(5);
+
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+    sock.connect(&endpoint).unwrap();
+    sock.send("first", 0).unwrap();
+    assert_eq!(sock.close_with_timeout(timeout), Ok(CloseOutcome::Pending));
+
+    // The socket holds the last handle to its context.
+    let sock = Context::new().socket(PUSH).unwrap();
+    sock.connect(&endpoint).unwrap();
+    sock.send("second", 0).unwrap();
+    assert_eq!(sock.close_with_timeout(timeout), Ok(CloseOutcome::Flushed));
+
+    // The messages arrive over separate connections, in either order.
+    assert_eq!(
+        receiver.join().unwrap(),
+        vec![b"first".to_vec(), b"second".to_vec()]
+    );
+});
+
+test!(test_bind_scoped, {
+    let ctx = Context::new();
+    let socket = ctx.socket(PULL).unwrap();
+    let endpoint = {
+        let binding = socket.bind_scoped("tcp://127.0.0.1:*").unwrap();
+        assert!(binding.endpoint().starts_with("tcp://127.0.0.1:"));
+        assert_ne!(binding.endpoint(), "tcp://127.0.0.1:*");
+        binding.endpoint().to_owned()
+    };
+    // Dropping the guard unbound the endpoint, so it is free again once
+    // the listener has been closed asynchronously.
+    let other = ctx.socket(PULL).unwrap();
+    while other.bind(&endpoint) == Err(Error::EADDRINUSE) {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert_eq!(socket.unbind(&endpoint), Err(Error::ENOENT));
+
+    let binding = socket.bind_scoped("inproc://scoped").unwrap();
+    assert_eq!(binding.endpoint(), "inproc://scoped");
+    binding.unbind().unwrap();
+    socket.bind("inproc://scoped").unwrap();
+});
+
+test!(test_send_blocking_with_progress, {
+    let ctx = Context::new();
+    let sender = ctx.socket(PUSH).unwrap();
+    let receiver = ctx.socket(PULL).unwrap();
+    sender.set_sndhwm(1).unwrap();
+    receiver.set_rcvhwm(1).unwrap();
+    sender.bind("inproc://send-progress").unwrap();
+    receiver.connect("inproc://send-progress").unwrap();
+
+    let mut queued = 0;
+    while sender.send("fill", DONTWAIT).is_ok() {
+        queued += 1;
+    }
+    let drain = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(250));
+        for _ in 0..=queued {
+            receiver.recv_bytes(0).unwrap();
+        }
+    });
+
+    let mut progress = Vec::new();
+    sender
+        .send_blocking_with_progress("last", 0, |waited| progress.push(waited))
+        .unwrap();
+    drain.join().unwrap();
+    assert!(progress.len() >= 2);
+    assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    assert!(*progress.last().unwrap() >= std::time::Duration::from_millis(100));
+});
+
 // The `conflate` option limits the buffer size to one; let's see if we can get
 // messages (unreliably) across the connection.
 test!(test_conflating_receiver, {
@@ -176,9 +388,44 @@ test!(test_zmq_error, {
     assert_eq!(desc, debug);
 });
 
+test!(test_unknown_error, {
+    let err = Error::from_raw(123_456);
+    assert_eq!(err, Error::EUNKNOWN(123_456));
+    assert_eq!(err.to_raw(), 123_456);
+    assert!(!err.message().is_empty());
+    assert_eq!(err.message(), Error::EUNKNOWN(123_456).message());
+    assert_eq!(io::Error::from(err).kind(), io::ErrorKind::Other);
+});
+
 test!(test_into_io_error, {
     let e: io::Error = Error::ENOENT.into();
     assert!(e.kind() == io::ErrorKind::NotFound);
+
+    let kinds = [
+        (Error::EAGAIN, io::ErrorKind::WouldBlock),
+        (Error::ECONNREFUSED, io::ErrorKind::ConnectionRefused),
+        (Error::EINTR, io::ErrorKind::Interrupted),
+        (Error::ENOMEM, io::ErrorKind::OutOfMemory),
+        (Error::ENOTSUP, io::ErrorKind::Unsupported),
+        (Error::ETERM, io::ErrorKind::Other),
+    ];
+    for (error, kind) in kinds {
+        let e = io::Error::from(error);
+        assert_eq!(e.kind(), kind);
+        assert_eq!(e.to_string(), error.to_string());
+        let inner = e.get_ref().and_then(|inner| inner.downcast_ref::<Error>());
+        assert_eq!(inner, Some(&error));
+    }
+});
+
+test!(test_error_trait_object, {
+    fn fails() -> std::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Err(Error::EFSM)?;
+        Ok(())
+    }
+    let e = fails().unwrap_err();
+    assert_eq!(e.downcast_ref::<Error>(), Some(&Error::EFSM));
+    assert!(e.source().is_none());
 });
 
 test!(test_get_socket_type, {
@@ -249,6 +496,51 @@ test!(test_getset_identity, {
     assert_eq!(sock.get_identity().unwrap(), b"moo");
 });
 
+test!(test_getset_vmci_options, {
+    let ctx = Context::new();
+    let sock = ctx.socket(REQ).unwrap();
+    if zmq::has("vmci") {
+        sock.set_vmci_buffer_size(65536).unwrap();
+        assert_eq!(sock.get_vmci_buffer_size().unwrap(), 65536);
+        sock.set_vmci_connect_timeout(500).unwrap();
+        assert_eq!(sock.get_vmci_connect_timeout().unwrap(), 500);
+    } else {
+        assert_eq!(sock.set_vmci_buffer_size(65536), Err(Error::EINVAL));
+        assert_eq!(sock.get_vmci_connect_timeout(), Err(Error::EINVAL));
+    }
+});
+
+test!(test_getset_routing_id, {
+    let ctx = Context::new();
+    let sock = ctx.socket(REQ).unwrap();
+    sock.set_routing_id(b"moo").unwrap();
+    assert_eq!(sock.get_routing_id().unwrap(), b"moo");
+    assert_eq!(sock.get_identity().unwrap(), b"moo");
+});
+
+test!(test_connect_routing_id, {
+    let ctx = Context::new();
+    let server = ctx.socket(ROUTER).unwrap();
+    server.bind("inproc://connect_routing_id").unwrap();
+
+    let client = ctx.socket(ROUTER).unwrap();
+    client.set_routing_id(b"client").unwrap();
+    client.set_connect_routing_id(b"server").unwrap();
+    client.connect("inproc://connect_routing_id").unwrap();
+
+    // The peer is addressable by the chosen identity right away.
+    client.send_multipart([&b"server"[..], b"ping"], 0).unwrap();
+    assert_eq!(
+        server.recv_multipart(0).unwrap(),
+        vec![b"client".to_vec(), b"ping".to_vec()]
+    );
+    server.send_multipart([&b"client"[..], b"pong"], 0).unwrap();
+    assert_eq!(
+        client.recv_multipart(0).unwrap(),
+        vec![b"server".to_vec(), b"pong".to_vec()]
+    );
+});
+
 test!(test_subscription, {
     let ctx = Context::new();
     let sock = ctx.socket(SUB).unwrap();
@@ -256,6 +548,100 @@ test!(test_subscription, {
     assert!(sock.set_unsubscribe(b"/channel").is_ok());
 });
 
+test!(test_generic_sockopt, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+
+    sock.setsockopt(zmq_sys::ZMQ_SNDHWM as i32, 42).unwrap();
+    assert_eq!(sock.get_sndhwm().unwrap(), 42);
+    assert_eq!(sock.getsockopt::<i32>(zmq_sys::ZMQ_SNDHWM as i32), Ok(42));
+
+    sock.setsockopt(zmq_sys::ZMQ_IPV6 as i32, true).unwrap();
+    assert_eq!(sock.getsockopt::<bool>(zmq_sys::ZMQ_IPV6 as i32), Ok(true));
+
+    sock.setsockopt(zmq_sys::ZMQ_MAXMSGSIZE as i32, 1024i64)
+        .unwrap();
+    assert_eq!(sock.get_maxmsgsize().unwrap(), 1024);
+    assert_eq!(
+        sock.getsockopt::<i32>(zmq_sys::ZMQ_MAXMSGSIZE as i32),
+        Err(Error::EINVAL)
+    );
+
+    sock.setsockopt(zmq_sys::ZMQ_ROUTING_ID as i32, "pusher")
+        .unwrap();
+    assert_eq!(
+        sock.getsockopt::<Vec<u8>>(zmq_sys::ZMQ_ROUTING_ID as i32),
+        Ok(b"pusher".to_vec())
+    );
+    assert_eq!(
+        sock.setsockopt(zmq_sys::ZMQ_SUBSCRIBE as i32, &b"topic"[..]),
+        Err(Error::ENOTSUP)
+    );
+});
+
+test!(test_raw_sockopt, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+
+    sock.setsockopt_raw(zmq_sys::ZMQ_LINGER as i32, &250i32.to_ne_bytes())
+        .unwrap();
+    let mut value = [0; 4];
+    let len = sock
+        .getsockopt_raw(zmq_sys::ZMQ_LINGER as i32, &mut value)
+        .unwrap();
+    assert_eq!(len, 4);
+    assert_eq!(i32::from_ne_bytes(value), 250);
+
+    let mut too_small = [0; 2];
+    assert_eq!(
+        sock.getsockopt_raw(zmq_sys::ZMQ_LINGER as i32, &mut too_small),
+        Err(Error::EINVAL)
+    );
+});
+
+test!(test_option_socket_type_mismatch, {
+    let ctx = Context::new();
+    let push = ctx.socket(PUSH).unwrap();
+    assert_eq!(push.set_subscribe(b"topic"), Err(Error::ENOTSUP));
+    assert_eq!(push.set_req_relaxed(true), Err(Error::ENOTSUP));
+    assert_eq!(push.set_router_mandatory(true), Err(Error::ENOTSUP));
+    assert_eq!(push.set_xpub_verbose(true), Err(Error::ENOTSUP));
+    assert_eq!(push.set_xpub_verboser(true), Err(Error::ENOTSUP));
+    assert_eq!(push.set_xpub_manual(true), Err(Error::ENOTSUP));
+    assert_eq!(push.set_invert_matching(true), Err(Error::ENOTSUP));
+    // Options applying to all socket types are still accepted.
+    assert!(push.set_sndhwm(10).is_ok());
+
+    let dealer = ctx.socket(DEALER).unwrap();
+    assert!(dealer.set_probe_router(true).is_ok());
+});
+
+test!(test_router_mandatory_unroutable, {
+    let ctx = Context::new();
+    let router = ctx.socket(ROUTER).unwrap();
+    router.bind("inproc://router_mandatory").unwrap();
+    router.send("nobody", SNDMORE).unwrap();
+    router.send("dropped", 0).unwrap();
+
+    router.set_router_mandatory(true).unwrap();
+    assert_eq!(router.send("nobody", SNDMORE), Err(Error::EHOSTUNREACH));
+});
+
+test!(test_probe_router, {
+    let ctx = Context::new();
+    let router = ctx.socket(ROUTER).unwrap();
+    router.bind("inproc://probe_router").unwrap();
+
+    let dealer = ctx.socket(DEALER).unwrap();
+    dealer.set_identity(b"worker").unwrap();
+    dealer.set_probe_router(true).unwrap();
+    assert_eq!(dealer.is_probe_router(), Err(Error::EINVAL));
+    dealer.connect("inproc://probe_router").unwrap();
+
+    let probe = router.recv_multipart(0).unwrap();
+    assert_eq!(probe, vec![b"worker".to_vec(), Vec::new()]);
+});
+
 test!(test_set_req_relaxed, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -270,6 +656,28 @@ test!(test_set_req_correlate, {
     assert!(sock.set_req_correlate(false).is_ok());
 });
 
+test!(test_req_relaxed_resend, {
+    let ctx = Context::new();
+    let rep = ctx.socket(REP).unwrap();
+    rep.bind("inproc://req_relaxed_resend").unwrap();
+
+    let req = ctx.socket(REQ).unwrap();
+    req.connect("inproc://req_relaxed_resend").unwrap();
+    req.send("first", 0).unwrap();
+    assert_eq!(req.send("second", 0), Err(Error::EFSM));
+
+    req.set_req_relaxed(true).unwrap();
+    req.set_req_correlate(true).unwrap();
+    req.send("second", 0).unwrap();
+
+    // The reply to the abandoned request is discarded.
+    assert_eq!(rep.recv_bytes(0).unwrap(), b"first");
+    rep.send("late", 0).unwrap();
+    assert_eq!(rep.recv_bytes(0).unwrap(), b"second");
+    rep.send("reply", 0).unwrap();
+    assert_eq!(req.recv_bytes(0).unwrap(), b"reply");
+});
+
 test!(test_getset_rate, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -336,10 +744,19 @@ test!(test_getset_backlog, {
 test!(test_getset_multicast_hops, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
+    assert_eq!(sock.get_multicast_hops().unwrap(), 1);
     sock.set_multicast_hops(20).unwrap();
     assert_eq!(sock.get_multicast_hops().unwrap(), 20);
 });
 
+test!(test_getset_multicast_maxtpdu, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUB).unwrap();
+    assert_eq!(sock.get_multicast_maxtpdu().unwrap(), 1500);
+    sock.set_multicast_maxtpdu(1400).unwrap();
+    assert_eq!(sock.get_multicast_maxtpdu().unwrap(), 1400);
+});
+
 test!(test_getset_rcvtimeo, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -354,6 +771,19 @@ test!(test_getset_sndtimeo, {
     assert_eq!(sock.get_sndtimeo().unwrap(), 5000);
 });
 
+test!(test_rcvtimeo_sndtimeo_expire, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+    assert_eq!(sock.get_sndtimeo().unwrap(), -1);
+    sock.set_sndtimeo(10).unwrap();
+    assert_eq!(sock.send("hello", 0), Err(zmq::Error::EAGAIN));
+
+    let sock = ctx.socket(PULL).unwrap();
+    assert_eq!(sock.get_rcvtimeo().unwrap(), -1);
+    sock.set_rcvtimeo(10).unwrap();
+    assert_eq!(sock.recv_bytes(0), Err(zmq::Error::EAGAIN));
+});
+
 test!(test_getset_ipv6, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -365,6 +795,21 @@ test!(test_getset_ipv6, {
     assert!(!sock.is_ipv6().unwrap());
 });
 
+test!(test_ipv6_loopback, {
+    let ctx = Context::new();
+    let server = ctx.socket(PULL).unwrap();
+    assert_eq!(server.bind("tcp://[::1]:*"), Err(zmq::Error::ENODEV));
+    server.set_ipv6(true).unwrap();
+    server.bind("tcp://[::1]:*").unwrap();
+    let endpoint = server.get_last_endpoint().unwrap().unwrap();
+
+    let client = ctx.socket(PUSH).unwrap();
+    client.set_ipv6(true).unwrap();
+    client.connect(&endpoint).unwrap();
+    client.send("hello", 0).unwrap();
+    assert_eq!(server.recv_bytes(0).unwrap(), b"hello");
+});
+
 test!(test_getset_socks_proxy, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -438,6 +883,25 @@ test!(test_getset_immediate, {
     assert!(!sock.is_immediate().unwrap());
 });
 
+test!(test_immediate_skips_pending_connections, {
+    let ctx = Context::new();
+    // Nothing listens on this privileged port, whereas a port released by
+    // another socket may be reused by tests running in parallel.
+    let endpoint = "tcp://127.0.0.1:1";
+
+    let sock = ctx.socket(PUSH).unwrap();
+    sock.set_sndtimeo(10).unwrap();
+    sock.set_linger(0).unwrap();
+    sock.connect(endpoint).unwrap();
+    sock.send("queued", 0).unwrap();
+
+    let sock = ctx.socket(PUSH).unwrap();
+    sock.set_sndtimeo(10).unwrap();
+    sock.set_immediate(true).unwrap();
+    sock.connect(endpoint).unwrap();
+    assert_eq!(sock.send("dropped", 0), Err(zmq::Error::EAGAIN));
+});
+
 test!(test_getset_plain_server, {
     let ctx = Context::new();
     let sock = ctx.socket(REQ).unwrap();
@@ -473,6 +937,35 @@ test!(test_getset_plain_password, {
     assert!(sock.get_mechanism().unwrap() == Mechanism::ZMQ_NULL);
 });
 
+test!(test_invert_matching, {
+    let ctx = Context::new();
+    let publisher = ctx.socket(PUB).unwrap();
+    publisher.set_invert_matching(true).unwrap();
+    assert!(publisher.is_invert_matching().unwrap());
+    publisher.bind("inproc://invert_matching").unwrap();
+
+    let subscriber = ctx.socket(SUB).unwrap();
+    subscriber.set_invert_matching(true).unwrap();
+    subscriber.set_subscribe(b"noisy").unwrap();
+    subscriber.connect("inproc://invert_matching").unwrap();
+
+    // Wait for the subscription to reach the publisher.
+    let mut received = None;
+    for _ in 0..100 {
+        publisher.send("noisy update", 0).unwrap();
+        publisher.send("quiet update", 0).unwrap();
+        match subscriber.recv_bytes(DONTWAIT) {
+            Ok(msg) => {
+                received = Some(msg);
+                break;
+            }
+            Err(Error::EAGAIN) => std::thread::sleep(Duration::from_millis(10)),
+            Err(e) => panic!("{}", e),
+        }
+    }
+    assert_eq!(received.unwrap(), b"quiet update");
+});
+
 test!(test_zmq_set_xpub_verbose, {
     let ctx = Context::new();
     let xpub = ctx.socket(XPUB).unwrap();
@@ -491,6 +984,43 @@ test!(test_zmq_set_xpub_verbose, {
     }
 });
 
+test!(test_zmq_set_xpub_verboser, {
+    let ctx = Context::new();
+    let xpub = ctx.socket(XPUB).unwrap();
+    xpub.bind("inproc://set_xpub_verboser").unwrap();
+    xpub.set_xpub_verboser(true).unwrap();
+
+    let subs = [ctx.socket(SUB).unwrap(), ctx.socket(SUB).unwrap()];
+    for sub in &subs {
+        sub.connect("inproc://set_xpub_verboser").unwrap();
+        sub.set_subscribe(b"topic").unwrap();
+        assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x01topic");
+    }
+    for sub in &subs {
+        sub.set_unsubscribe(b"topic").unwrap();
+        assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x00topic");
+    }
+});
+
+test!(test_zmq_set_xpub_manual, {
+    let ctx = Context::new();
+    let xpub = ctx.socket(XPUB).unwrap();
+    xpub.bind("inproc://set_xpub_manual").unwrap();
+    xpub.set_xpub_manual(true).unwrap();
+
+    let sub = ctx.socket(SUB).unwrap();
+    sub.connect("inproc://set_xpub_manual").unwrap();
+    sub.set_subscribe(b"tenant-a/").unwrap();
+
+    // Narrow the requested subscription down.
+    assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x01tenant-a/");
+    xpub.set_subscribe(b"tenant-a/public").unwrap();
+
+    xpub.send("tenant-a/private", 0).unwrap();
+    xpub.send("tenant-a/public", 0).unwrap();
+    assert_eq!(sub.recv_bytes(0).unwrap(), b"tenant-a/public");
+});
+
 test!(test_zmq_xpub_welcome_msg, {
     let ctx = Context::new();
     let xpub = ctx.socket(XPUB).unwrap();
@@ -577,6 +1107,72 @@ test!(test_getset_handshake_ivl, {
     assert_eq!(sock.get_handshake_ivl().unwrap(), 50000);
 });
 
+test!(test_handshake_ivl_closes_silent_peer, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PULL).unwrap();
+    sock.set_handshake_ivl(100).unwrap();
+    sock.bind("tcp://127.0.0.1:*").unwrap();
+    let ep = sock.get_last_endpoint().unwrap().unwrap();
+
+    // A peer that never sends a greeting is disconnected.
+    let mut stream = TcpStream::connect(ep.trim_start_matches("tcp://")).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut greeting = Vec::new();
+    stream.read_to_end(&mut greeting).unwrap();
+});
+
+test!(test_getset_bindtodevice, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PUSH).unwrap();
+    assert_eq!(sock.get_bindtodevice().unwrap(), Ok(String::new()));
+    sock.set_bindtodevice("lo").unwrap();
+    assert_eq!(sock.get_bindtodevice().unwrap(), Ok("lo".to_owned()));
+});
+
+test!(test_getset_use_fd, {
+    let ctx = Context::new();
+    let sock = ctx.socket(PULL).unwrap();
+    assert_eq!(sock.get_use_fd().unwrap(), -1);
+    sock.set_use_fd(3).unwrap();
+    assert_eq!(sock.get_use_fd().unwrap(), 3);
+});
+
+#[cfg(unix)]
+test!(test_use_fd_bind, {
+    use std::net::TcpListener;
+    use std::os::unix::io::IntoRawFd;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("tcp://{}", listener.local_addr().unwrap());
+
+    let ctx = Context::new();
+    let server = ctx.socket(PULL).unwrap();
+    server.set_use_fd(listener.into_raw_fd()).unwrap();
+    server.bind(&endpoint).unwrap();
+
+    let client = ctx.socket(PUSH).unwrap();
+    client.connect(&endpoint).unwrap();
+    client.send("hello", 0).unwrap();
+    assert_eq!(server.recv_bytes(0).unwrap(), b"hello");
+});
+
+test!(test_getset_heartbeat, {
+    let ctx = Context::new();
+    let sock = ctx.socket(DEALER).unwrap();
+    assert_eq!(sock.get_heartbeat_ivl().unwrap(), 0);
+    assert_eq!(sock.get_heartbeat_timeout().unwrap(), -1);
+    assert_eq!(sock.get_heartbeat_ttl().unwrap(), 0);
+
+    sock.set_heartbeat_ivl(1000).unwrap();
+    assert_eq!(sock.get_heartbeat_ivl().unwrap(), 1000);
+    sock.set_heartbeat_timeout(3000).unwrap();
+    assert_eq!(sock.get_heartbeat_timeout().unwrap(), 3000);
+    sock.set_heartbeat_ttl(2050).unwrap();
+    assert_eq!(sock.get_heartbeat_ttl().unwrap(), 2000);
+});
+
 test!(test_getset_connect_timeout, {
     if version_ge_4_2() {
         let ctx = Context::new();
@@ -0,0 +1,98 @@
+#![cfg(feature = "topology")]
+
+#[macro_use]
+mod common;
+
+use zmq::topology::{Topology, TopologyError};
+use zmq::Context;
+
+test!(test_json_topology, {
+    let topology = Topology::from_json(
+        r#"{
+            "sockets": {
+                "publisher": {
+                    "type": "PUB",
+                    "bind": ["inproc://topology-json"],
+                    "options": { "linger": 0, "sndhwm": 50 }
+                },
+                "subscriber": {
+                    "type": "sub",
+                    "connect": ["inproc://topology-json"],
+                    "subscribe": ["a"],
+                    "options": { "rcvtimeo": 100 }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let ctx = Context::new();
+    let sockets = topology.instantiate(&ctx).unwrap();
+    let publisher = &sockets["publisher"];
+    let subscriber = &sockets["subscriber"];
+    assert_eq!(publisher.get_socket_type().unwrap(), zmq::PUB);
+    assert_eq!(publisher.get_sndhwm().unwrap(), 50);
+    assert_eq!(subscriber.get_rcvtimeo().unwrap(), 100);
+
+    // Subscriptions propagate asynchronously.
+    loop {
+        publisher.send("b-ignored", 0).unwrap();
+        publisher.send("a-message", 0).unwrap();
+        match subscriber.recv_bytes(0) {
+            Ok(msg) => {
+                assert_eq!(msg, b"a-message");
+                break;
+            }
+            Err(e) => assert_eq!(e, zmq::Error::EAGAIN),
+        }
+    }
+});
+
+test!(test_toml_topology, {
+    let topology = Topology::from_toml(
+        r#"
+        [sockets.server]
+        type = "ROUTER"
+        bind = ["inproc://topology-toml"]
+
+        [sockets.client]
+        type = "DEALER"
+        connect = ["inproc://topology-toml"]
+        options = { identity = "client-1" }
+        security = { mechanism = "plain", username = "admin", password = "secret" }
+        "#,
+    )
+    .unwrap();
+
+    let ctx = Context::new();
+    let sockets = topology.instantiate(&ctx).unwrap();
+    let client = &sockets["client"];
+    assert_eq!(client.get_identity().unwrap(), b"client-1");
+    assert_eq!(client.get_mechanism().unwrap(), zmq::Mechanism::ZMQ_PLAIN);
+    assert_eq!(client.get_plain_username().unwrap().unwrap(), "admin");
+});
+
+test!(test_invalid_topology, {
+    match Topology::from_json(r#"{ "sockets": { "s": { "type": "NOPE" } } }"#) {
+        Err(TopologyError::Json(_)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let topology = Topology::from_toml(
+        r#"
+        [sockets.bad]
+        type = "PUSH"
+        bind = ["nonsense://endpoint"]
+        "#,
+    )
+    .unwrap();
+    let ctx = Context::new();
+    match topology.instantiate(&ctx) {
+        Err(TopologyError::Socket { name, error }) => {
+            assert_eq!(name, "bad");
+            assert_eq!(error, zmq::Error::EPROTONOSUPPORT);
+        }
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("instantiation should have failed"),
+    }
+});
@@ -0,0 +1,79 @@
+#[macro_use]
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use zmq::{send_with_ttl, Context, ExpiryFilter, TtlStamp};
+
+test!(test_ttl_stamp_roundtrip, {
+    let stamp = TtlStamp::now(Duration::from_millis(1500));
+    let decoded = TtlStamp::decode(&stamp.encode()).unwrap();
+    assert_eq!(decoded.ttl, stamp.ttl);
+    let skew = stamp
+        .sent_at
+        .duration_since(decoded.sent_at)
+        .unwrap_or_else(|e| e.duration());
+    assert!(skew < Duration::from_micros(1));
+    assert!(!decoded.is_expired_at(stamp.sent_at + Duration::from_secs(1)));
+    assert!(decoded.is_expired_at(stamp.sent_at + Duration::from_secs(2)));
+    assert_eq!(TtlStamp::decode(b"short"), None);
+});
+
+test!(test_ttl_stamp_overflow, {
+    // A TTL too large to add to the send time never expires.
+    let stamp = TtlStamp::now(Duration::MAX);
+    assert_eq!(stamp.expires_at(), None);
+    assert!(!stamp.is_expired_at(SystemTime::now()));
+    let decoded = TtlStamp::decode(&stamp.encode()).unwrap();
+    assert!(!decoded.is_expired_at(SystemTime::now()));
+
+    // A send time beyond the range of `SystemTime` is rejected, rather than
+    // panicking; the range depends on the platform.
+    if let Some(stamp) = TtlStamp::decode(&[0xff; 16]) {
+        assert!(!stamp.is_expired_at(SystemTime::now()));
+    }
+});
+
+test!(test_expiry_filter, {
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.bind("inproc://ttl-filter").unwrap();
+    push.connect("inproc://ttl-filter").unwrap();
+
+    send_with_ttl(&push, vec!["old", "command"], Duration::from_secs(0), 0).unwrap();
+    send_with_ttl(&push, vec!["older"], Duration::from_secs(0), 0).unwrap();
+    std::thread::sleep(Duration::from_millis(5));
+    send_with_ttl(&push, vec!["new", "command"], Duration::from_secs(60), 0).unwrap();
+
+    let mut filter = ExpiryFilter::new();
+    assert_eq!(
+        filter.recv_multipart(&pull, 0).unwrap(),
+        vec![b"new".to_vec(), b"command".to_vec()]
+    );
+    assert_eq!(filter.expired(), 2);
+    assert_eq!(
+        filter.recv_multipart(&pull, zmq::DONTWAIT),
+        Err(zmq::Error::EAGAIN)
+    );
+
+    push.send("unstamped", 0).unwrap();
+    assert_eq!(filter.recv_multipart(&pull, 0), Err(zmq::Error::EPROTO));
+});
+
+test!(test_send_with_ttl_empty, {
+    let ctx = Context::new();
+    let push = ctx.socket(zmq::PUSH).unwrap();
+    let pull = ctx.socket(zmq::PULL).unwrap();
+    pull.bind("inproc://ttl-empty").unwrap();
+    push.connect("inproc://ttl-empty").unwrap();
+
+    let empty: Vec<&str> = Vec::new();
+    assert_eq!(
+        send_with_ttl(&push, empty, Duration::from_secs(60), 0),
+        Err(zmq::Error::EINVAL)
+    );
+    // Nothing was left pending on the socket.
+    push.send("next", 0).unwrap();
+    assert_eq!(pull.recv_multipart(0).unwrap(), vec![b"next".to_vec()]);
+});
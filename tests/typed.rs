@@ -0,0 +1,175 @@
+#[macro_use]
+mod common;
+
+use std::convert::TryFrom;
+use zmq::{
+    Context, DealerSocket, PubSocket, RouterSocket, Socket, SubSocket, SubscriptionChange,
+    SubscriptionTracker, XPubSocket, XSubSocket,
+};
+
+test!(test_pub_sub, {
+    let ctx = Context::new();
+    let publisher = PubSocket::new(&ctx).unwrap();
+    let subscriber = SubSocket::new(&ctx).unwrap();
+
+    publisher.bind("inproc://typed-pub-sub").unwrap();
+    subscriber.subscribe(b"topic").unwrap();
+    subscriber.connect("inproc://typed-pub-sub").unwrap();
+
+    // The subscription is propagated asynchronously, so keep publishing until
+    // the first message arrives.
+    loop {
+        publisher.send("other", 0).unwrap();
+        publisher.send("topic data", 0).unwrap();
+        if subscriber.poll(zmq::POLLIN, 10).unwrap() > 0 {
+            break;
+        }
+    }
+    assert_eq!(subscriber.recv_bytes(0).unwrap(), b"topic data");
+});
+
+test!(test_router_dealer, {
+    let ctx = Context::new();
+    let router = RouterSocket::new(&ctx).unwrap();
+    let dealer = DealerSocket::new(&ctx).unwrap();
+
+    router.bind("inproc://typed-router-dealer").unwrap();
+    let socket: Socket = dealer.into_socket();
+    socket.set_identity(b"dealer").unwrap();
+    let dealer = DealerSocket::try_from(socket).ok().unwrap();
+    dealer.connect("inproc://typed-router-dealer").unwrap();
+
+    dealer.send_multipart(["hello", "world"], 0).unwrap();
+    let (identity, parts) = router.recv_from(0).unwrap();
+    assert_eq!(identity, b"dealer");
+    assert_eq!(parts, vec![b"hello", b"world"]);
+
+    router.send_to(&identity, "reply", 0).unwrap();
+    assert_eq!(dealer.recv_bytes(0).unwrap(), b"reply");
+});
+
+test!(test_router_connect_routing_id, {
+    let ctx = Context::new();
+    let server = RouterSocket::new(&ctx).unwrap();
+    server.bind("inproc://typed-connect-routing-id").unwrap();
+
+    // The router names the connection itself, so it can send first.
+    let router = RouterSocket::new(&ctx).unwrap();
+    router.set_router_mandatory(true).unwrap();
+    router.set_connect_routing_id(b"backend").unwrap();
+    router.connect("inproc://typed-connect-routing-id").unwrap();
+    router.send_to(b"backend", "hello", 0).unwrap();
+
+    let (identity, parts) = server.recv_from(0).unwrap();
+    assert!(!identity.is_empty());
+    assert_eq!(parts, vec![b"hello"]);
+});
+
+test!(test_router_begin_send_to, {
+    let ctx = Context::new();
+    let router = RouterSocket::new(&ctx).unwrap();
+    router.set_router_mandatory(true).unwrap();
+    router.bind("inproc://typed-begin-send-to").unwrap();
+    assert!(router.begin_send_to(b"nobody").unwrap().is_none());
+
+    let dealer = ctx.socket(zmq::DEALER).unwrap();
+    dealer.set_identity(b"somebody").unwrap();
+    dealer.connect("inproc://typed-begin-send-to").unwrap();
+    dealer.send("ready", 0).unwrap();
+    let (identity, _) = router.recv_from(0).unwrap();
+
+    let guard = router.begin_send_to(&identity).unwrap().unwrap();
+    guard.send("routed", 0).unwrap();
+    assert_eq!(dealer.recv_bytes(0).unwrap(), b"routed");
+
+    // A dropped guard completes its message, rather than leaving the socket
+    // in the middle of it.
+    drop(router.begin_send_to(&identity).unwrap());
+    router
+        .begin_send_to(&identity)
+        .unwrap()
+        .unwrap()
+        .send_multipart(["a", "b"], 0)
+        .unwrap();
+    assert_eq!(dealer.recv_multipart(0).unwrap(), vec![b"".to_vec()]);
+    assert_eq!(
+        dealer.recv_multipart(0).unwrap(),
+        vec![b"a".to_vec(), b"b".to_vec()]
+    );
+});
+
+test!(test_xpub_xsub, {
+    let ctx = Context::new();
+    let xpub = XPubSocket::new(&ctx).unwrap();
+    let xsub = XSubSocket::new(&ctx).unwrap();
+
+    xpub.bind("inproc://typed-xpub-xsub").unwrap();
+    xsub.connect("inproc://typed-xpub-xsub").unwrap();
+    xsub.subscribe(b"news").unwrap();
+    assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x01news");
+    xsub.unsubscribe(b"news").unwrap();
+    assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x00news");
+});
+
+test!(test_xpub_manual_subscribe, {
+    let ctx = Context::new();
+    let xpub = XPubSocket::new(&ctx).unwrap();
+    xpub.set_xpub_manual(true).unwrap();
+    xpub.bind("inproc://typed-xpub-manual").unwrap();
+    let sub = SubSocket::new(&ctx).unwrap();
+    sub.set_rcvtimeo(100).unwrap();
+    sub.connect("inproc://typed-xpub-manual").unwrap();
+
+    sub.subscribe(b"a.").unwrap();
+    assert_eq!(xpub.recv_bytes(0).unwrap(), b"\x01a.");
+    // Narrow the requested subscription down.
+    xpub.subscribe(b"a.1").unwrap();
+    xpub.send("a.2", 0).unwrap();
+    xpub.send("a.1", 0).unwrap();
+    assert_eq!(sub.recv_bytes(0).unwrap(), b"a.1");
+
+    // The SUB socket still accepts "a.", so this is filtered by the XPUB.
+    xpub.unsubscribe(b"a.1").unwrap();
+    xpub.send("a.1", 0).unwrap();
+    assert_eq!(sub.recv_bytes(0), Err(zmq::Error::EAGAIN));
+});
+
+test!(test_xpub_subscription_tracking, {
+    let ctx = Context::new();
+    let xpub = XPubSocket::new(&ctx).unwrap();
+    xpub.bind("inproc://typed-xpub-tracking").unwrap();
+    let sub1 = SubSocket::new(&ctx).unwrap();
+    let sub2 = SubSocket::new(&ctx).unwrap();
+    sub1.connect("inproc://typed-xpub-tracking").unwrap();
+    sub2.connect("inproc://typed-xpub-tracking").unwrap();
+
+    let mut tracker = SubscriptionTracker::new();
+    sub1.subscribe(b"a.").unwrap();
+    sub2.subscribe(b"a.").unwrap();
+    sub2.subscribe(b"b.").unwrap();
+    // Duplicate subscriptions are filtered by libzmq.
+    for _ in 0..2 {
+        let change = xpub.recv_subscription(0).unwrap().unwrap();
+        assert!(tracker.apply(&change));
+    }
+    assert_eq!(
+        tracker.subscriptions().collect::<Vec<_>>(),
+        vec![&b"a."[..], &b"b."[..]]
+    );
+    assert!(tracker.has_subscribers(b"a.1"));
+    assert!(!tracker.has_subscribers(b"c.1"));
+
+    drop(sub2);
+    let change = xpub.recv_subscription(0).unwrap().unwrap();
+    assert_eq!(change, SubscriptionChange::Unsubscribe(b"b.".to_vec()));
+    tracker.apply(&change);
+    assert_eq!(tracker.len(), 1);
+    assert!(!tracker.has_subscribers(b"b.1"));
+});
+
+test!(test_try_from_wrong_type, {
+    let ctx = Context::new();
+    let socket = ctx.socket(zmq::PUSH).unwrap();
+    let socket = PubSocket::try_from(socket).err().unwrap();
+    assert_eq!(socket.get_socket_type().unwrap(), zmq::PUSH);
+});
@@ -1,3 +1,5 @@
+use std::env;
+
 pub fn configure() {
     println!("cargo:rerun-if-changed=build/main.rs");
     println!("cargo:rerun-if-env-changed=PROFILE");
@@ -6,9 +8,18 @@ pub fn configure() {
     // relying on `tweetnacl`. However since this `tweetnacl` [has never been
     // audited nor is ready for production](https://github.com/zeromq/libzmq/issues/3006),
     // we link against `libsodium` to enable `ZMQ_CURVE`.
+    let draft = env::var_os("CARGO_FEATURE_DRAFT").is_some();
     zeromq_src::Build::new()
         .with_libsodium(None)
+        .enable_draft(draft)
         .build();
+
+    // Tell dependent build scripts, via `DEP_ZMQ_DRAFT`, whether the draft
+    // API is available. When the build script is overridden to link another
+    // libzmq, the override has to provide this as well.
+    if draft {
+        println!("cargo:draft=1");
+    }
 }
 
 fn main() {
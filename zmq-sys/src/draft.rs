@@ -0,0 +1,122 @@
+//! Declarations of the draft API of libzmq, see the `ZMQ_BUILD_DRAFT_API`
+//! section of `zmq.h`.
+
+use crate::ffi::zmq_msg_t;
+use crate::RawFd;
+
+pub const ZMQ_SERVER: u32 = 12;
+pub const ZMQ_CLIENT: u32 = 13;
+pub const ZMQ_RADIO: u32 = 14;
+pub const ZMQ_DISH: u32 = 15;
+pub const ZMQ_GATHER: u32 = 16;
+pub const ZMQ_SCATTER: u32 = 17;
+
+pub const ZMQ_MULTICAST_LOOP: u32 = 96;
+pub const ZMQ_XPUB_MANUAL_LAST_VALUE: u32 = 98;
+
+extern "C" {
+    pub fn zmq_msg_set_routing_id(msg: *mut zmq_msg_t, routing_id: u32) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_msg_routing_id(msg: *mut zmq_msg_t) -> u32;
+}
+extern "C" {
+    pub fn zmq_msg_set_group(
+        msg: *mut zmq_msg_t,
+        group: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_msg_group(msg: *mut zmq_msg_t) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn zmq_join(
+        s: *mut ::std::os::raw::c_void,
+        group: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_leave(
+        s: *mut ::std::os::raw::c_void,
+        group: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int;
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct zmq_poller_event_t {
+    pub socket: *mut ::std::os::raw::c_void,
+    pub fd: RawFd,
+    pub user_data: *mut ::std::os::raw::c_void,
+    pub events: ::std::os::raw::c_short,
+}
+extern "C" {
+    pub fn zmq_poller_new() -> *mut ::std::os::raw::c_void;
+}
+extern "C" {
+    pub fn zmq_poller_destroy(poller_p: *mut *mut ::std::os::raw::c_void) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_add(
+        poller: *mut ::std::os::raw::c_void,
+        socket: *mut ::std::os::raw::c_void,
+        user_data: *mut ::std::os::raw::c_void,
+        events: ::std::os::raw::c_short,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_modify(
+        poller: *mut ::std::os::raw::c_void,
+        socket: *mut ::std::os::raw::c_void,
+        events: ::std::os::raw::c_short,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_remove(
+        poller: *mut ::std::os::raw::c_void,
+        socket: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_add_fd(
+        poller: *mut ::std::os::raw::c_void,
+        fd: RawFd,
+        user_data: *mut ::std::os::raw::c_void,
+        events: ::std::os::raw::c_short,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_modify_fd(
+        poller: *mut ::std::os::raw::c_void,
+        fd: RawFd,
+        events: ::std::os::raw::c_short,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_remove_fd(
+        poller: *mut ::std::os::raw::c_void,
+        fd: RawFd,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_wait(
+        poller: *mut ::std::os::raw::c_void,
+        event: *mut zmq_poller_event_t,
+        timeout: ::std::os::raw::c_long,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn zmq_poller_wait_all(
+        poller: *mut ::std::os::raw::c_void,
+        events: *mut zmq_poller_event_t,
+        n_events: ::std::os::raw::c_int,
+        timeout: ::std::os::raw::c_long,
+    ) -> ::std::os::raw::c_int;
+}
+
+// The timer API is declared along with the stable API, but only exported
+// here, since older libzmq releases only provide it with the draft API.
+pub use crate::ffi::{
+    zmq_timer_fn, zmq_timers_add, zmq_timers_cancel, zmq_timers_destroy, zmq_timers_execute,
+    zmq_timers_new, zmq_timers_reset, zmq_timers_set_interval, zmq_timers_timeout,
+};
@@ -233,9 +233,26 @@ pub use crate::ffi::{
     zmq_z85_encode,
     zmq_z85_decode,
     zmq_curve_keypair,
+    // Added in 4.2.0.
+    zmq_atomic_counter_new,
+    zmq_atomic_counter_set,
+    zmq_atomic_counter_inc,
+    zmq_atomic_counter_dec,
+    zmq_atomic_counter_value,
+    zmq_atomic_counter_destroy,
+    // Added in 4.2.1.
+    zmq_curve_public,
 };
 
 #[allow(non_camel_case_types)]
 #[allow(dead_code)]
 #[allow(clippy::unreadable_literal)]
 mod ffi;
+
+// The draft API is unstable, and only available if libzmq was built with
+// it, so it is only declared if asked for.
+#[cfg(feature = "draft")]
+#[allow(non_camel_case_types)]
+mod draft;
+#[cfg(feature = "draft")]
+pub use crate::draft::*;